@@ -1,41 +1,150 @@
 //! Simple CLI to parse the raw data and store it into the database.
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use refinery::embed_migrations;
-use rusqlite::Connection;
+use rusqlite::{Connection, Transaction};
 
 use nott_a_database_core::{
-    database::{insert_student_info_transaction, insert_student_result_transaction},
-    AcademicYear, StudentInfo, StudentResult,
+    database::{
+        insert_component_marks_transaction, insert_student_info_transaction,
+        insert_student_result_transaction, repair_intake_years_transaction, rollback_import,
+    },
+    errors::{CreditConflict, DuplicateImport, FieldChange, IntakeConflict, RowDiagnostic},
+    layout::ReportLayouts,
+    palette::StatusPalette,
+    AcademicYear, ComponentMark, ConflictPolicy, CreditPolicy, Error, ImportBatch, ParseOptions,
+    ReportKind, ResitSession, StudentInfo, StudentResult,
 };
 
 embed_migrations!("../nott-a-database-core/migrations");
 
 /// Simple CLI to parse the raw data and store it into the database.
 #[derive(Debug, Parser)]
+#[command(subcommand_negates_reqs = true)]
 struct Arg {
+    /// Manages the imports in the database instead of importing raw data.
+    #[command(subcommand)]
+    command: Option<Command>,
     /// The acdemic year of the reports.
-    #[clap(value_parser = AcademicYear::from_str)]
-    academic_year: AcademicYear,
+    #[clap(required = true, value_parser = AcademicYear::from_str)]
+    academic_year: Option<AcademicYear>,
     /// The database file to save to.
-    datbase: PathBuf,
+    #[clap(required = true)]
+    datbase: Option<PathBuf>,
     /// List of raw data file to parse.
     #[command(flatten)]
     data: RawData,
 
+    /// Skips invalid rows and reports all of them instead of stopping at the
+    /// first invalid row.
+    #[arg(short, long)]
+    lenient: bool,
+    /// The TOML file of the fill colours of each module status, the default
+    /// colours are used if it is not given.
+    #[arg(long)]
+    palette: Option<PathBuf>,
+    /// The TOML file of the fill colours of each module status in the resit
+    /// reports, the default resit colours are used if it is not given.
+    #[arg(long)]
+    resit_palette: Option<PathBuf>,
+    /// Specify (can specify multiple) TOML file of a custom report layout,
+    /// replacing the built-in layout of the same type of report.
+    #[arg(long)]
+    layout: Vec<PathBuf>,
+    /// How modules with different credits from the credits in the database
+    /// are resolved: "keep" the stored credits, "overwrite" them or "fail".
+    #[arg(long, default_value = "keep", value_parser = CreditPolicy::from_str)]
+    credit_policy: CreditPolicy,
+    /// How the stored details of students and component marks are updated:
+    /// "overwrite" them, "fill-missing" fields only, "keep-existing" details
+    /// or "fail" if a stored field would change.
+    #[arg(long, default_value = "overwrite", value_parser = ConflictPolicy::from_str)]
+    conflict_policy: ConflictPolicy,
+    /// Skips the files with the same content as a previous import instead of
+    /// importing them again with a warning.
+    #[arg(long)]
+    skip_duplicates: bool,
     /// Prints nothing to the standard output.
-    #[arg(short, long, group = "print")]
+    #[arg(short, long, group = "print", global = true)]
     quiet: bool,
     /// Prints debug outputs to the standard output.
     #[arg(short, long, group = "print")]
     verbose: bool,
+    /// Prints errors and invalid rows as JSON objects with a stable error
+    /// `code`, `category`, `message` and the invalid `cell` to the standard
+    /// error output.
+    #[arg(long, global = true)]
+    json: bool,
+}
+
+impl Arg {
+    /// Gets the academic year of the reports, which is required without a
+    /// subcommand.
+    fn academic_year(&self) -> &AcademicYear {
+        self.academic_year
+            .as_ref()
+            .expect("The academic year should be given without a subcommand")
+    }
+
+    /// Gets the database file, which is required without a subcommand.
+    fn database(&self) -> &Path {
+        self.datbase
+            .as_deref()
+            .expect("The database should be given without a subcommand")
+    }
+}
+
+/// Subcommands to manage the imports in the database.
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Lists the imports in the database.
+    Imports {
+        /// The database file.
+        database: PathBuf,
+    },
+    /// Rolls back an import, restoring the rows updated by the import and
+    /// deleting the rows inserted by the import.
+    Rollback {
+        /// The database file.
+        database: PathBuf,
+        /// The ID of the import, see the imports subcommand.
+        batch: i64,
+    },
+    /// Recomputes the intake years of the students from the intake in the
+    /// award report (0B) or the year of study of their results.
+    RepairIntake {
+        /// The database file.
+        database: PathBuf,
+        /// Lists the intake years which would be updated without updating
+        /// them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+impl Command {
+    /// Gets the database file of the subcommand.
+    fn database(&self) -> &Path {
+        match self {
+            Self::Imports { database }
+            | Self::Rollback { database, .. }
+            | Self::RepairIntake { database, .. } => database,
+        }
+    }
 }
 
 /// CLI arguments to supply raw data.
 #[derive(Debug, Parser)]
 #[group(required = true)]
 struct RawData {
+    /// List of raw data file to parse, the type of report is detected
+    /// automatically.
+    files: Vec<PathBuf>,
     /// Specify (can specify multiple) result report (0A) raw data to parse.
     #[arg(long)]
     result: Vec<PathBuf>,
@@ -48,26 +157,242 @@ struct RawData {
     /// Specify (can specify multiple) August resit report (0D) raw data to parse.
     #[arg(long)]
     resit_aug: Vec<PathBuf>,
+    /// Specify (can specify multiple) component mark sheet to parse, the
+    /// marks of the modules must be in the reports or the database.
+    #[arg(long)]
+    components: Vec<PathBuf>,
 }
 
 fn main() -> Result<(), anyhow::Error> {
-    let args = Arg::parse();
+    let mut args = Arg::parse();
+    if let Some(command) = &args.command {
+        let mut conn = open_database(command.database())?;
+        return match manage_imports(command, &mut conn, args.quiet) {
+            Ok(()) => Ok(()),
+            Err(e) => report_error(e, args.json),
+        };
+    }
+
+    let options = match parse_options(&args) {
+        Ok(options) => options,
+        Err(e) => return report_error(e, args.json),
+    };
+    if let Err(e) = detect_reports(&mut args, &options.layouts) {
+        return report_error(e, args.json);
+    }
+
+    if !args.quiet {
+        println!("Saving data to: {}", args.database().to_string_lossy());
+    }
+    let mut conn = open_database(args.database())?;
+
+    if let Err(e) = import_reports(&args, &options, &mut conn) {
+        return report_error(e, args.json);
+    }
 
     if !args.quiet {
-        println!("Saving data to: {}", &args.datbase.to_string_lossy());
+        println!("Done");
     }
-    let mut conn = Connection::open(args.datbase)?;
+
+    Ok(())
+}
+
+/// Loads the status palettes and the report layouts given in the arguments.
+fn parse_options(args: &Arg) -> Result<ParseOptions, Error> {
+    let palette = match &args.palette {
+        Some(file) => StatusPalette::open(file)?,
+        None => StatusPalette::default(),
+    };
+    let resit_palette = match &args.resit_palette {
+        Some(file) => StatusPalette::open(file)?,
+        None => StatusPalette::resit(),
+    };
+
+    let mut layouts = ReportLayouts::default();
+    for file in &args.layout {
+        let kind = layouts.load(file)?;
+        if args.verbose {
+            println!("Using layout {} for {kind}", file.to_string_lossy());
+        }
+    }
+
+    Ok(ParseOptions {
+        lenient: args.lenient,
+        palette,
+        resit_palette,
+        layouts,
+    })
+}
+
+/// Detects the type of report of all the files given without a type.
+fn detect_reports(args: &mut Arg, layouts: &ReportLayouts) -> Result<(), Error> {
+    // Detect the type of report of each file
+    for file in std::mem::take(&mut args.data.files) {
+        let detection = ReportKind::detect_with_layouts(&file, layouts)?;
+        if !args.quiet {
+            println!(
+                "Detected {} in {} ({} confidence)",
+                detection.kind,
+                file.to_string_lossy(),
+                detection.confidence
+            );
+        }
+        if args.verbose {
+            println!("{}", detection.explanation);
+        }
+
+        match detection.kind {
+            ReportKind::Result => args.data.result.push(file),
+            ReportKind::Award => args.data.award.push(file),
+            ReportKind::Resit(ResitSession::May) => args.data.resit_may.push(file),
+            ReportKind::Resit(ResitSession::August) => args.data.resit_aug.push(file),
+        }
+    }
+
+    Ok(())
+}
+
+/// Opens the database and runs the migrations.
+fn open_database(file: &Path) -> Result<Connection, anyhow::Error> {
+    let mut conn = Connection::open(file)?;
     conn.pragma(None, "foreign_keys", 1, |_| Ok(()))?;
     migrations::runner().run(&mut conn)?;
-    args.academic_year.insert_db_sync(&mut conn)?;
+    Ok(conn)
+}
+
+/// Lists or rolls back the imports in the database, or repairs the intake
+/// years of the students.
+fn manage_imports(command: &Command, conn: &mut Connection, quiet: bool) -> Result<(), Error> {
+    match command {
+        Command::Imports { .. } => {
+            let mut statement = conn.prepare(
+                "SELECT ID, FileName, Kind, Session, AcademicYear, ImportedAt, Rows,
+                 Skipped, RolledBackAt
+                 FROM ImportBatch ORDER BY ID",
+            )?;
+            let batches = statement.query_map([], |row| {
+                let session: Option<String> = row.get(3)?;
+                let rolled_back: Option<String> = row.get(8)?;
+                Ok(format!(
+                    "{}\t{}\t{}{}\t{}\t{}\t{} rows\t{} skipped{}",
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    session
+                        .map(|session| format!(" ({session})"))
+                        .unwrap_or_default(),
+                    row.get::<_, String>(4)?,
+                    row.get::<_, String>(5)?,
+                    row.get::<_, i64>(6)?,
+                    row.get::<_, i64>(7)?,
+                    rolled_back
+                        .map(|time| format!("\trolled back at {time}"))
+                        .unwrap_or_default()
+                ))
+            })?;
+            for batch in batches {
+                println!("{}", batch?);
+            }
+        }
+        Command::Rollback { batch, .. } => {
+            rollback_import(conn, *batch)?;
+            if !quiet {
+                println!("Rolled back import {batch}");
+            }
+        }
+        Command::RepairIntake { dry_run, .. } => {
+            let trans = conn.transaction()?;
+            let repaired = repair_intake_years_transaction(&trans)?;
+            if !dry_run {
+                trans.commit()?;
+            }
+
+            if !quiet {
+                for conflict in &repaired {
+                    println!(
+                        "{}\t{}\t{}",
+                        conflict.id,
+                        conflict.stored.as_deref().unwrap_or("unknown"),
+                        conflict.inferred
+                    );
+                }
+                if *dry_run {
+                    println!("Found {} intake years to repair", repaired.len());
+                } else {
+                    println!("Repaired {} intake years", repaired.len());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Hashes `file` into a new import batch, warning if a file with the same
+/// content was already imported.
+///
+/// Returns `None` if the file was already imported and duplicates are
+/// skipped.
+fn prepare_batch(
+    trans: &Transaction,
+    args: &Arg,
+    file: &Path,
+    kind: ReportKind,
+) -> Result<Option<ImportBatch>, Error> {
+    let name = file
+        .file_name()
+        .map_or_else(|| file.to_string_lossy(), |name| name.to_string_lossy());
+    let batch = ImportBatch::new(name, &fs::read(file)?, kind, args.academic_year().clone());
+
+    let Some(duplicate) = batch.find_duplicate_sync(trans)? else {
+        return Ok(Some(batch));
+    };
+    print_duplicate(file, &duplicate, args.skip_duplicates, args.json);
+    if args.skip_duplicates {
+        Ok(None)
+    } else {
+        Ok(Some(batch))
+    }
+}
+
+/// Records the import `batch` of `file` with the number of rows imported and
+/// skipped.
+///
+/// Returns the ID of the import.
+fn insert_batch(
+    trans: &Transaction,
+    args: &Arg,
+    file: &Path,
+    mut batch: ImportBatch,
+    rows: usize,
+    skipped: usize,
+) -> Result<i64, Error> {
+    batch.rows = rows;
+    batch.skipped = skipped;
+
+    let id = batch.insert_db_transaction_sync(trans)?;
+    if !args.quiet {
+        println!("Recorded import {id} of {}", file.to_string_lossy());
+    }
+    Ok(id)
+}
+
+/// Parses all the raw data and inserts it into the database.
+fn import_reports(args: &Arg, options: &ParseOptions, conn: &mut Connection) -> Result<(), Error> {
+    args.academic_year().insert_db_sync(conn)?;
     let trans = conn.transaction()?;
+    let mut skipped = 0;
 
     // Parse result raw data
-    for file in args.data.result {
+    for file in &args.data.result {
+        let Some(batch) = prepare_batch(&trans, args, file, ReportKind::Result)? else {
+            continue;
+        };
         if !args.quiet {
             println!("Parsing data from {}..", &file.to_string_lossy());
         }
-        let data = StudentResult::from_result(&file)?;
+        let (data, diagnostics) = StudentResult::from_result_with_options(file, options)?;
+        skipped += print_diagnostics(file, &diagnostics, args.json);
 
         if args.verbose {
             println!("{:#?}", data);
@@ -75,15 +400,36 @@ fn main() -> Result<(), anyhow::Error> {
         if !args.quiet {
             println!("Found {} rows in {}", data.len(), file.to_string_lossy());
         }
-        insert_student_result_transaction(&trans, &data, &args.academic_year)?;
+        let batch = insert_batch(&trans, args, file, batch, data.len(), diagnostics.len())?;
+        let report = insert_student_result_transaction(
+            &trans,
+            &data,
+            args.academic_year(),
+            args.credit_policy,
+            args.conflict_policy,
+            batch,
+        )?;
+        print_conflicts(
+            file,
+            &report.credit_conflicts,
+            args.credit_policy,
+            args.json,
+        );
+        print_intake_conflicts(file, &report.intake_conflicts, args.json);
+        print_unknown_intakes(file, &report.unknown_intakes, args.json);
+        print_changes(file, &report.changes, args);
     }
 
     // Parse award report raw data
-    for file in args.data.award {
+    for file in &args.data.award {
+        let Some(batch) = prepare_batch(&trans, args, file, ReportKind::Award)? else {
+            continue;
+        };
         if !args.quiet {
             println!("Parsing data from {}..", &file.to_string_lossy());
         }
-        let data = StudentInfo::from_award(&file)?;
+        let (data, diagnostics) = StudentInfo::from_award_with_options(file, options)?;
+        skipped += print_diagnostics(file, &diagnostics, args.json);
 
         if args.verbose {
             println!("{:#?}", data);
@@ -91,31 +437,71 @@ fn main() -> Result<(), anyhow::Error> {
         if !args.quiet {
             println!("Found {} rows in {}", data.len(), file.to_string_lossy());
         }
-        insert_student_info_transaction(&data, &trans, &args.academic_year, true)?;
+        let batch = insert_batch(&trans, args, file, batch, data.len(), diagnostics.len())?;
+        let report = insert_student_info_transaction(
+            &data,
+            &trans,
+            args.academic_year(),
+            true,
+            args.conflict_policy,
+            batch,
+        )?;
+        print_intake_conflicts(file, &report.intake_conflicts, args.json);
+        print_unknown_intakes(file, &report.unknown_intakes, args.json);
+        print_changes(file, &report.changes, args);
     }
 
-    // Parse May resit raw data
-    for file in args.data.resit_may {
-        if !args.quiet {
-            println!("Parsing data from {}..", &file.to_string_lossy());
-        }
-        let data = StudentResult::from_resit_may(&file)?;
+    // Parse resit raw data
+    let resits = [
+        (ResitSession::May, &args.data.resit_may),
+        (ResitSession::August, &args.data.resit_aug),
+    ];
+    for (session, files) in resits {
+        for file in files {
+            let Some(batch) = prepare_batch(&trans, args, file, session.kind())? else {
+                continue;
+            };
+            if !args.quiet {
+                println!("Parsing data from {}..", &file.to_string_lossy());
+            }
+            let (data, diagnostics) =
+                StudentResult::from_resit_with_options(file, session, options)?;
+            skipped += print_diagnostics(file, &diagnostics, args.json);
 
-        if args.verbose {
-            println!("{:#?}", data);
+            if args.verbose {
+                println!("{:#?}", data);
+            }
+            if !args.quiet {
+                println!("Found {} rows in {}", data.len(), file.to_string_lossy());
+            }
+            let batch = insert_batch(&trans, args, file, batch, data.len(), diagnostics.len())?;
+            let report = insert_student_result_transaction(
+                &trans,
+                &data,
+                args.academic_year(),
+                args.credit_policy,
+                args.conflict_policy,
+                batch,
+            )?;
+            print_conflicts(
+                file,
+                &report.credit_conflicts,
+                args.credit_policy,
+                args.json,
+            );
+            print_intake_conflicts(file, &report.intake_conflicts, args.json);
+            print_unknown_intakes(file, &report.unknown_intakes, args.json);
+            print_changes(file, &report.changes, args);
         }
-        if !args.quiet {
-            println!("Found {} rows in {}", data.len(), file.to_string_lossy());
-        }
-        insert_student_result_transaction(&trans, &data, &args.academic_year)?;
     }
 
-    // Parse August resit raw data
-    for file in args.data.resit_aug {
+    // Parse component mark sheets
+    for file in &args.data.components {
         if !args.quiet {
             println!("Parsing data from {}..", &file.to_string_lossy());
         }
-        let data = StudentResult::from_resit_aug(&file)?;
+        let (data, diagnostics) = ComponentMark::from_components_with_options(file, options)?;
+        skipped += print_diagnostics(file, &diagnostics, args.json);
 
         if args.verbose {
             println!("{:#?}", data);
@@ -123,14 +509,190 @@ fn main() -> Result<(), anyhow::Error> {
         if !args.quiet {
             println!("Found {} rows in {}", data.len(), file.to_string_lossy());
         }
-        insert_student_result_transaction(&trans, &data, &args.academic_year)?;
+        let report = insert_component_marks_transaction(
+            &trans,
+            &data,
+            args.academic_year(),
+            args.conflict_policy,
+        )?;
+        print_changes(file, &report.changes, args);
     }
 
     trans.commit()?;
 
-    if !args.quiet {
-        println!("Done");
+    if skipped > 0 {
+        eprintln!("Skipped {skipped} invalid cells, see the report above");
     }
 
     Ok(())
 }
+
+/// Prints `error` as JSON and exits if `json` is set, otherwise returns the
+/// error.
+fn report_error(error: Error, json: bool) -> Result<(), anyhow::Error> {
+    if json {
+        eprintln!("{}", serde_json::to_string(&error)?);
+        std::process::exit(1);
+    }
+
+    Err(error.into())
+}
+
+/// Prints the report of all the invalid cells skipped in `file`.
+///
+/// Each invalid cell is printed as a JSON object if `json` is set.
+///
+/// Returns the number of invalid cells.
+fn print_diagnostics(file: &Path, diagnostics: &[RowDiagnostic], json: bool) -> usize {
+    if json {
+        for diagnostic in diagnostics {
+            match serde_json::to_string(diagnostic) {
+                Ok(diagnostic) => eprintln!("{diagnostic}"),
+                Err(e) => eprintln!("Unable to serialize invalid cell: {e}"),
+            }
+        }
+        return diagnostics.len();
+    }
+
+    if diagnostics.is_empty() {
+        return 0;
+    }
+
+    eprintln!(
+        "Found {} invalid cells in {}:",
+        diagnostics.len(),
+        file.to_string_lossy()
+    );
+    for diagnostic in diagnostics {
+        eprintln!("{diagnostic}");
+    }
+
+    diagnostics.len()
+}
+
+/// Prints the previous import of a file with the same content as `file`.
+fn print_duplicate(file: &Path, duplicate: &DuplicateImport, skipped: bool, json: bool) {
+    if json {
+        match serde_json::to_string(duplicate) {
+            Ok(duplicate) => eprintln!("{duplicate}"),
+            Err(e) => eprintln!("Unable to serialize duplicate import: {e}"),
+        }
+        return;
+    }
+
+    let file = file.to_string_lossy();
+    if skipped {
+        eprintln!("Skipping {file}: {duplicate}");
+    } else {
+        eprintln!("Importing {file} again: {duplicate}");
+    }
+}
+
+/// Prints the module credit conflicts found in a file to the standard error
+/// output, as JSON objects if `json` is set.
+fn print_conflicts(file: &Path, conflicts: &[CreditConflict], policy: CreditPolicy, json: bool) {
+    if json {
+        for conflict in conflicts {
+            match serde_json::to_string(conflict) {
+                Ok(conflict) => eprintln!("{conflict}"),
+                Err(e) => eprintln!("Unable to serialize credit conflict: {e}"),
+            }
+        }
+        return;
+    }
+
+    if conflicts.is_empty() {
+        return;
+    }
+
+    eprintln!(
+        "Found {} module credit conflicts in {} ({}):",
+        conflicts.len(),
+        file.to_string_lossy(),
+        policy
+    );
+    for conflict in conflicts {
+        eprintln!("{conflict}");
+    }
+}
+
+/// Prints the stored students with a different intake year from the intake
+/// year inferred from `file` to the standard error output, as JSON objects if
+/// `json` is set.
+fn print_intake_conflicts(file: &Path, conflicts: &[IntakeConflict], json: bool) {
+    if json {
+        for conflict in conflicts {
+            match serde_json::to_string(conflict) {
+                Ok(conflict) => eprintln!("{conflict}"),
+                Err(e) => eprintln!("Unable to serialize intake conflict: {e}"),
+            }
+        }
+        return;
+    }
+
+    if conflicts.is_empty() {
+        return;
+    }
+
+    eprintln!(
+        "Found {} students with a different intake year in {}, see the repair-intake subcommand:",
+        conflicts.len(),
+        file.to_string_lossy()
+    );
+    for conflict in conflicts {
+        eprintln!("{conflict}");
+    }
+}
+
+/// Prints the new students inserted from `file` with an unknown intake year to
+/// the standard error output, as a JSON array if `json` is set.
+fn print_unknown_intakes(file: &Path, ids: &[i64], json: bool) {
+    if ids.is_empty() {
+        return;
+    }
+
+    if json {
+        match serde_json::to_string(ids) {
+            Ok(ids) => eprintln!("{{\"unknownIntakes\":{ids}}}"),
+            Err(e) => eprintln!("Unable to serialize unknown intakes: {e}"),
+        }
+        return;
+    }
+
+    eprintln!(
+        "Unable to infer the intake year of {} new students in {}, their intake year is unknown:",
+        ids.len(),
+        file.to_string_lossy()
+    );
+    for id in ids {
+        eprintln!("{id}");
+    }
+}
+
+/// Prints the fields of stored students and component marks changed by
+/// importing `file` to the standard output, as JSON objects if `json` is set.
+fn print_changes(file: &Path, changes: &[FieldChange], args: &Arg) {
+    if args.quiet || changes.is_empty() {
+        return;
+    }
+
+    if args.json {
+        for change in changes {
+            match serde_json::to_string(change) {
+                Ok(change) => println!("{change}"),
+                Err(e) => eprintln!("Unable to serialize student change: {e}"),
+            }
+        }
+        return;
+    }
+
+    println!(
+        "Changed {} student fields from {} ({}):",
+        changes.len(),
+        file.to_string_lossy(),
+        args.conflict_policy
+    );
+    for change in changes {
+        println!("{change}");
+    }
+}
@@ -0,0 +1 @@
+../migrations/V2__result_attempts.sql
\ No newline at end of file
@@ -0,0 +1 @@
+../migrations/V3__summer_semester.sql
\ No newline at end of file
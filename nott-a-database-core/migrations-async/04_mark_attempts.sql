@@ -0,0 +1 @@
+../migrations/V4__mark_attempts.sql
\ No newline at end of file
@@ -0,0 +1 @@
+../migrations/V5__mark_codes.sql
\ No newline at end of file
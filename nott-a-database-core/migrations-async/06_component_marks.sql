@@ -0,0 +1 @@
+../migrations/V6__component_marks.sql
\ No newline at end of file
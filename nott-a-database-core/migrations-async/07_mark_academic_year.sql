@@ -0,0 +1 @@
+../migrations/V7__mark_academic_year.sql
\ No newline at end of file
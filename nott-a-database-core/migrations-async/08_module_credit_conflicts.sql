@@ -0,0 +1 @@
+../migrations/V8__module_credit_conflicts.sql
\ No newline at end of file
@@ -0,0 +1 @@
+../migrations/V9__import_batches.sql
\ No newline at end of file
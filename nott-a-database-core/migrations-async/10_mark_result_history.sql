@@ -0,0 +1 @@
+../migrations/V10__mark_result_history.sql
\ No newline at end of file
@@ -0,0 +1 @@
+../migrations/V11__unknown_intake_year.sql
\ No newline at end of file
@@ -0,0 +1 @@
+../migrations/V12__module_batches.sql
\ No newline at end of file
@@ -0,0 +1 @@
+../migrations/V13__mark_attempt_history.sql
\ No newline at end of file
@@ -0,0 +1,148 @@
+-- "MarkHistory" and "ResultHistory" definitions
+--
+-- Every version of the marks and results, valid from the time it was inserted
+-- or updated until the time it was replaced or deleted (NULL while it is the
+-- current version), with the batch which imported it. The history is kept by
+-- the triggers below, a new version is only added when the values change.
+CREATE TABLE "MarkHistory" (
+	HistoryID INTEGER PRIMARY KEY AUTOINCREMENT,
+	ID INTEGER NOT NULL,
+	AcademicYear TEXT NOT NULL,
+	Module TEXT NOT NULL,
+	Mark REAL,
+	MarkCode TEXT,
+	Fill INTEGER,
+	Status TEXT NOT NULL,
+	Batch INTEGER REFERENCES "ImportBatch"(ID),
+	ValidFrom TEXT NOT NULL,
+	ValidTo TEXT
+);
+
+CREATE INDEX MarkHistory_Student_IDX ON "MarkHistory" (ID, AcademicYear, Module);
+CREATE INDEX MarkHistory_Batch_IDX ON "MarkHistory" (Batch);
+
+CREATE TABLE "ResultHistory" (
+	HistoryID INTEGER PRIMARY KEY AUTOINCREMENT,
+	ID INTEGER NOT NULL,
+	AcademicYear TEXT NOT NULL,
+	YearOfStudy INTEGER NOT NULL,
+	AutumnCredits INTEGER,
+	AutumnMean REAL,
+	SpringCredits INTEGER,
+	SpringMean REAL,
+	SummerCredits INTEGER,
+	SummerMean REAL,
+	YearCredits INTEGER,
+	YearMean REAL,
+	Progression TEXT,
+	Remarks TEXT,
+	Batch INTEGER REFERENCES "ImportBatch"(ID),
+	ValidFrom TEXT NOT NULL,
+	ValidTo TEXT
+);
+
+CREATE INDEX ResultHistory_Student_IDX ON "ResultHistory" (ID, AcademicYear);
+CREATE INDEX ResultHistory_Batch_IDX ON "ResultHistory" (Batch);
+
+-- The time the last row of a batch was written, the history as of a batch is
+-- the history at this time
+ALTER TABLE "ImportBatch" ADD COLUMN CompletedAt TEXT;
+UPDATE "ImportBatch" SET CompletedAt = strftime('%Y-%m-%d %H:%M:%f', ImportedAt);
+
+-- The existing marks and results are valid from the time they were imported,
+-- or from now if the batch which imported them is unknown
+INSERT INTO "MarkHistory"
+(ID, AcademicYear, Module, Mark, MarkCode, Fill, Status, Batch, ValidFrom)
+SELECT m.ID, m.AcademicYear, m.Module, m.Mark, m.MarkCode, m.Fill, m.Status, m.ImportBatch,
+COALESCE(b.CompletedAt, strftime('%Y-%m-%d %H:%M:%f', 'now'))
+FROM "Mark" m LEFT JOIN "ImportBatch" b ON b.ID = m.ImportBatch;
+
+INSERT INTO "ResultHistory"
+(ID, AcademicYear, YearOfStudy, AutumnCredits, AutumnMean, SpringCredits, SpringMean,
+SummerCredits, SummerMean, YearCredits, YearMean, Progression, Remarks, Batch, ValidFrom)
+SELECT r.ID, r.AcademicYear, r.YearOfStudy, r.AutumnCredits, r.AutumnMean, r.SpringCredits,
+r.SpringMean, r.SummerCredits, r.SummerMean, r.YearCredits, r.YearMean, r.Progression,
+r.Remarks, r.ImportBatch, COALESCE(b.CompletedAt, strftime('%Y-%m-%d %H:%M:%f', 'now'))
+FROM "Result" r LEFT JOIN "ImportBatch" b ON b.ID = r.ImportBatch;
+
+-- Marks are inserted, updated in place and deleted when rolling back a batch
+CREATE TRIGGER MarkHistory_Insert AFTER INSERT ON "Mark"
+BEGIN
+	UPDATE "MarkHistory" SET ValidTo = strftime('%Y-%m-%d %H:%M:%f', 'now')
+	WHERE ID = NEW.ID AND AcademicYear = NEW.AcademicYear AND Module = NEW.Module
+	AND ValidTo IS NULL;
+	INSERT INTO "MarkHistory"
+	(ID, AcademicYear, Module, Mark, MarkCode, Fill, Status, Batch, ValidFrom)
+	VALUES (NEW.ID, NEW.AcademicYear, NEW.Module, NEW.Mark, NEW.MarkCode, NEW.Fill, NEW.Status,
+	NEW.ImportBatch, strftime('%Y-%m-%d %H:%M:%f', 'now'));
+END;
+
+CREATE TRIGGER MarkHistory_Update AFTER UPDATE ON "Mark"
+WHEN OLD.Mark IS NOT NEW.Mark OR OLD.MarkCode IS NOT NEW.MarkCode OR OLD.Fill IS NOT NEW.Fill
+OR OLD.Status IS NOT NEW.Status
+BEGIN
+	UPDATE "MarkHistory" SET ValidTo = strftime('%Y-%m-%d %H:%M:%f', 'now')
+	WHERE ID = OLD.ID AND AcademicYear = OLD.AcademicYear AND Module = OLD.Module
+	AND ValidTo IS NULL;
+	INSERT INTO "MarkHistory"
+	(ID, AcademicYear, Module, Mark, MarkCode, Fill, Status, Batch, ValidFrom)
+	VALUES (NEW.ID, NEW.AcademicYear, NEW.Module, NEW.Mark, NEW.MarkCode, NEW.Fill, NEW.Status,
+	NEW.ImportBatch, strftime('%Y-%m-%d %H:%M:%f', 'now'));
+END;
+
+CREATE TRIGGER MarkHistory_Delete AFTER DELETE ON "Mark"
+BEGIN
+	UPDATE "MarkHistory" SET ValidTo = strftime('%Y-%m-%d %H:%M:%f', 'now')
+	WHERE ID = OLD.ID AND AcademicYear = OLD.AcademicYear AND Module = OLD.Module
+	AND ValidTo IS NULL;
+END;
+
+-- Results are replaced when imported, so the current version is closed when a
+-- result is inserted unless the values are the same
+CREATE TRIGGER ResultHistory_Insert AFTER INSERT ON "Result"
+WHEN NOT EXISTS (
+	SELECT 1 FROM "ResultHistory" h
+	WHERE h.ID = NEW.ID AND h.AcademicYear = NEW.AcademicYear AND h.ValidTo IS NULL
+	AND h.YearOfStudy IS NEW.YearOfStudy
+	AND h.AutumnCredits IS NEW.AutumnCredits AND h.AutumnMean IS NEW.AutumnMean
+	AND h.SpringCredits IS NEW.SpringCredits AND h.SpringMean IS NEW.SpringMean
+	AND h.SummerCredits IS NEW.SummerCredits AND h.SummerMean IS NEW.SummerMean
+	AND h.YearCredits IS NEW.YearCredits AND h.YearMean IS NEW.YearMean
+	AND h.Progression IS NEW.Progression AND h.Remarks IS NEW.Remarks
+)
+BEGIN
+	UPDATE "ResultHistory" SET ValidTo = strftime('%Y-%m-%d %H:%M:%f', 'now')
+	WHERE ID = NEW.ID AND AcademicYear = NEW.AcademicYear AND ValidTo IS NULL;
+	INSERT INTO "ResultHistory"
+	(ID, AcademicYear, YearOfStudy, AutumnCredits, AutumnMean, SpringCredits, SpringMean,
+	SummerCredits, SummerMean, YearCredits, YearMean, Progression, Remarks, Batch, ValidFrom)
+	VALUES (NEW.ID, NEW.AcademicYear, NEW.YearOfStudy, NEW.AutumnCredits, NEW.AutumnMean,
+	NEW.SpringCredits, NEW.SpringMean, NEW.SummerCredits, NEW.SummerMean, NEW.YearCredits,
+	NEW.YearMean, NEW.Progression, NEW.Remarks, NEW.ImportBatch,
+	strftime('%Y-%m-%d %H:%M:%f', 'now'));
+END;
+
+CREATE TRIGGER ResultHistory_Update AFTER UPDATE ON "Result"
+WHEN OLD.YearOfStudy IS NOT NEW.YearOfStudy
+OR OLD.AutumnCredits IS NOT NEW.AutumnCredits OR OLD.AutumnMean IS NOT NEW.AutumnMean
+OR OLD.SpringCredits IS NOT NEW.SpringCredits OR OLD.SpringMean IS NOT NEW.SpringMean
+OR OLD.SummerCredits IS NOT NEW.SummerCredits OR OLD.SummerMean IS NOT NEW.SummerMean
+OR OLD.YearCredits IS NOT NEW.YearCredits OR OLD.YearMean IS NOT NEW.YearMean
+OR OLD.Progression IS NOT NEW.Progression OR OLD.Remarks IS NOT NEW.Remarks
+BEGIN
+	UPDATE "ResultHistory" SET ValidTo = strftime('%Y-%m-%d %H:%M:%f', 'now')
+	WHERE ID = OLD.ID AND AcademicYear = OLD.AcademicYear AND ValidTo IS NULL;
+	INSERT INTO "ResultHistory"
+	(ID, AcademicYear, YearOfStudy, AutumnCredits, AutumnMean, SpringCredits, SpringMean,
+	SummerCredits, SummerMean, YearCredits, YearMean, Progression, Remarks, Batch, ValidFrom)
+	VALUES (NEW.ID, NEW.AcademicYear, NEW.YearOfStudy, NEW.AutumnCredits, NEW.AutumnMean,
+	NEW.SpringCredits, NEW.SpringMean, NEW.SummerCredits, NEW.SummerMean, NEW.YearCredits,
+	NEW.YearMean, NEW.Progression, NEW.Remarks, NEW.ImportBatch,
+	strftime('%Y-%m-%d %H:%M:%f', 'now'));
+END;
+
+CREATE TRIGGER ResultHistory_Delete AFTER DELETE ON "Result"
+BEGIN
+	UPDATE "ResultHistory" SET ValidTo = strftime('%Y-%m-%d %H:%M:%f', 'now')
+	WHERE ID = OLD.ID AND AcademicYear = OLD.AcademicYear AND ValidTo IS NULL;
+END;
@@ -0,0 +1,75 @@
+-- Allowing an unknown intake year, for the students whose intake year cannot
+-- be inferred from the year of study or the intake in the award report (0B)
+--
+-- The foreign keys referencing the students are deferred while the table is
+-- rebuilt, as dropping the StudentInfo table would otherwise fail.
+PRAGMA defer_foreign_keys = ON;
+
+CREATE TABLE "StudentInfoOld" AS SELECT * FROM "StudentInfo";
+DROP TABLE "StudentInfo";
+
+-- StudentInfo definition
+CREATE TABLE "StudentInfo" (
+	"ID"	INTEGER,
+	LastName TEXT NOT NULL,
+	FirstName TEXT NOT NULL,
+	CareerNo INTEGER,
+	Program TEXT,
+	ProgramDesc TEXT,
+	"Plan" TEXT NOT NULL,
+	PlanDesc TEXT,
+	INTAKE TEXT,
+	QAA TEXT,
+	CalcModel TEXT,
+	RawMark REAL,
+	TruncatedMark REAL,
+	FinalMark INTEGER,
+	Borderline TEXT,
+	Calculation INTEGER,
+	DegreeAward TEXT,
+	Selected INTEGER,
+	ExceptionData TEXT,
+	Recommendation TEXT,
+	IntakeYear TEXT,
+	GraduationYear TEXT,
+	ImportBatch INTEGER REFERENCES "ImportBatch"(ID),
+	PRIMARY KEY("ID"),
+	CONSTRAINT FK_intake FOREIGN KEY (IntakeYear) REFERENCES "AcademicYear"(AcademicYear),
+	CONSTRAINT FK_graduation_year FOREIGN KEY (GraduationYear) REFERENCES "AcademicYear"(AcademicYear)
+);
+INSERT INTO "StudentInfo" SELECT * FROM "StudentInfoOld";
+DROP TABLE "StudentInfoOld";
+
+CREATE TABLE "ImportBatchStudentInfoOld" AS SELECT * FROM "ImportBatchStudentInfo";
+DROP TABLE "ImportBatchStudentInfo";
+
+CREATE TABLE "ImportBatchStudentInfo" (
+	Batch INTEGER NOT NULL,
+	ID INTEGER NOT NULL,
+	LastName TEXT NOT NULL,
+	FirstName TEXT NOT NULL,
+	CareerNo INTEGER,
+	Program TEXT,
+	ProgramDesc TEXT,
+	"Plan" TEXT NOT NULL,
+	PlanDesc TEXT,
+	INTAKE TEXT,
+	QAA TEXT,
+	CalcModel TEXT,
+	RawMark REAL,
+	TruncatedMark REAL,
+	FinalMark INTEGER,
+	Borderline TEXT,
+	Calculation INTEGER,
+	DegreeAward TEXT,
+	Selected INTEGER,
+	ExceptionData TEXT,
+	Recommendation TEXT,
+	IntakeYear TEXT,
+	GraduationYear TEXT,
+	ImportBatch INTEGER,
+	PRIMARY KEY("Batch", "ID"),
+	CONSTRAINT ImportBatchStudentInfo_Batch_FK FOREIGN KEY (Batch) REFERENCES "ImportBatch"(ID) ON DELETE CASCADE
+);
+INSERT INTO "ImportBatchStudentInfo" SELECT * FROM "ImportBatchStudentInfoOld";
+DROP TABLE "ImportBatchStudentInfoOld";
@@ -0,0 +1,29 @@
+-- The batch which last inserted or updated each module and credit conflict,
+-- NULL for the rows imported before the batches were recorded
+ALTER TABLE "Module" ADD COLUMN ImportBatch INTEGER REFERENCES "ImportBatch"(ID);
+ALTER TABLE "ModuleCreditConflict" ADD COLUMN ImportBatch INTEGER REFERENCES "ImportBatch"(ID);
+
+-- The modules and credit conflicts as they were before being updated by a
+-- batch, restored when the batch is rolled back
+CREATE TABLE "ImportBatchModule" (
+	Batch INTEGER NOT NULL,
+	Code TEXT NOT NULL,
+	Credit INTEGER NOT NULL,
+	Name TEXT,
+	ImportBatch INTEGER,
+	PRIMARY KEY("Batch", "Code"),
+	CONSTRAINT ImportBatchModule_Batch_FK FOREIGN KEY (Batch) REFERENCES "ImportBatch"(ID) ON DELETE CASCADE
+);
+
+CREATE TABLE "ImportBatchModuleCreditConflict" (
+	Batch INTEGER NOT NULL,
+	Module TEXT NOT NULL,
+	AcademicYear TEXT NOT NULL,
+	StoredCredit INTEGER NOT NULL,
+	IncomingCredit INTEGER NOT NULL,
+	Resolution TEXT NOT NULL,
+	DetectedAt TEXT NOT NULL,
+	ImportBatch INTEGER,
+	PRIMARY KEY("Batch", "Module", "AcademicYear", "StoredCredit", "IncomingCredit"),
+	CONSTRAINT ImportBatchModuleCreditConflict_Batch_FK FOREIGN KEY (Batch) REFERENCES "ImportBatch"(ID) ON DELETE CASCADE
+);
@@ -0,0 +1,77 @@
+-- The batch which last inserted or updated each attempt of a mark, NULL for
+-- the attempts imported before the batches were recorded
+ALTER TABLE "MarkAttempt" ADD COLUMN ImportBatch INTEGER REFERENCES "ImportBatch"(ID);
+ALTER TABLE "ImportBatchMarkAttempt" ADD COLUMN ImportBatch INTEGER;
+
+UPDATE "MarkAttempt" SET ImportBatch = (
+	SELECT m.ImportBatch FROM "Mark" m
+	WHERE m.ID = "MarkAttempt".ID AND m.AcademicYear = "MarkAttempt".AcademicYear
+	AND m.Module = "MarkAttempt".Module
+);
+
+-- "MarkAttemptHistory" definition
+--
+-- Every version of the attempts of the marks, kept like the versions of the
+-- marks in "MarkHistory".
+CREATE TABLE "MarkAttemptHistory" (
+	HistoryID INTEGER PRIMARY KEY AUTOINCREMENT,
+	ID INTEGER NOT NULL,
+	AcademicYear TEXT NOT NULL,
+	Module TEXT NOT NULL,
+	Attempt INTEGER NOT NULL,
+	"Session" TEXT NOT NULL,
+	Mark REAL,
+	MarkCode TEXT,
+	Capped INTEGER NOT NULL,
+	Status TEXT,
+	Batch INTEGER REFERENCES "ImportBatch"(ID),
+	ValidFrom TEXT NOT NULL,
+	ValidTo TEXT
+);
+
+CREATE INDEX MarkAttemptHistory_Student_IDX ON "MarkAttemptHistory" (ID, AcademicYear, Module, Attempt);
+CREATE INDEX MarkAttemptHistory_Batch_IDX ON "MarkAttemptHistory" (Batch);
+
+-- The existing attempts are valid from the time they were imported, or from
+-- now if the batch which imported them is unknown
+INSERT INTO "MarkAttemptHistory"
+(ID, AcademicYear, Module, Attempt, "Session", Mark, MarkCode, Capped, Status, Batch, ValidFrom)
+SELECT a.ID, a.AcademicYear, a.Module, a.Attempt, a."Session", a.Mark, a.MarkCode, a.Capped,
+a.Status, a.ImportBatch, COALESCE(b.CompletedAt, strftime('%Y-%m-%d %H:%M:%f', 'now'))
+FROM "MarkAttempt" a LEFT JOIN "ImportBatch" b ON b.ID = a.ImportBatch;
+
+-- Attempts are inserted, updated in place and deleted when a mark has fewer
+-- attempts or a batch is rolled back
+CREATE TRIGGER MarkAttemptHistory_Insert AFTER INSERT ON "MarkAttempt"
+BEGIN
+	UPDATE "MarkAttemptHistory" SET ValidTo = strftime('%Y-%m-%d %H:%M:%f', 'now')
+	WHERE ID = NEW.ID AND AcademicYear = NEW.AcademicYear AND Module = NEW.Module
+	AND Attempt = NEW.Attempt AND ValidTo IS NULL;
+	INSERT INTO "MarkAttemptHistory"
+	(ID, AcademicYear, Module, Attempt, "Session", Mark, MarkCode, Capped, Status, Batch,
+	ValidFrom)
+	VALUES (NEW.ID, NEW.AcademicYear, NEW.Module, NEW.Attempt, NEW."Session", NEW.Mark,
+	NEW.MarkCode, NEW.Capped, NEW.Status, NEW.ImportBatch, strftime('%Y-%m-%d %H:%M:%f', 'now'));
+END;
+
+CREATE TRIGGER MarkAttemptHistory_Update AFTER UPDATE ON "MarkAttempt"
+WHEN OLD."Session" IS NOT NEW."Session" OR OLD.Mark IS NOT NEW.Mark
+OR OLD.MarkCode IS NOT NEW.MarkCode OR OLD.Capped IS NOT NEW.Capped
+OR OLD.Status IS NOT NEW.Status
+BEGIN
+	UPDATE "MarkAttemptHistory" SET ValidTo = strftime('%Y-%m-%d %H:%M:%f', 'now')
+	WHERE ID = OLD.ID AND AcademicYear = OLD.AcademicYear AND Module = OLD.Module
+	AND Attempt = OLD.Attempt AND ValidTo IS NULL;
+	INSERT INTO "MarkAttemptHistory"
+	(ID, AcademicYear, Module, Attempt, "Session", Mark, MarkCode, Capped, Status, Batch,
+	ValidFrom)
+	VALUES (NEW.ID, NEW.AcademicYear, NEW.Module, NEW.Attempt, NEW."Session", NEW.Mark,
+	NEW.MarkCode, NEW.Capped, NEW.Status, NEW.ImportBatch, strftime('%Y-%m-%d %H:%M:%f', 'now'));
+END;
+
+CREATE TRIGGER MarkAttemptHistory_Delete AFTER DELETE ON "MarkAttempt"
+BEGIN
+	UPDATE "MarkAttemptHistory" SET ValidTo = strftime('%Y-%m-%d %H:%M:%f', 'now')
+	WHERE ID = OLD.ID AND AcademicYear = OLD.AcademicYear AND Module = OLD.Module
+	AND Attempt = OLD.Attempt AND ValidTo IS NULL;
+END;
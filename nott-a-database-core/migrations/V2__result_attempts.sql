@@ -0,0 +1,15 @@
+-- "ResultAttempt" definition
+CREATE TABLE "ResultAttempt" (
+	AcademicYear TEXT NOT NULL,
+	ID INTEGER NOT NULL,
+	"Session" TEXT NOT NULL,
+	Attempt INTEGER NOT NULL,
+	YearCredits INTEGER,
+	YearMean REAL,
+	CreditsL3Lt30 REAL,
+	CreditsL33039 REAL,
+	PRIMARY KEY("ID", "AcademicYear", "Session", "Attempt"),
+	CONSTRAINT ResultAttempt_StudentInfo_FK FOREIGN KEY (ID) REFERENCES StudentInfo(ID),
+	CONSTRAINT ResultAttempt_AcademicYear_FK FOREIGN KEY (AcademicYear) REFERENCES AcademicYear(AcademicYear),
+	CONSTRAINT ResultAttempt_Session_CHECK CHECK ("Session" IN ('Main', 'May', 'August'))
+);
@@ -0,0 +1,3 @@
+-- Summer semester of "Result"
+ALTER TABLE "Result" ADD COLUMN SummerCredits INTEGER;
+ALTER TABLE "Result" ADD COLUMN SummerMean REAL;
@@ -0,0 +1,37 @@
+-- "MarkAttempt" definition
+CREATE TABLE "MarkAttempt" (
+	ID INTEGER NOT NULL,
+	Module TEXT NOT NULL,
+	Attempt INTEGER NOT NULL,
+	"Session" TEXT CHECK ( "Session" in ('First Sit', 'May Resit', 'August Resit', 'Repeat Year', 'Unknown') ) NOT NULL,
+	Mark REAL NOT NULL,
+	Capped INTEGER NOT NULL DEFAULT 0,
+	Status TEXT CHECK ( Status in ('Pass', 'CF', 'HF', 'SF') ),
+	PRIMARY KEY("ID", "Module", "Attempt"),
+	CONSTRAINT MarkAttempt_Mark_FK FOREIGN KEY (ID, Module) REFERENCES "Mark"(ID, Module) ON DELETE CASCADE
+);
+
+-- Converting the retakes of the existing marks into attempts
+--
+-- The newest attempt has the status of the module. The session of the
+-- retakes and the status of the earlier attempts are not stored, so they are
+-- unknown, the retakes may be from either resit session or from repeating the
+-- year.
+INSERT INTO MarkAttempt (ID, Module, Attempt, "Session", Mark, Capped, Status)
+SELECT ID, Module, 0, 'First Sit', Mark, 0,
+	CASE WHEN Retake1 IS NULL AND Retake2 IS NULL THEN Status END
+FROM "Mark";
+
+INSERT INTO MarkAttempt (ID, Module, Attempt, "Session", Mark, Capped, Status)
+SELECT ID, Module, 1, 'Unknown', Retake1, 1,
+	CASE WHEN Retake2 IS NULL THEN Status END
+FROM "Mark"
+WHERE Retake1 IS NOT NULL;
+
+INSERT INTO MarkAttempt (ID, Module, Attempt, "Session", Mark, Capped, Status)
+SELECT ID, Module, CASE WHEN Retake1 IS NULL THEN 1 ELSE 2 END, 'Unknown', Retake2, 1, Status
+FROM "Mark"
+WHERE Retake2 IS NOT NULL;
+
+ALTER TABLE "Mark" DROP COLUMN Retake1;
+ALTER TABLE "Mark" DROP COLUMN Retake2;
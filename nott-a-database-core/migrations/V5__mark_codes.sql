@@ -0,0 +1,46 @@
+-- Allowing codes (e.g. ABS, DEF) instead of a numeric mark
+--
+-- SQLite is unable to drop the NOT NULL constraint of a column, so the Mark
+-- and MarkAttempt tables are recreated. The attempts are moved to a temporary
+-- table first as dropping the Mark table would otherwise delete them through
+-- the foreign key.
+CREATE TABLE "MarkAttemptOld" AS SELECT * FROM "MarkAttempt";
+DROP TABLE "MarkAttempt";
+
+-- Mark definition
+CREATE TABLE "MarkNew" (
+	ID INTEGER NOT NULL,
+	Mark REAL,
+	MarkCode TEXT CHECK ( MarkCode in ('ABS', 'DEF', 'EC', 'EX', 'WH') ),
+	Fill INTEGER,
+	Extra TEXT,
+	Module TEXT NOT NULL,
+	Status TEXT CHECK ( Status in ('Pass', 'CF', 'HF', 'SF') ) NOT NULL,
+	PRIMARY KEY("ID", "Module"),
+	CONSTRAINT marks_fill_FK FOREIGN KEY (Fill) REFERENCES "FillColour"(rowid),
+	CONSTRAINT FK_marks_student_info FOREIGN KEY (ID) REFERENCES "StudentInfo"(ID),
+	CONSTRAINT marks_modules_FK FOREIGN KEY (Module) REFERENCES "Module"(Code),
+	CONSTRAINT Mark_Value_CHECK CHECK ( (Mark IS NULL) != (MarkCode IS NULL) )
+);
+INSERT INTO "MarkNew" (ID, Mark, Fill, Extra, Module, Status)
+SELECT ID, Mark, Fill, Extra, Module, Status FROM "Mark";
+DROP TABLE "Mark";
+ALTER TABLE "MarkNew" RENAME TO "Mark";
+
+-- "MarkAttempt" definition
+CREATE TABLE "MarkAttempt" (
+	ID INTEGER NOT NULL,
+	Module TEXT NOT NULL,
+	Attempt INTEGER NOT NULL,
+	"Session" TEXT CHECK ( "Session" in ('First Sit', 'May Resit', 'August Resit', 'Repeat Year', 'Unknown') ) NOT NULL,
+	Mark REAL,
+	MarkCode TEXT CHECK ( MarkCode in ('ABS', 'DEF', 'EC', 'EX', 'WH') ),
+	Capped INTEGER NOT NULL DEFAULT 0,
+	Status TEXT CHECK ( Status in ('Pass', 'CF', 'HF', 'SF') ),
+	PRIMARY KEY("ID", "Module", "Attempt"),
+	CONSTRAINT MarkAttempt_Mark_FK FOREIGN KEY (ID, Module) REFERENCES "Mark"(ID, Module) ON DELETE CASCADE,
+	CONSTRAINT MarkAttempt_Value_CHECK CHECK ( (Mark IS NULL) != (MarkCode IS NULL) )
+);
+INSERT INTO "MarkAttempt" (ID, Module, Attempt, "Session", Mark, Capped, Status)
+SELECT ID, Module, Attempt, "Session", Mark, Capped, Status FROM "MarkAttemptOld";
+DROP TABLE "MarkAttemptOld";
@@ -0,0 +1,25 @@
+-- "ComponentMark" definition
+CREATE TABLE "ComponentMark" (
+	ID INTEGER NOT NULL,
+	Module TEXT NOT NULL,
+	Attempt INTEGER NOT NULL DEFAULT 0,
+	Component TEXT NOT NULL,
+	Weight REAL NOT NULL,
+	Mark REAL,
+	MarkCode TEXT CHECK ( MarkCode in ('ABS', 'DEF', 'EC', 'EX', 'WH') ),
+	PRIMARY KEY("ID", "Module", "Attempt", "Component"),
+	CONSTRAINT ComponentMark_Mark_FK FOREIGN KEY (ID, Module) REFERENCES "Mark"(ID, Module) ON DELETE CASCADE,
+	CONSTRAINT ComponentMark_Value_CHECK CHECK ( (Mark IS NULL) != (MarkCode IS NULL) )
+);
+
+-- The failed components of every module with the status of the module, e.g.
+-- the components explaining a component-fail (CF)
+--
+-- A component is failed if it is below the pass mark of the module (50 for
+-- level 4 modules and 40 otherwise) or the student is absent.
+CREATE VIEW "FailedComponent" AS
+SELECT c.ID, c.Module, c.Attempt, c.Component, c.Weight, c.Mark, c.MarkCode, m.Status
+FROM "ComponentMark" c
+JOIN "Mark" m ON m.ID = c.ID AND m.Module = c.Module
+WHERE c.MarkCode = 'ABS'
+	OR c.Mark < (CASE WHEN substr(c.Module, 5, 1) = '4' THEN 50 ELSE 40 END);
@@ -0,0 +1,96 @@
+-- Keeping the marks of every academic year, e.g. a module repeated in a
+-- later year
+--
+-- The academic year of the existing marks is back-filled from the newest
+-- result of the student, or the intake year of the student if the student has
+-- no result. The attempts and component marks are moved to temporary tables
+-- first as dropping the Mark table would otherwise delete them through the
+-- foreign keys.
+DROP VIEW "FailedComponent";
+CREATE TABLE "MarkAttemptOld" AS SELECT * FROM "MarkAttempt";
+DROP TABLE "MarkAttempt";
+CREATE TABLE "ComponentMarkOld" AS SELECT * FROM "ComponentMark";
+DROP TABLE "ComponentMark";
+
+-- Mark definition
+CREATE TABLE "MarkNew" (
+	ID INTEGER NOT NULL,
+	AcademicYear TEXT NOT NULL,
+	Mark REAL,
+	MarkCode TEXT CHECK ( MarkCode in ('ABS', 'DEF', 'EC', 'EX', 'WH') ),
+	Fill INTEGER,
+	Extra TEXT,
+	Module TEXT NOT NULL,
+	Status TEXT CHECK ( Status in ('Pass', 'CF', 'HF', 'SF') ) NOT NULL,
+	PRIMARY KEY("ID", "AcademicYear", "Module"),
+	CONSTRAINT marks_fill_FK FOREIGN KEY (Fill) REFERENCES "FillColour"(rowid),
+	CONSTRAINT FK_marks_student_info FOREIGN KEY (ID) REFERENCES "StudentInfo"(ID),
+	CONSTRAINT marks_modules_FK FOREIGN KEY (Module) REFERENCES "Module"(Code),
+	CONSTRAINT Mark_AcademicYear_FK FOREIGN KEY (AcademicYear) REFERENCES "AcademicYear"(AcademicYear),
+	CONSTRAINT Mark_Value_CHECK CHECK ( (Mark IS NULL) != (MarkCode IS NULL) )
+);
+INSERT INTO "MarkNew" (ID, AcademicYear, Mark, MarkCode, Fill, Extra, Module, Status)
+SELECT m.ID,
+	COALESCE(
+		(SELECT MAX(r.AcademicYear) FROM "Result" r WHERE r.ID = m.ID),
+		(SELECT s.IntakeYear FROM "StudentInfo" s WHERE s.ID = m.ID)
+	),
+	m.Mark, m.MarkCode, m.Fill, m.Extra, m.Module, m.Status
+FROM "Mark" m;
+DROP TABLE "Mark";
+ALTER TABLE "MarkNew" RENAME TO "Mark";
+
+-- "MarkAttempt" definition
+CREATE TABLE "MarkAttempt" (
+	ID INTEGER NOT NULL,
+	AcademicYear TEXT NOT NULL,
+	Module TEXT NOT NULL,
+	Attempt INTEGER NOT NULL,
+	"Session" TEXT CHECK ( "Session" in ('First Sit', 'May Resit', 'August Resit', 'Repeat Year', 'Unknown') ) NOT NULL,
+	Mark REAL,
+	MarkCode TEXT CHECK ( MarkCode in ('ABS', 'DEF', 'EC', 'EX', 'WH') ),
+	Capped INTEGER NOT NULL DEFAULT 0,
+	Status TEXT CHECK ( Status in ('Pass', 'CF', 'HF', 'SF') ),
+	PRIMARY KEY("ID", "AcademicYear", "Module", "Attempt"),
+	CONSTRAINT MarkAttempt_Mark_FK FOREIGN KEY (ID, AcademicYear, Module) REFERENCES "Mark"(ID, AcademicYear, Module) ON DELETE CASCADE,
+	CONSTRAINT MarkAttempt_Value_CHECK CHECK ( (Mark IS NULL) != (MarkCode IS NULL) )
+);
+INSERT INTO "MarkAttempt"
+	(ID, AcademicYear, Module, Attempt, "Session", Mark, MarkCode, Capped, Status)
+SELECT a.ID, m.AcademicYear, a.Module, a.Attempt, a."Session", a.Mark, a.MarkCode, a.Capped, a.Status
+FROM "MarkAttemptOld" a
+JOIN "Mark" m ON m.ID = a.ID AND m.Module = a.Module;
+DROP TABLE "MarkAttemptOld";
+
+-- "ComponentMark" definition
+CREATE TABLE "ComponentMark" (
+	ID INTEGER NOT NULL,
+	AcademicYear TEXT NOT NULL,
+	Module TEXT NOT NULL,
+	Attempt INTEGER NOT NULL DEFAULT 0,
+	Component TEXT NOT NULL,
+	Weight REAL NOT NULL,
+	Mark REAL,
+	MarkCode TEXT CHECK ( MarkCode in ('ABS', 'DEF', 'EC', 'EX', 'WH') ),
+	PRIMARY KEY("ID", "AcademicYear", "Module", "Attempt", "Component"),
+	CONSTRAINT ComponentMark_Mark_FK FOREIGN KEY (ID, AcademicYear, Module) REFERENCES "Mark"(ID, AcademicYear, Module) ON DELETE CASCADE,
+	CONSTRAINT ComponentMark_Value_CHECK CHECK ( (Mark IS NULL) != (MarkCode IS NULL) )
+);
+INSERT INTO "ComponentMark"
+	(ID, AcademicYear, Module, Attempt, Component, Weight, Mark, MarkCode)
+SELECT c.ID, m.AcademicYear, c.Module, c.Attempt, c.Component, c.Weight, c.Mark, c.MarkCode
+FROM "ComponentMarkOld" c
+JOIN "Mark" m ON m.ID = c.ID AND m.Module = c.Module;
+DROP TABLE "ComponentMarkOld";
+
+-- The failed components of every module with the status of the module, e.g.
+-- the components explaining a component-fail (CF)
+--
+-- A component is failed if it is below the pass mark of the module (50 for
+-- level 4 modules and 40 otherwise) or the student is absent.
+CREATE VIEW "FailedComponent" AS
+SELECT c.ID, c.AcademicYear, c.Module, c.Attempt, c.Component, c.Weight, c.Mark, c.MarkCode, m.Status
+FROM "ComponentMark" c
+JOIN "Mark" m ON m.ID = c.ID AND m.AcademicYear = c.AcademicYear AND m.Module = c.Module
+WHERE c.MarkCode = 'ABS'
+	OR c.Mark < (CASE WHEN substr(c.Module, 5, 1) = '4' THEN 50 ELSE 40 END);
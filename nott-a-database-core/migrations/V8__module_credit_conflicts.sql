@@ -0,0 +1,16 @@
+-- "ModuleCreditConflict" definition
+--
+-- The modules found with different credits from the stored credits of the
+-- module when importing the data of an academic year, with the resolution of
+-- the conflict, i.e. whether the stored credits were kept or overwritten.
+CREATE TABLE "ModuleCreditConflict" (
+	Module TEXT NOT NULL,
+	AcademicYear TEXT NOT NULL,
+	StoredCredit INTEGER NOT NULL,
+	IncomingCredit INTEGER NOT NULL,
+	Resolution TEXT CHECK ( Resolution in ('Keep', 'Overwrite') ) NOT NULL,
+	DetectedAt TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY("Module", "AcademicYear", "StoredCredit", "IncomingCredit"),
+	CONSTRAINT ModuleCreditConflict_Module_FK FOREIGN KEY (Module) REFERENCES "Module"(Code),
+	CONSTRAINT ModuleCreditConflict_AcademicYear_FK FOREIGN KEY (AcademicYear) REFERENCES "AcademicYear"(AcademicYear)
+);
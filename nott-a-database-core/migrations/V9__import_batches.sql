@@ -0,0 +1,120 @@
+-- "ImportBatch" definition
+--
+-- Every imported report with the rows inserted or updated by it, so an import
+-- can be rolled back.
+CREATE TABLE "ImportBatch" (
+	ID INTEGER PRIMARY KEY AUTOINCREMENT,
+	FileName TEXT NOT NULL,
+	Sha256 TEXT NOT NULL,
+	Kind TEXT CHECK ( Kind in ('result', 'award', 'resit') ) NOT NULL,
+	-- The session of a resit report, as in ResultAttempt
+	"Session" TEXT CHECK ( "Session" IN ('May', 'August') ),
+	AcademicYear TEXT NOT NULL,
+	ImportedAt TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	"Rows" INTEGER NOT NULL,
+	Skipped INTEGER NOT NULL DEFAULT 0,
+	RolledBackAt TEXT,
+	CONSTRAINT ImportBatch_Session_CHECK CHECK ( (Kind = 'resit') = ("Session" IS NOT NULL) ),
+	CONSTRAINT ImportBatch_AcademicYear_FK FOREIGN KEY (AcademicYear) REFERENCES "AcademicYear"(AcademicYear)
+);
+
+-- The batch which last inserted or updated each row, NULL for the rows
+-- imported before the batches were recorded
+ALTER TABLE "StudentInfo" ADD COLUMN ImportBatch INTEGER REFERENCES "ImportBatch"(ID);
+ALTER TABLE "Result" ADD COLUMN ImportBatch INTEGER REFERENCES "ImportBatch"(ID);
+ALTER TABLE "Mark" ADD COLUMN ImportBatch INTEGER REFERENCES "ImportBatch"(ID);
+
+-- The rows as they were before being updated by a batch, restored when the
+-- batch is rolled back
+CREATE TABLE "ImportBatchStudentInfo" (
+	Batch INTEGER NOT NULL,
+	ID INTEGER NOT NULL,
+	LastName TEXT NOT NULL,
+	FirstName TEXT NOT NULL,
+	CareerNo INTEGER,
+	Program TEXT,
+	ProgramDesc TEXT,
+	"Plan" TEXT NOT NULL,
+	PlanDesc TEXT,
+	INTAKE TEXT,
+	QAA TEXT,
+	CalcModel TEXT,
+	RawMark REAL,
+	TruncatedMark REAL,
+	FinalMark INTEGER,
+	Borderline TEXT,
+	Calculation INTEGER,
+	DegreeAward TEXT,
+	Selected INTEGER,
+	ExceptionData TEXT,
+	Recommendation TEXT,
+	IntakeYear TEXT NOT NULL,
+	GraduationYear TEXT,
+	ImportBatch INTEGER,
+	PRIMARY KEY("Batch", "ID"),
+	CONSTRAINT ImportBatchStudentInfo_Batch_FK FOREIGN KEY (Batch) REFERENCES "ImportBatch"(ID) ON DELETE CASCADE
+);
+
+CREATE TABLE "ImportBatchResult" (
+	Batch INTEGER NOT NULL,
+	AcademicYear TEXT NOT NULL,
+	ID INTEGER NOT NULL,
+	YearOfStudy INTEGER NOT NULL,
+	AutumnCredits INTEGER,
+	AutumnMean REAL,
+	SpringCredits INTEGER,
+	SpringMean REAL,
+	YearCredits INTEGER,
+	YearMean REAL,
+	Progression TEXT,
+	Remarks TEXT,
+	SummerCredits INTEGER,
+	SummerMean REAL,
+	ImportBatch INTEGER,
+	PRIMARY KEY("Batch", "ID", "AcademicYear"),
+	CONSTRAINT ImportBatchResult_Batch_FK FOREIGN KEY (Batch) REFERENCES "ImportBatch"(ID) ON DELETE CASCADE
+);
+
+CREATE TABLE "ImportBatchResultAttempt" (
+	Batch INTEGER NOT NULL,
+	AcademicYear TEXT NOT NULL,
+	ID INTEGER NOT NULL,
+	"Session" TEXT NOT NULL,
+	Attempt INTEGER NOT NULL,
+	YearCredits INTEGER,
+	YearMean REAL,
+	CreditsL3Lt30 REAL,
+	CreditsL33039 REAL,
+	PRIMARY KEY("Batch", "ID", "AcademicYear", "Session", "Attempt"),
+	CONSTRAINT ImportBatchResultAttempt_Batch_FK FOREIGN KEY (Batch) REFERENCES "ImportBatch"(ID) ON DELETE CASCADE
+);
+
+CREATE TABLE "ImportBatchMark" (
+	Batch INTEGER NOT NULL,
+	ID INTEGER NOT NULL,
+	AcademicYear TEXT NOT NULL,
+	Mark REAL,
+	MarkCode TEXT,
+	Fill INTEGER,
+	Extra TEXT,
+	Module TEXT NOT NULL,
+	Status TEXT NOT NULL,
+	ImportBatch INTEGER,
+	PRIMARY KEY("Batch", "ID", "AcademicYear", "Module"),
+	CONSTRAINT ImportBatchMark_Batch_FK FOREIGN KEY (Batch) REFERENCES "ImportBatch"(ID) ON DELETE CASCADE
+);
+
+CREATE TABLE "ImportBatchMarkAttempt" (
+	Batch INTEGER NOT NULL,
+	ID INTEGER NOT NULL,
+	AcademicYear TEXT NOT NULL,
+	Module TEXT NOT NULL,
+	Attempt INTEGER NOT NULL,
+	"Session" TEXT NOT NULL,
+	Mark REAL,
+	MarkCode TEXT,
+	Capped INTEGER NOT NULL,
+	Status TEXT,
+	PRIMARY KEY("Batch", "ID", "AcademicYear", "Module", "Attempt"),
+	CONSTRAINT ImportBatchMarkAttempt_Batch_FK FOREIGN KEY (Batch) REFERENCES "ImportBatch"(ID) ON DELETE CASCADE
+);
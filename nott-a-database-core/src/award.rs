@@ -1,15 +1,23 @@
 //! Parser implementation of award report (0B) raw data.
-use std::{path::Path, str::FromStr};
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek},
+    path::Path,
+};
+
+use calamine::{open_workbook_from_rs, Data, DataType, Reader, Xlsx, XlsxError};
 
-use calamine::{open_workbook, Data, DataType, Reader, Xlsx};
+use serde::Deserialize;
 
 use crate::{
-    errors::{ParseAwardError, ParseAwardRowError},
-    StudentInfo,
+    errors::{ParseAwardError, ParseAwardRowError, RowDiagnostic},
+    report::SheetHeaders,
+    ParseOptions, StudentInfo,
 };
 
 /// The header columns in award report (0B) raw data.
-#[derive(Debug)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
 pub enum AwardHeader {
     /// The row number of the data.
     No,
@@ -32,6 +40,7 @@ pub enum AwardHeader {
     /// The intake year of the student.
     Intake,
     /// The QAA Effective Data of the student.
+    #[serde(rename = "qaa_effective_date")]
     QAAEffectiveDate,
     /// The Degree Calculation Model of the student.
     DegreeCalculationModel,
@@ -58,39 +67,28 @@ pub enum AwardHeader {
     Recommendation,
 }
 
-impl FromStr for AwardHeader {
-    type Err = ParseAwardError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "No" => Self::No,
-            "Student ID" => Self::Id,
-            "Surname" => Self::LastName,
-            "First Name" => Self::FirstName,
-            "Career Number" => Self::CareerNumber,
-            "Academic Program" => Self::AcademicProgram,
-            "Program Description" => Self::ProgramDescription,
-            "Academic Plan" => Self::AcademicPlan,
-            "Plan Description" => Self::PlanDescription,
-            "Intake" => Self::Intake,
-            "QAA Effective Date" => Self::QAAEffectiveDate,
-            "Degree Calculation Model" => Self::DegreeCalculationModel,
-            "Raw Final Mark" => Self::RawFinalMark,
-            "Truncated Final Mark" => Self::TruncatedFinalMark,
-            "Final Mark" => Self::FinalMark,
-            "Borderline?" => Self::Borderline,
-            "Calculation Review Rqd" => Self::CalculationReviewRqd,
-            "Degree Award" => Self::DegreeAward,
-            "Selected" => Self::Selected,
-            "Exception Data" => Self::ExceptionData,
-            "" => Self::Empty,
-            "Recommendation" => Self::Recommendation,
-            _ => {
-                return Err(ParseAwardError::InvalidHeader(
-                    "Invalid award header".into(),
-                ))
-            }
-        })
+impl AwardHeader {
+    /// Gets the type of value expected in the column.
+    pub fn expected(&self) -> &'static str {
+        match self {
+            Self::No | Self::Empty => "anything",
+            Self::Id | Self::CareerNumber | Self::FinalMark => "an integer",
+            Self::LastName
+            | Self::FirstName
+            | Self::AcademicProgram
+            | Self::ProgramDescription
+            | Self::AcademicPlan
+            | Self::PlanDescription
+            | Self::Intake
+            | Self::DegreeCalculationModel
+            | Self::Borderline
+            | Self::ExceptionData
+            | Self::Recommendation => "text",
+            Self::QAAEffectiveDate => "a date",
+            Self::RawFinalMark | Self::TruncatedFinalMark => "a number",
+            Self::CalculationReviewRqd | Self::Selected => "\"Y\" or \"N\"",
+            Self::DegreeAward => "text or a time",
+        }
     }
 }
 
@@ -103,168 +101,293 @@ impl StudentInfo {
         let mut output = Self::new();
 
         for (header, data) in headers.iter().zip(data) {
-            match header {
-                AwardHeader::No => continue,
-                AwardHeader::Id => {
-                    output.id = data.as_i64().ok_or(ParseAwardRowError::InvalidId)?
-                }
-                AwardHeader::LastName => {
-                    output.last_name = data
-                        .as_string()
-                        .ok_or(ParseAwardRowError::InvalidLastName)?
-                }
-                AwardHeader::FirstName => {
-                    output.first_name = data
-                        .as_string()
-                        .ok_or(ParseAwardRowError::InvalidFirstName)?
-                }
-                AwardHeader::CareerNumber => {
-                    output.carrer_number = Some(
-                        data.as_i64()
-                            .ok_or(ParseAwardRowError::InvalidCareerNumber)?,
-                    )
-                }
-                AwardHeader::AcademicProgram => {
-                    output.academic_program = Some(
-                        data.as_string()
-                            .ok_or(ParseAwardRowError::InvalidAcademicProgram)?,
-                    )
-                }
-                AwardHeader::ProgramDescription => {
-                    output.program_description = Some(
-                        data.as_string()
-                            .ok_or(ParseAwardRowError::InvalidProgramDescription)?,
-                    )
-                }
-                AwardHeader::AcademicPlan => {
-                    output.plan = data
-                        .as_string()
-                        .ok_or(ParseAwardRowError::InvalidAcademicPlan)?
-                }
-                AwardHeader::PlanDescription => {
-                    output.plan_description = Some(
-                        data.as_string()
-                            .ok_or(ParseAwardRowError::InvalidPlanDescription)?,
-                    )
-                }
-                AwardHeader::Intake => {
-                    output.intake = Some(data.as_string().ok_or(ParseAwardRowError::InvalidIntake)?)
-                }
-                AwardHeader::QAAEffectiveDate => {
-                    output.qaa_effective_date = Some(
-                        data.as_datetime()
-                            .ok_or(ParseAwardRowError::InvalidQAAEffectiveDate)?,
-                    )
-                }
-                AwardHeader::DegreeCalculationModel => {
-                    output.calculation_model = Some(
-                        data.as_string()
-                            .ok_or(ParseAwardRowError::InvalidDegreeCalculationModel)?,
-                    )
-                }
-                AwardHeader::RawFinalMark => {
-                    output.raw_mark = Some(
-                        data.as_f64()
-                            .ok_or(ParseAwardRowError::InvalidRawFinalMark)?,
-                    )
-                }
-                AwardHeader::TruncatedFinalMark => {
-                    output.truncated_mark = Some(
-                        data.as_f64()
-                            .ok_or(ParseAwardRowError::InvalidTruncatedFinalMark)?,
-                    )
-                }
-                AwardHeader::FinalMark => {
-                    output.final_mark =
-                        Some(data.as_i64().ok_or(ParseAwardRowError::InvalidFinalMark)?)
-                }
-                AwardHeader::Borderline => {
-                    output.borderline = Some(
-                        data.as_string()
-                            .ok_or(ParseAwardRowError::InvalidBorderline)?,
-                    )
-                }
-                AwardHeader::CalculationReviewRqd => {
-                    let data = data
-                        .as_string()
-                        .ok_or(ParseAwardRowError::InvalidCalculationReviewRqd)?;
-                    output.calculation = match data.as_str() {
-                        "Y" => Some(true),
-                        "N" => Some(false),
-                        _ => return Err(ParseAwardRowError::InvalidSelected),
-                    };
-                }
-                AwardHeader::DegreeAward => {
-                    if DataType::is_empty(data) {
-                        continue;
-                    }
+            output.set_award_cell(header, data)?;
+        }
 
-                    output.degree_award = match data.as_string() {
-                        Some(e) => Some(e),
-                        None => {
-                            let e = data
-                                .as_time()
-                                .ok_or(ParseAwardRowError::InvalidDegreeAward)?;
-                            Some(e.format("%H:%M").to_string())
-                        }
-                    };
-                }
-                AwardHeader::Selected => {
-                    let data = data
-                        .as_string()
-                        .ok_or(ParseAwardRowError::InvalidSelected)?;
-                    output.selected = match data.as_str() {
-                        "Y" => Some(true),
-                        "N" => Some(false),
-                        _ => return Err(ParseAwardRowError::InvalidSelected),
-                    }
+        Ok(output)
+    }
+
+    /// Creates [`StudentInfo`] from a row of award report (0B) raw data,
+    /// collecting the errors of every invalid cell with its column.
+    fn from_award_row_lenient(
+        data: &[Data],
+        headers: &[AwardHeader],
+    ) -> Result<Self, Vec<(usize, ParseAwardRowError)>> {
+        let mut output = Self::new();
+
+        let errors: Vec<_> = headers
+            .iter()
+            .zip(data)
+            .enumerate()
+            .filter_map(|(col, (header, data))| {
+                output.set_award_cell(header, data).err().map(|e| (col, e))
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(output)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Sets the field of `header` from a cell of award report (0B) raw data.
+    fn set_award_cell(
+        &mut self,
+        header: &AwardHeader,
+        data: &Data,
+    ) -> Result<(), ParseAwardRowError> {
+        match header {
+            AwardHeader::No => {}
+            AwardHeader::Id => self.id = data.as_i64().ok_or(ParseAwardRowError::InvalidId)?,
+            AwardHeader::LastName => {
+                self.last_name = data
+                    .as_string()
+                    .ok_or(ParseAwardRowError::InvalidLastName)?
+            }
+            AwardHeader::FirstName => {
+                self.first_name = data
+                    .as_string()
+                    .ok_or(ParseAwardRowError::InvalidFirstName)?
+            }
+            AwardHeader::CareerNumber => {
+                self.carrer_number = Some(
+                    data.as_i64()
+                        .ok_or(ParseAwardRowError::InvalidCareerNumber)?,
+                )
+            }
+            AwardHeader::AcademicProgram => {
+                self.academic_program = Some(
+                    data.as_string()
+                        .ok_or(ParseAwardRowError::InvalidAcademicProgram)?,
+                )
+            }
+            AwardHeader::ProgramDescription => {
+                self.program_description = Some(
+                    data.as_string()
+                        .ok_or(ParseAwardRowError::InvalidProgramDescription)?,
+                )
+            }
+            AwardHeader::AcademicPlan => {
+                self.plan = data
+                    .as_string()
+                    .ok_or(ParseAwardRowError::InvalidAcademicPlan)?
+            }
+            AwardHeader::PlanDescription => {
+                self.plan_description = Some(
+                    data.as_string()
+                        .ok_or(ParseAwardRowError::InvalidPlanDescription)?,
+                )
+            }
+            AwardHeader::Intake => {
+                self.intake = Some(data.as_string().ok_or(ParseAwardRowError::InvalidIntake)?)
+            }
+            AwardHeader::QAAEffectiveDate => {
+                self.qaa_effective_date = Some(
+                    data.as_datetime()
+                        .ok_or(ParseAwardRowError::InvalidQAAEffectiveDate)?,
+                )
+            }
+            AwardHeader::DegreeCalculationModel => {
+                self.calculation_model = Some(
+                    data.as_string()
+                        .ok_or(ParseAwardRowError::InvalidDegreeCalculationModel)?,
+                )
+            }
+            AwardHeader::RawFinalMark => {
+                self.raw_mark = Some(
+                    data.as_f64()
+                        .ok_or(ParseAwardRowError::InvalidRawFinalMark)?,
+                )
+            }
+            AwardHeader::TruncatedFinalMark => {
+                self.truncated_mark = Some(
+                    data.as_f64()
+                        .ok_or(ParseAwardRowError::InvalidTruncatedFinalMark)?,
+                )
+            }
+            AwardHeader::FinalMark => {
+                self.final_mark = Some(data.as_i64().ok_or(ParseAwardRowError::InvalidFinalMark)?)
+            }
+            AwardHeader::Borderline => {
+                self.borderline = Some(
+                    data.as_string()
+                        .ok_or(ParseAwardRowError::InvalidBorderline)?,
+                )
+            }
+            AwardHeader::CalculationReviewRqd => {
+                let data = data
+                    .as_string()
+                    .ok_or(ParseAwardRowError::InvalidCalculationReviewRqd)?;
+                self.calculation = match data.as_str() {
+                    "Y" => Some(true),
+                    "N" => Some(false),
+                    _ => return Err(ParseAwardRowError::InvalidSelected),
+                };
+            }
+            AwardHeader::DegreeAward => {
+                if DataType::is_empty(data) {
+                    return Ok(());
                 }
-                AwardHeader::ExceptionData => {
-                    if DataType::is_empty(data) {
-                        continue;
-                    }
 
-                    output.exception_data = Some(
-                        data.as_string()
-                            .ok_or(ParseAwardRowError::InvalidExceptionData)?,
-                    );
+                self.degree_award = match data.as_string() {
+                    Some(e) => Some(e),
+                    None => {
+                        let e = data
+                            .as_time()
+                            .ok_or(ParseAwardRowError::InvalidDegreeAward)?;
+                        Some(e.format("%H:%M").to_string())
+                    }
+                };
+            }
+            AwardHeader::Selected => {
+                let data = data
+                    .as_string()
+                    .ok_or(ParseAwardRowError::InvalidSelected)?;
+                self.selected = match data.as_str() {
+                    "Y" => Some(true),
+                    "N" => Some(false),
+                    _ => return Err(ParseAwardRowError::InvalidSelected),
                 }
-                AwardHeader::Empty => continue,
-                AwardHeader::Recommendation => {
-                    output.recommendation = Some(
-                        data.as_string()
-                            .ok_or(ParseAwardRowError::InvalidRecommendation)?,
-                    )
+            }
+            AwardHeader::ExceptionData => {
+                if DataType::is_empty(data) {
+                    return Ok(());
                 }
+
+                self.exception_data = Some(
+                    data.as_string()
+                        .ok_or(ParseAwardRowError::InvalidExceptionData)?,
+                );
+            }
+            AwardHeader::Empty => {}
+            AwardHeader::Recommendation => {
+                self.recommendation = Some(
+                    data.as_string()
+                        .ok_or(ParseAwardRowError::InvalidRecommendation)?,
+                )
             }
         }
 
-        Ok(output)
+        Ok(())
     }
 
     /// Creates [`StudentInfo`] from award report (0B) raw data.
     pub fn from_award<P: AsRef<Path>>(file: P) -> Result<Vec<Self>, ParseAwardError> {
-        let mut excel: Xlsx<_> = open_workbook(&file).map_err(ParseAwardError::WorkbookError)?;
+        let file =
+            File::open(file).map_err(|e| ParseAwardError::WorkbookError(XlsxError::Io(e)))?;
+        Self::from_award_reader(BufReader::new(file))
+    }
+
+    /// Creates [`StudentInfo`] from award report (0B) raw data in a reader.
+    pub fn from_award_reader<R: Read + Seek>(reader: R) -> Result<Vec<Self>, ParseAwardError> {
+        Self::parse_award(reader, &ParseOptions::default()).map(|(data, _)| data)
+    }
+
+    /// Creates [`StudentInfo`] from award report (0B) raw data, skipping
+    /// invalid rows.
+    ///
+    /// A [`RowDiagnostic`] is returned for every invalid cell in the skipped
+    /// rows instead of stopping at the first invalid row.
+    pub fn from_award_lenient<P: AsRef<Path>>(
+        file: P,
+    ) -> Result<(Vec<Self>, Vec<RowDiagnostic>), ParseAwardError> {
+        let file =
+            File::open(file).map_err(|e| ParseAwardError::WorkbookError(XlsxError::Io(e)))?;
+        Self::from_award_reader_lenient(BufReader::new(file))
+    }
+
+    /// Creates [`StudentInfo`] from award report (0B) raw data in a reader,
+    /// skipping invalid rows.
+    ///
+    /// See [`StudentInfo::from_award_lenient`] for more information.
+    pub fn from_award_reader_lenient<R: Read + Seek>(
+        reader: R,
+    ) -> Result<(Vec<Self>, Vec<RowDiagnostic>), ParseAwardError> {
+        let options = ParseOptions {
+            lenient: true,
+            ..Default::default()
+        };
+        Self::parse_award(reader, &options)
+    }
+
+    /// Creates [`StudentInfo`] from award report (0B) raw data with the
+    /// [`ParseOptions`], e.g. a custom layout.
+    ///
+    /// Invalid rows are skipped and reported if `lenient` is set in the
+    /// `options`, otherwise the first invalid row is returned as an error.
+    pub fn from_award_with_options<P: AsRef<Path>>(
+        file: P,
+        options: &ParseOptions,
+    ) -> Result<(Vec<Self>, Vec<RowDiagnostic>), ParseAwardError> {
+        let file =
+            File::open(file).map_err(|e| ParseAwardError::WorkbookError(XlsxError::Io(e)))?;
+        Self::from_award_reader_with_options(BufReader::new(file), options)
+    }
+
+    /// Creates [`StudentInfo`] from award report (0B) raw data in a reader
+    /// with the [`ParseOptions`].
+    ///
+    /// See [`StudentInfo::from_award_with_options`] for more information.
+    pub fn from_award_reader_with_options<R: Read + Seek>(
+        reader: R,
+        options: &ParseOptions,
+    ) -> Result<(Vec<Self>, Vec<RowDiagnostic>), ParseAwardError> {
+        Self::parse_award(reader, options)
+    }
+
+    /// Parses award report (0B) raw data in a reader.
+    ///
+    /// Invalid rows are skipped and reported if `lenient` is set in the
+    /// `options`, otherwise the first invalid row is returned as an error.
+    fn parse_award<R: Read + Seek>(
+        reader: R,
+        options: &ParseOptions,
+    ) -> Result<(Vec<Self>, Vec<RowDiagnostic>), ParseAwardError> {
+        let mut excel: Xlsx<_> =
+            open_workbook_from_rs(reader).map_err(ParseAwardError::WorkbookError)?;
 
         let award = excel
             .worksheet_range("Award Report")
             .map_err(ParseAwardError::InvalidWorksheet)?;
 
-        let headers: Vec<AwardHeader> = award
-            .headers()
-            .ok_or(ParseAwardError::NoHeaders)?
-            .iter()
-            .map(String::as_str)
-            .map(AwardHeader::from_str)
-            .collect::<Result<_, ParseAwardError>>()?;
+        let mut sheet_headers = SheetHeaders::from_range(String::from("Award Report"), &award)
+            .ok_or(ParseAwardError::NoHeaders)?;
+        let layout = &options.layouts.award;
+        if layout.header_rows() == 1 {
+            sheet_headers.sub_headers = None;
+        }
+        let headers = layout
+            .headers(&sheet_headers.headers, sheet_headers.sub_headers.as_deref())
+            .map_err(ParseAwardError::InvalidHeader)?;
 
         let mut data = vec![];
-        for (row_no, row) in award.rows().enumerate().skip(1) {
-            let row_data = Self::from_award_row(row, &headers)
-                .map_err(|err| ParseAwardError::InvalidRow(row_no, err))?;
-            data.push(row_data);
+        let mut diagnostics = vec![];
+        for (row_no, row) in award.rows().enumerate().skip(layout.header_rows()) {
+            match Self::from_award_row_lenient(row, &headers) {
+                Ok(row_data) => data.push(row_data),
+                Err(mut errors) if !options.lenient => {
+                    let (col, err) = errors.swap_remove(0);
+                    let context = sheet_headers.cell_context(
+                        row_no + 1,
+                        col,
+                        row.get(col),
+                        headers[col].expected(),
+                    );
+                    return Err(ParseAwardError::InvalidRow(Box::new(context), err));
+                }
+                Err(errors) => diagnostics.extend(errors.into_iter().map(|(col, err)| {
+                    sheet_headers.diagnostic(
+                        row_no + 1,
+                        col,
+                        row.get(col),
+                        headers[col].expected(),
+                        err,
+                    )
+                })),
+            }
         }
 
-        Ok(data)
+        Ok((data, diagnostics))
     }
 }
@@ -0,0 +1,283 @@
+//! Parser for component mark sheets.
+//!
+//! A component mark sheet has the marks of every assessment component (e.g.
+//! coursework and exam) of a module. Each visible worksheet has the marks of
+//! a single module and is named after the module code, e.g. `EEEE2049`:
+//!
+//! | ID       | Attempt | Coursework | Exam |
+//! |----------|---------|------------|------|
+//! | Weight   |         | 40         | 60   |
+//! | 20123456 | 0       | 62         | 35   |
+//! | 20123456 | 1       |            | 48   |
+//!
+//! The first row has the names of the components, the `Attempt` column is
+//! optional and starts from 0 for the first sit. The second row has the
+//! weight of each component in percent. A component without a mark is not
+//! taken in the attempt, a mark can also be a code (see [`MarkValue`]).
+
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek},
+    path::Path,
+};
+
+use calamine::{
+    open_workbook_from_rs, Data, DataType, Range, Reader, SheetType, SheetVisible, Xlsx, XlsxError,
+};
+
+use crate::{
+    errors::{ParseComponentError, ParseComponentRowError, RowDiagnostic},
+    pass_mark,
+    report::SheetHeaders,
+    ComponentMark, MarkValue, ParseOptions,
+};
+
+/// A column of a component mark sheet.
+#[derive(Clone, Debug, PartialEq)]
+enum ComponentHeader {
+    /// The student ID of the student.
+    Id,
+    /// The attempt of the module, starting from 0 for the first sit.
+    Attempt,
+    /// The marks of a component with its name and weight.
+    Component(String, f64),
+    /// A column without a header.
+    Ignored,
+}
+
+impl ComponentHeader {
+    /// Gets the type of value expected in the column.
+    fn expected(&self) -> &'static str {
+        match self {
+            Self::Id => "an integer",
+            Self::Attempt => "an integer or an empty cell",
+            Self::Component(..) => "a mark, a code or an empty cell",
+            Self::Ignored => "anything",
+        }
+    }
+}
+
+/// Parses the mark or the code of a component, an empty cell is parsed as
+/// [`None`].
+fn component_mark(value: &Data) -> Result<Option<MarkValue>, ParseComponentRowError> {
+    match value {
+        Data::Empty => Ok(None),
+        Data::String(s) if s.trim().is_empty() => Ok(None),
+        Data::String(s) => s
+            .parse()
+            .map(Some)
+            .map_err(|_| ParseComponentRowError::InvalidMark),
+        value => value
+            .as_f64()
+            .map(|mark| Some(MarkValue::Number(mark)))
+            .ok_or(ParseComponentRowError::InvalidMark),
+    }
+}
+
+impl ComponentMark {
+    /// Checks if the component is failed, i.e. the mark is below the pass
+    /// mark of the module or the student is absent.
+    pub fn failed(&self) -> bool {
+        match self.mark {
+            MarkValue::Number(mark) => mark < pass_mark(&self.module),
+            MarkValue::Absent => true,
+            _ => false,
+        }
+    }
+
+    /// Parses the component marks of a row of a component mark sheet,
+    /// collecting the errors of every invalid cell with its column.
+    fn from_component_row(
+        module: &str,
+        headers: &[ComponentHeader],
+        row: &[Data],
+    ) -> Result<Vec<Self>, Vec<(usize, ParseComponentRowError)>> {
+        let mut id = 0;
+        let mut attempt = 0;
+        let mut marks = vec![];
+        let mut errors = vec![];
+
+        for (col, (header, value)) in headers.iter().zip(row).enumerate() {
+            let result = match header {
+                ComponentHeader::Id => value
+                    .as_i64()
+                    .map(|value| id = value)
+                    .ok_or(ParseComponentRowError::InvalidID),
+                ComponentHeader::Attempt if value.is_empty() => Ok(()),
+                ComponentHeader::Attempt => value
+                    .as_i64()
+                    .and_then(|value| usize::try_from(value).ok())
+                    .map(|value| attempt = value)
+                    .ok_or(ParseComponentRowError::InvalidAttempt),
+                ComponentHeader::Component(name, weight) => component_mark(value).map(|mark| {
+                    if let Some(mark) = mark {
+                        marks.push((name, *weight, mark));
+                    }
+                }),
+                ComponentHeader::Ignored => Ok(()),
+            };
+            if let Err(e) = result {
+                errors.push((col, e));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(marks
+            .into_iter()
+            .map(|(name, weight, mark)| ComponentMark {
+                id,
+                module: module.to_owned(),
+                name: name.clone(),
+                weight,
+                mark,
+                attempt,
+            })
+            .collect())
+    }
+
+    /// Parse [`ComponentMark`] from a component mark sheet.
+    pub fn from_components<P: AsRef<Path>>(file: P) -> Result<Vec<Self>, ParseComponentError> {
+        let file =
+            File::open(file).map_err(|e| ParseComponentError::WorkbookError(XlsxError::Io(e)))?;
+        Self::from_components_reader(BufReader::new(file))
+    }
+
+    /// Parse [`ComponentMark`] from a component mark sheet in a reader.
+    pub fn from_components_reader<R: Read + Seek>(
+        reader: R,
+    ) -> Result<Vec<Self>, ParseComponentError> {
+        Self::parse_components(reader, &ParseOptions::default()).map(|(data, _)| data)
+    }
+
+    /// Parse [`ComponentMark`] from a component mark sheet with the
+    /// [`ParseOptions`].
+    ///
+    /// Invalid rows are skipped and reported if `lenient` is set in the
+    /// `options`, otherwise the first invalid row is returned as an error.
+    pub fn from_components_with_options<P: AsRef<Path>>(
+        file: P,
+        options: &ParseOptions,
+    ) -> Result<(Vec<Self>, Vec<RowDiagnostic>), ParseComponentError> {
+        let file =
+            File::open(file).map_err(|e| ParseComponentError::WorkbookError(XlsxError::Io(e)))?;
+        Self::from_components_reader_with_options(BufReader::new(file), options)
+    }
+
+    /// Parse [`ComponentMark`] from a component mark sheet in a reader with
+    /// the [`ParseOptions`].
+    ///
+    /// See [`ComponentMark::from_components_with_options`] for more
+    /// information.
+    pub fn from_components_reader_with_options<R: Read + Seek>(
+        reader: R,
+        options: &ParseOptions,
+    ) -> Result<(Vec<Self>, Vec<RowDiagnostic>), ParseComponentError> {
+        Self::parse_components(reader, options)
+    }
+
+    /// Parses every visible worksheet of a component mark sheet in a reader.
+    fn parse_components<R: Read + Seek>(
+        reader: R,
+        options: &ParseOptions,
+    ) -> Result<(Vec<Self>, Vec<RowDiagnostic>), ParseComponentError> {
+        let mut excel: Xlsx<_> =
+            open_workbook_from_rs(reader).map_err(ParseComponentError::WorkbookError)?;
+        let names: Vec<_> = excel
+            .sheets_metadata()
+            .iter()
+            .filter(|sheet| {
+                sheet.typ == SheetType::WorkSheet && sheet.visible == SheetVisible::Visible
+            })
+            .map(|sheet| sheet.name.clone())
+            .collect();
+
+        let mut data = vec![];
+        let mut diagnostics = vec![];
+        for name in names {
+            let range = excel
+                .worksheet_range(&name)
+                .map_err(ParseComponentError::WorkbookError)?;
+            let (sheet_data, sheet_diagnostics) =
+                Self::parse_component_worksheet(name, &range, options)?;
+            data.extend(sheet_data);
+            diagnostics.extend(sheet_diagnostics);
+        }
+
+        Ok((data, diagnostics))
+    }
+
+    /// Parses a worksheet of a component mark sheet with the marks of the
+    /// module the worksheet is named after.
+    fn parse_component_worksheet(
+        name: String,
+        range: &Range<Data>,
+        options: &ParseOptions,
+    ) -> Result<(Vec<Self>, Vec<RowDiagnostic>), ParseComponentError> {
+        let module = name.trim().to_owned();
+        let sheet_headers = SheetHeaders::from_range(name.clone(), range)
+            .ok_or_else(|| ParseComponentError::NoHeaders(name.clone()))?;
+
+        // Getting the components and their weights
+        let mut headers = vec![];
+        for (col, header) in sheet_headers.headers.iter().enumerate() {
+            let header = header.trim();
+            headers.push(if header.eq_ignore_ascii_case("ID") {
+                ComponentHeader::Id
+            } else if header.eq_ignore_ascii_case("Attempt") {
+                ComponentHeader::Attempt
+            } else if header.is_empty() {
+                ComponentHeader::Ignored
+            } else {
+                let weight = range.get((1, col)).unwrap_or(&Data::Empty);
+                match weight.as_f64() {
+                    Some(weight) => ComponentHeader::Component(header.to_owned(), weight),
+                    None => {
+                        return Err(ParseComponentError::InvalidWeight(Box::new(
+                            sheet_headers.cell_context(2, col, Some(weight), "a number"),
+                        )))
+                    }
+                }
+            });
+        }
+        if !headers.contains(&ComponentHeader::Id) {
+            return Err(ParseComponentError::InvalidHeader(name, String::from("ID")));
+        }
+
+        let mut data = vec![];
+        let mut diagnostics = vec![];
+        for (row_no, row) in range.rows().enumerate().skip(2) {
+            // Skipping empty rows
+            if row.iter().all(|value| value.is_empty()) {
+                continue;
+            }
+
+            match Self::from_component_row(&module, &headers, row) {
+                Ok(marks) => data.extend(marks),
+                Err(mut errors) if !options.lenient => {
+                    let (col, err) = errors.swap_remove(0);
+                    let context = sheet_headers.cell_context(
+                        row_no + 1,
+                        col,
+                        row.get(col),
+                        headers[col].expected(),
+                    );
+                    return Err(ParseComponentError::InvalidRow(Box::new(context), err));
+                }
+                Err(errors) => diagnostics.extend(errors.into_iter().map(|(col, err)| {
+                    sheet_headers.diagnostic(
+                        row_no + 1,
+                        col,
+                        row.get(col),
+                        headers[col].expected(),
+                        err,
+                    )
+                })),
+            }
+        }
+
+        Ok((data, diagnostics))
+    }
+}
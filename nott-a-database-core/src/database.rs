@@ -1,15 +1,22 @@
 //! Implementation for inserting data into the database.
+#[cfg(any(feature = "sync", feature = "async"))]
+use serde::Serialize;
+
 #[cfg(feature = "sync")]
-use rusqlite::{params, types::ToSqlOutput, Connection, ToSql, Transaction};
+use rusqlite::{params, types::ToSqlOutput, Connection, OptionalExtension, ToSql, Transaction};
 
 #[cfg(feature = "async")]
-use sqlx::{Sqlite, SqlitePool, Transaction as AsyncTransaction};
+use sqlx::{Row, Sqlite, SqlitePool, Transaction as AsyncTransaction};
 
-#[cfg(feature = "sync")]
-use crate::ModuleStatus;
 #[cfg(any(feature = "sync", feature = "async"))]
-use crate::StudentResult;
+use crate::{
+    errors::{CreditConflict, DuplicateImport, Error, FieldChange, IntakeConflict, RollbackError},
+    AsOf, ComponentMark, ConflictPolicy, CreditPolicy, ImportBatch, Mark, MarkValue, ReportKind,
+    StudentRecord, StudentResult,
+};
 use crate::{AcademicYear, StudentInfo};
+#[cfg(feature = "sync")]
+use crate::{AttemptSession, MarkAttemptVersion, MarkVersion, ModuleStatus, ResultVersion};
 
 #[cfg(feature = "sync")]
 impl ToSql for AcademicYear {
@@ -68,6 +75,990 @@ impl AcademicYear {
     }
 }
 
+/// Gets the kind and the session of a report stored in the database.
+#[cfg(any(feature = "sync", feature = "async"))]
+fn kind_columns(kind: ReportKind) -> (&'static str, Option<&'static str>) {
+    match kind {
+        ReportKind::Result => ("result", None),
+        ReportKind::Award => ("award", None),
+        ReportKind::Resit(session) => ("resit", Some(session.name())),
+    }
+}
+
+#[cfg(any(feature = "sync", feature = "async"))]
+impl ImportBatch {
+    pub const INSERT_STATEMENT: &'static str = "
+        INSERT INTO ImportBatch
+        (FileName, Sha256, Kind, Session, AcademicYear, Rows, Skipped)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        ";
+
+    /// Add a new [`ImportBatch`] into database using a database connection.
+    ///
+    /// Returns the ID of the batch.
+    #[cfg(feature = "sync")]
+    pub fn insert_db_sync(&self, conn: &mut Connection) -> Result<i64, rusqlite::Error> {
+        let trans = conn.transaction()?;
+        let id = self.insert_db_transaction_sync(&trans)?;
+        trans.commit()?;
+        Ok(id)
+    }
+
+    /// Add a new [`ImportBatch`] into database using a database transaction.
+    /// *Note*: This function does not commit the changes to the database.
+    ///
+    /// Returns the ID of the batch.
+    #[cfg(feature = "sync")]
+    pub fn insert_db_transaction_sync(&self, trans: &Transaction) -> Result<i64, rusqlite::Error> {
+        let (kind, session) = kind_columns(self.kind);
+        trans.execute(
+            Self::INSERT_STATEMENT,
+            params![
+                self.file_name,
+                self.sha256,
+                kind,
+                session,
+                self.academic_year,
+                self.rows,
+                self.skipped,
+            ],
+        )?;
+        Ok(trans.last_insert_rowid())
+    }
+
+    /// Add a new [`ImportBatch`] into database using a database connection.
+    ///
+    /// Returns the ID of the batch.
+    #[cfg(feature = "async")]
+    pub async fn insert_db_async(&self, conn: &mut SqlitePool) -> Result<i64, sqlx::Error> {
+        let mut trans = conn.begin().await?;
+        let id = self.insert_db_transaction_async(&mut trans).await?;
+        trans.commit().await?;
+        Ok(id)
+    }
+
+    /// Add a new [`ImportBatch`] into database using a database transaction.
+    /// *Note*: This function does not commit the changes to the database.
+    ///
+    /// Returns the ID of the batch.
+    #[cfg(feature = "async")]
+    pub async fn insert_db_transaction_async(
+        &self,
+        trans: &mut AsyncTransaction<'_, Sqlite>,
+    ) -> Result<i64, sqlx::Error> {
+        let (kind, session) = kind_columns(self.kind);
+        let result = sqlx::query(Self::INSERT_STATEMENT)
+            .bind(&self.file_name)
+            .bind(&self.sha256)
+            .bind(kind)
+            .bind(session)
+            .bind(self.academic_year.to_string())
+            .bind(self.rows as i64)
+            .bind(self.skipped as i64)
+            .execute(&mut **trans)
+            .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    /// The statement to find the latest import batch with the hash `?1`
+    /// which was not rolled back.
+    const DUPLICATE_STATEMENT: &str = "
+        SELECT ID, FileName, ImportedAt, AcademicYear FROM ImportBatch
+        WHERE Sha256=?1 AND RolledBackAt IS NULL
+        ORDER BY ID DESC LIMIT 1";
+
+    /// Finds the latest import of a file with the same content as the
+    /// [`ImportBatch`], which was not rolled back, using a database
+    /// connection or transaction.
+    #[cfg(feature = "sync")]
+    pub fn find_duplicate_sync(
+        &self,
+        conn: &Connection,
+    ) -> Result<Option<DuplicateImport>, rusqlite::Error> {
+        conn.query_row(Self::DUPLICATE_STATEMENT, params![self.sha256], |row| {
+            Ok(DuplicateImport {
+                batch: row.get(0)?,
+                file_name: row.get(1)?,
+                imported_at: row.get(2)?,
+                academic_year: row.get(3)?,
+            })
+        })
+        .optional()
+    }
+
+    /// Finds the latest import of a file with the same content as the
+    /// [`ImportBatch`], which was not rolled back, using a database
+    /// connection.
+    #[cfg(feature = "async")]
+    pub async fn find_duplicate_async(
+        &self,
+        conn: &mut SqlitePool,
+    ) -> Result<Option<DuplicateImport>, sqlx::Error> {
+        let mut trans = conn.begin().await?;
+        let duplicate = self.find_duplicate_transaction_async(&mut trans).await?;
+        trans.commit().await?;
+        Ok(duplicate)
+    }
+
+    /// Finds the latest import of a file with the same content as the
+    /// [`ImportBatch`], which was not rolled back, using a database
+    /// transaction.
+    #[cfg(feature = "async")]
+    pub async fn find_duplicate_transaction_async(
+        &self,
+        trans: &mut AsyncTransaction<'_, Sqlite>,
+    ) -> Result<Option<DuplicateImport>, sqlx::Error> {
+        let duplicate =
+            sqlx::query_as::<_, (i64, String, String, String)>(Self::DUPLICATE_STATEMENT)
+                .bind(&self.sha256)
+                .fetch_optional(&mut **trans)
+                .await?;
+        Ok(duplicate.map(
+            |(batch, file_name, imported_at, academic_year)| DuplicateImport {
+                batch,
+                file_name,
+                imported_at,
+                academic_year,
+            },
+        ))
+    }
+}
+
+/// The statement to record the time the last row of the import batch `?1` was
+/// written, see [`AsOf::Batch`].
+#[cfg(any(feature = "sync", feature = "async"))]
+const COMPLETE_BATCH_STATEMENT: &str = "
+    UPDATE ImportBatch SET CompletedAt=strftime('%Y-%m-%d %H:%M:%f', 'now')
+    WHERE ID=?1";
+
+/// The changes made to the stored data by inserting the data of a report.
+#[cfg(any(feature = "sync", feature = "async"))]
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertReport {
+    /// The modules found with different credits from the stored credits.
+    pub credit_conflicts: Vec<CreditConflict>,
+    /// The fields of the stored students changed by the data.
+    pub changes: Vec<FieldChange>,
+    /// The stored students with a different intake year from the intake year
+    /// inferred from the data, see [`repair_intake_years`].
+    pub intake_conflicts: Vec<IntakeConflict>,
+    /// The new students inserted with an unknown intake year, as it cannot be
+    /// inferred from the data.
+    pub unknown_intakes: Vec<i64>,
+}
+
+/// The fields of a student updated when a stored student is found again, in
+/// the order of [`STUDENT_FIELDS_STATEMENT`].
+#[cfg(any(feature = "sync", feature = "async"))]
+const STUDENT_FIELDS: [&str; 20] = [
+    "FirstName",
+    "LastName",
+    "Plan",
+    "PlanDesc",
+    "Program",
+    "ProgramDesc",
+    "INTAKE",
+    "CareerNo",
+    "QAA",
+    "CalcModel",
+    "RawMark",
+    "TruncatedMark",
+    "FinalMark",
+    "Borderline",
+    "Calculation",
+    "DegreeAward",
+    "Selected",
+    "ExceptionData",
+    "Recommendation",
+    "GraduationYear",
+];
+
+/// The statement to read the fields of the student `?1` as text, to find the
+/// fields changed by an import.
+#[cfg(any(feature = "sync", feature = "async"))]
+const STUDENT_FIELDS_STATEMENT: &str = "
+    SELECT CAST(FirstName AS TEXT), CAST(LastName AS TEXT), CAST(Plan AS TEXT),
+    CAST(PlanDesc AS TEXT), CAST(Program AS TEXT), CAST(ProgramDesc AS TEXT),
+    CAST(INTAKE AS TEXT), CAST(CareerNo AS TEXT), CAST(QAA AS TEXT), CAST(CalcModel AS TEXT),
+    CAST(RawMark AS TEXT), CAST(TruncatedMark AS TEXT), CAST(FinalMark AS TEXT),
+    CAST(Borderline AS TEXT), CAST(Calculation AS TEXT), CAST(DegreeAward AS TEXT),
+    CAST(Selected AS TEXT), CAST(ExceptionData AS TEXT), CAST(Recommendation AS TEXT),
+    CAST(GraduationYear AS TEXT)
+    FROM StudentInfo WHERE ID=?1";
+
+/// The statement to insert the student `?1` of a result or resit report, or
+/// to update the stored student. The stored fields are overwritten if `?7` is
+/// set, otherwise only the missing fields are filled, and the stored student
+/// is only updated if `?8` is set, see [`ConflictPolicy`].
+#[cfg(any(feature = "sync", feature = "async"))]
+const INSERT_RESULT_STUDENT_STATEMENT: &str = "
+    INSERT INTO StudentInfo
+    (ID, FirstName, LastName, Plan, IntakeYear, ImportBatch)
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+    ON CONFLICT DO UPDATE SET
+    FirstName=CASE WHEN ?7 THEN ?2 ELSE COALESCE(FirstName, ?2) END,
+    LastName=CASE WHEN ?7 THEN ?3 ELSE COALESCE(LastName, ?3) END,
+    Plan=CASE WHEN ?7 THEN ?4 ELSE COALESCE(Plan, ?4) END,
+    ImportBatch=?6
+    WHERE ?8";
+
+#[cfg(any(feature = "sync", feature = "async"))]
+impl ConflictPolicy {
+    /// Checks if the stored fields of a student are overwritten, instead of
+    /// only filling the missing fields.
+    fn overwrites(self) -> bool {
+        matches!(self, ConflictPolicy::Overwrite | ConflictPolicy::Fail)
+    }
+
+    /// Checks if a stored student is updated.
+    fn updates(self) -> bool {
+        self != ConflictPolicy::KeepExisting
+    }
+}
+
+/// Finds the fields of the student `id` changed from `old` to `new`, the
+/// fields before and after inserting the student.
+///
+/// Returns [`Error::StudentConflict`] if a stored field was changed and the
+/// `policy` is [`ConflictPolicy::Fail`].
+#[cfg(any(feature = "sync", feature = "async"))]
+fn student_changes(
+    id: i64,
+    old: Option<Vec<Option<String>>>,
+    new: Option<Vec<Option<String>>>,
+    policy: ConflictPolicy,
+) -> Result<Vec<FieldChange>, Error> {
+    // The student was inserted instead of updated
+    let (Some(old), Some(new)) = (old, new) else {
+        return Ok(vec![]);
+    };
+
+    let changes: Vec<_> = STUDENT_FIELDS
+        .iter()
+        .zip(old.into_iter().zip(new))
+        .filter(|(_, (old, new))| old != new)
+        .map(|(field, (old, new))| FieldChange {
+            id,
+            field: field.to_string(),
+            old,
+            new,
+        })
+        .collect();
+
+    if policy == ConflictPolicy::Fail {
+        if let Some(change) = changes.iter().find(|change| change.old.is_some()) {
+            return Err(Error::StudentConflict(change.clone()));
+        }
+    }
+    Ok(changes)
+}
+
+/// Reads the fields of the student `id` as text using a database transaction.
+#[cfg(feature = "sync")]
+fn student_fields_sync(
+    trans: &Transaction,
+    id: i64,
+) -> Result<Option<Vec<Option<String>>>, rusqlite::Error> {
+    trans
+        .query_row(STUDENT_FIELDS_STATEMENT, params![id], |row| {
+            (0..STUDENT_FIELDS.len()).map(|i| row.get(i)).collect()
+        })
+        .optional()
+}
+
+/// Reads the fields of the student `id` as text using a database transaction.
+#[cfg(feature = "async")]
+async fn student_fields_async(
+    trans: &mut AsyncTransaction<'_, Sqlite>,
+    id: i64,
+) -> Result<Option<Vec<Option<String>>>, sqlx::Error> {
+    let Some(row) = sqlx::query(STUDENT_FIELDS_STATEMENT)
+        .bind(id)
+        .fetch_optional(&mut **trans)
+        .await?
+    else {
+        return Ok(None);
+    };
+    (0..STUDENT_FIELDS.len())
+        .map(|i| row.try_get(i))
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+/// The statement to read the intake in the award report (0B), the stored
+/// intake year and the results of the student `?1`, or of every student if
+/// `?1` is `NULL`, to infer the intake years.
+#[cfg(any(feature = "sync", feature = "async"))]
+const INTAKE_SOURCES_STATEMENT: &str = "
+    SELECT s.ID, CAST(s.INTAKE AS TEXT), s.IntakeYear, r.AcademicYear,
+    CAST(r.YearOfStudy AS TEXT)
+    FROM StudentInfo s LEFT JOIN Result r ON r.ID = s.ID
+    WHERE ?1 IS NULL OR s.ID = ?1
+    ORDER BY s.ID";
+
+/// The statement to update the intake year of the student `?1` to `?2`.
+#[cfg(any(feature = "sync", feature = "async"))]
+const UPDATE_INTAKE_STATEMENT: &str = "UPDATE StudentInfo SET IntakeYear=?2 WHERE ID=?1";
+
+/// A row of [`INTAKE_SOURCES_STATEMENT`], the ID, the intake, the stored
+/// intake year, and the academic year and year of study of a result.
+#[cfg(any(feature = "sync", feature = "async"))]
+type IntakeSource = (
+    i64,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+/// Infers the intake year of every student from the rows of
+/// [`INTAKE_SOURCES_STATEMENT`].
+///
+/// The intake in the award report (0B) is used if it is given, otherwise the
+/// earliest intake year inferred from the results, as a student repeating a
+/// year would be inferred a later intake year.
+///
+/// Returns the students with a different or unknown stored intake year, the
+/// students whose intake year cannot be inferred are skipped.
+#[cfg(any(feature = "sync", feature = "async"))]
+fn infer_intake_years(sources: Vec<IntakeSource>) -> Vec<IntakeConflict> {
+    let mut students: Vec<(i64, Option<String>, Option<AcademicYear>)> = vec![];
+
+    for (id, intake, stored, academic_year, year_of_program) in sources {
+        let inferred = intake
+            .as_deref()
+            .and_then(AcademicYear::from_intake)
+            .or_else(|| {
+                let academic_year: AcademicYear = academic_year?.parse().ok()?;
+                academic_year.intake_of(&year_of_program?)
+            });
+
+        match students.last_mut() {
+            Some((last, _, earliest)) if *last == id => {
+                if let Some(inferred) = inferred {
+                    if earliest.as_ref().is_none_or(|e| inferred.start < e.start) {
+                        *earliest = Some(inferred);
+                    }
+                }
+            }
+            _ => students.push((id, stored, inferred)),
+        }
+    }
+
+    students
+        .into_iter()
+        .filter_map(|(id, stored, inferred)| {
+            let inferred = inferred?.to_string();
+            (stored.as_ref() != Some(&inferred)).then_some(IntakeConflict {
+                id,
+                stored,
+                inferred,
+            })
+        })
+        .collect()
+}
+
+/// Finds the intake conflicts of the student `id`, or of every student if
+/// `id` is `None`, using a database transaction, see [`infer_intake_years`].
+///
+/// The stored intake year is not updated by an import, so an import reports
+/// the intake years which would be updated by [`repair_intake_years`].
+#[cfg(feature = "sync")]
+fn intake_conflicts_sync(
+    trans: &Transaction,
+    id: Option<i64>,
+) -> Result<Vec<IntakeConflict>, rusqlite::Error> {
+    let sources = trans
+        .prepare_cached(INTAKE_SOURCES_STATEMENT)?
+        .query_map(params![id], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(infer_intake_years(sources))
+}
+
+/// Finds the intake conflicts of the student `id`, or of every student if
+/// `id` is `None`, using a database transaction, see [`infer_intake_years`].
+#[cfg(feature = "async")]
+async fn intake_conflicts_async(
+    trans: &mut AsyncTransaction<'_, Sqlite>,
+    id: Option<i64>,
+) -> Result<Vec<IntakeConflict>, sqlx::Error> {
+    let sources: Vec<IntakeSource> = sqlx::query_as(INTAKE_SOURCES_STATEMENT)
+        .bind(id)
+        .fetch_all(&mut **trans)
+        .await?;
+
+    Ok(infer_intake_years(sources))
+}
+
+/// The statement to keep a student `?2` as it was before being updated by the
+/// import batch `?1`.
+#[cfg(any(feature = "sync", feature = "async"))]
+const BACKUP_STUDENT_INFO_STATEMENT: &str = "
+    INSERT OR IGNORE INTO ImportBatchStudentInfo
+    (Batch, ID, LastName, FirstName, CareerNo, Program, ProgramDesc, Plan,
+     PlanDesc, INTAKE, QAA, CalcModel, RawMark, TruncatedMark, FinalMark,
+     Borderline, Calculation, DegreeAward, Selected, ExceptionData,
+     Recommendation, IntakeYear, GraduationYear, ImportBatch)
+    SELECT ?1, ID, LastName, FirstName, CareerNo, Program, ProgramDesc, Plan,
+     PlanDesc, INTAKE, QAA, CalcModel, RawMark, TruncatedMark, FinalMark,
+     Borderline, Calculation, DegreeAward, Selected, ExceptionData,
+     Recommendation, IntakeYear, GraduationYear, ImportBatch
+    FROM StudentInfo
+    WHERE ID=?2 AND ImportBatch IS NOT ?1
+    ";
+
+/// The statements to keep the result of a student `?2` in the academic year
+/// `?3` and its attempts as they were before being updated by the import
+/// batch `?1`.
+#[cfg(any(feature = "sync", feature = "async"))]
+const BACKUP_RESULT_STATEMENTS: [&str; 2] = [
+    "
+    INSERT OR IGNORE INTO ImportBatchResultAttempt
+    (Batch, AcademicYear, ID, Session, Attempt, YearCredits, YearMean,
+     CreditsL3Lt30, CreditsL33039)
+    SELECT ?1, a.AcademicYear, a.ID, a.Session, a.Attempt, a.YearCredits,
+     a.YearMean, a.CreditsL3Lt30, a.CreditsL33039
+    FROM ResultAttempt a
+    JOIN Result r ON r.ID=a.ID AND r.AcademicYear=a.AcademicYear
+    WHERE a.ID=?2 AND a.AcademicYear=?3 AND r.ImportBatch IS NOT ?1
+    ",
+    "
+    INSERT OR IGNORE INTO ImportBatchResult
+    (Batch, AcademicYear, ID, YearOfStudy, AutumnCredits, AutumnMean,
+     SpringCredits, SpringMean, YearCredits, YearMean, Progression, Remarks,
+     SummerCredits, SummerMean, ImportBatch)
+    SELECT ?1, AcademicYear, ID, YearOfStudy, AutumnCredits, AutumnMean,
+     SpringCredits, SpringMean, YearCredits, YearMean, Progression, Remarks,
+     SummerCredits, SummerMean, ImportBatch
+    FROM Result
+    WHERE ID=?2 AND AcademicYear=?3 AND ImportBatch IS NOT ?1
+    ",
+];
+
+/// The statements to keep the mark of a student `?2` in the academic year
+/// `?3` of the module `?4` and its attempts as they were before being updated
+/// by the import batch `?1`.
+#[cfg(any(feature = "sync", feature = "async"))]
+const BACKUP_MARK_STATEMENTS: [&str; 2] = [
+    "
+    INSERT OR IGNORE INTO ImportBatchMarkAttempt
+    (Batch, ID, AcademicYear, Module, Attempt, Session, Mark, MarkCode,
+     Capped, Status, ImportBatch)
+    SELECT ?1, a.ID, a.AcademicYear, a.Module, a.Attempt, a.Session, a.Mark,
+     a.MarkCode, a.Capped, a.Status, a.ImportBatch
+    FROM MarkAttempt a
+    JOIN Mark m
+     ON m.ID=a.ID AND m.AcademicYear=a.AcademicYear AND m.Module=a.Module
+    WHERE a.ID=?2 AND a.AcademicYear=?3 AND a.Module=?4
+     AND m.ImportBatch IS NOT ?1
+    ",
+    "
+    INSERT OR IGNORE INTO ImportBatchMark
+    (Batch, ID, AcademicYear, Mark, MarkCode, Fill, Extra, Module, Status,
+     ImportBatch)
+    SELECT ?1, ID, AcademicYear, Mark, MarkCode, Fill, Extra, Module, Status,
+     ImportBatch
+    FROM Mark
+    WHERE ID=?2 AND AcademicYear=?3 AND Module=?4 AND ImportBatch IS NOT ?1
+    ",
+];
+
+/// The statements to keep the module `?2` and its credit conflict in the
+/// academic year `?3` between the stored credits `?4` and the incoming credits
+/// `?5` as they were before being updated by the import batch `?1`.
+#[cfg(any(feature = "sync", feature = "async"))]
+const BACKUP_MODULE_STATEMENTS: [&str; 2] = [
+    "
+    INSERT OR IGNORE INTO ImportBatchModule
+    (Batch, Code, Credit, Name, ImportBatch)
+    SELECT ?1, Code, Credit, Name, ImportBatch
+    FROM Module
+    WHERE Code=?2 AND ImportBatch IS NOT ?1
+    ",
+    "
+    INSERT OR IGNORE INTO ImportBatchModuleCreditConflict
+    (Batch, Module, AcademicYear, StoredCredit, IncomingCredit, Resolution,
+     DetectedAt, ImportBatch)
+    SELECT ?1, Module, AcademicYear, StoredCredit, IncomingCredit, Resolution,
+     DetectedAt, ImportBatch
+    FROM ModuleCreditConflict
+    WHERE Module=?2 AND AcademicYear=?3 AND StoredCredit=?4
+     AND IncomingCredit=?5 AND ImportBatch IS NOT ?1
+    ",
+];
+
+/// The statement to insert a new module `?1` with the credits `?2` by the
+/// import batch `?3`.
+#[cfg(any(feature = "sync", feature = "async"))]
+const INSERT_MODULE_STATEMENT: &str = "
+    INSERT INTO Module
+    (Code, Credit, ImportBatch) VALUES (?1, ?2, ?3)
+    ";
+
+/// The statement to overwrite the credits of the module `?1` with `?2` by the
+/// import batch `?3`.
+#[cfg(any(feature = "sync", feature = "async"))]
+const UPDATE_MODULE_CREDIT_STATEMENT: &str = "
+    UPDATE Module SET Credit=?2, ImportBatch=?3
+    WHERE Code=?1
+    ";
+
+/// The statement to record the credit conflict of a module found by the
+/// import batch `?6`.
+#[cfg(any(feature = "sync", feature = "async"))]
+const INSERT_CREDIT_CONFLICT_STATEMENT: &str = "
+    INSERT OR REPLACE INTO ModuleCreditConflict
+    (Module, AcademicYear, StoredCredit, IncomingCredit, Resolution,
+     ImportBatch)
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+    ";
+
+/// The statement to insert or update the attempt `?4` of the mark of a
+/// student `?1` in the academic year `?2` of the module `?3` by the import
+/// batch `?10`.
+///
+/// The attempts are updated in place, so their history only changes when
+/// their values change, see [`DELETE_MARK_ATTEMPTS_STATEMENT`].
+#[cfg(any(feature = "sync", feature = "async"))]
+const INSERT_MARK_ATTEMPT_STATEMENT: &str = "
+    INSERT INTO MarkAttempt
+    (ID, AcademicYear, Module, Attempt, Session, Mark, MarkCode, Capped,
+     Status, ImportBatch)
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+    ON CONFLICT DO UPDATE SET
+    Session=?5, Mark=?6, MarkCode=?7, Capped=?8, Status=?9, ImportBatch=?10
+    ";
+
+/// The statement to delete the attempts of the mark of a student `?1` in the
+/// academic year `?2` of the module `?3` from the attempt `?4`, i.e. the
+/// attempts which are no longer in the imported mark.
+#[cfg(any(feature = "sync", feature = "async"))]
+const DELETE_MARK_ATTEMPTS_STATEMENT: &str = "
+    DELETE FROM MarkAttempt
+    WHERE ID=?1 AND AcademicYear=?2 AND Module=?3 AND Attempt>=?4
+    ";
+
+/// The statements to roll back the import batch `?1`.
+///
+/// The updated rows are restored and the inserted rows are deleted, except
+/// the rows updated by a later batch. Students still referenced by the rows
+/// of the later batches are kept.
+#[cfg(any(feature = "sync", feature = "async"))]
+const ROLLBACK_STATEMENTS: [&str; 22] = [
+    // Restoring the attempts of the marks before restoring the marks
+    "
+    DELETE FROM MarkAttempt
+    WHERE EXISTS (
+        SELECT 1 FROM Mark m
+        WHERE m.ImportBatch=?1 AND m.ID=MarkAttempt.ID
+         AND m.AcademicYear=MarkAttempt.AcademicYear
+         AND m.Module=MarkAttempt.Module
+    )
+    ",
+    "
+    INSERT INTO MarkAttempt
+    (ID, AcademicYear, Module, Attempt, Session, Mark, MarkCode, Capped,
+     Status, ImportBatch)
+    SELECT s.ID, s.AcademicYear, s.Module, s.Attempt, s.Session, s.Mark,
+     s.MarkCode, s.Capped, s.Status, s.ImportBatch
+    FROM ImportBatchMarkAttempt s
+    WHERE s.Batch=?1 AND EXISTS (
+        SELECT 1 FROM Mark m
+        WHERE m.ImportBatch=?1 AND m.ID=s.ID
+         AND m.AcademicYear=s.AcademicYear AND m.Module=s.Module
+    )
+    ",
+    // Updating the marks in place to keep their component marks
+    "
+    UPDATE Mark
+    SET Mark=s.Mark, MarkCode=s.MarkCode, Fill=s.Fill, Extra=s.Extra,
+     Status=s.Status, ImportBatch=s.ImportBatch
+    FROM ImportBatchMark s
+    WHERE s.Batch=?1 AND Mark.ImportBatch=?1 AND s.ID=Mark.ID
+     AND s.AcademicYear=Mark.AcademicYear AND s.Module=Mark.Module
+    ",
+    "DELETE FROM Mark WHERE ImportBatch=?1",
+    // Restoring the credit conflicts and the credits of the modules, the
+    // modules inserted by the batch are deleted once they have no marks
+    "
+    INSERT OR REPLACE INTO ModuleCreditConflict
+    (Module, AcademicYear, StoredCredit, IncomingCredit, Resolution,
+     DetectedAt, ImportBatch)
+    SELECT s.Module, s.AcademicYear, s.StoredCredit, s.IncomingCredit,
+     s.Resolution, s.DetectedAt, s.ImportBatch
+    FROM ImportBatchModuleCreditConflict s
+    WHERE s.Batch=?1 AND EXISTS (
+        SELECT 1 FROM ModuleCreditConflict c
+        WHERE c.ImportBatch=?1 AND c.Module=s.Module
+         AND c.AcademicYear=s.AcademicYear AND c.StoredCredit=s.StoredCredit
+         AND c.IncomingCredit=s.IncomingCredit
+    )
+    ",
+    "DELETE FROM ModuleCreditConflict WHERE ImportBatch=?1",
+    "
+    UPDATE Module
+    SET Credit=s.Credit, Name=s.Name, ImportBatch=s.ImportBatch
+    FROM ImportBatchModule s
+    WHERE s.Batch=?1 AND Module.ImportBatch=?1 AND s.Code=Module.Code
+    ",
+    "
+    DELETE FROM Module
+    WHERE ImportBatch=?1
+     AND NOT EXISTS (SELECT 1 FROM Mark m WHERE m.Module=Module.Code)
+     AND NOT EXISTS (
+        SELECT 1 FROM ModuleCreditConflict c WHERE c.Module=Module.Code
+     )
+    ",
+    // Restoring the attempts of the results before restoring the results
+    "
+    DELETE FROM ResultAttempt
+    WHERE EXISTS (
+        SELECT 1 FROM Result r
+        WHERE r.ImportBatch=?1 AND r.ID=ResultAttempt.ID
+         AND r.AcademicYear=ResultAttempt.AcademicYear
+    )
+    ",
+    "
+    INSERT INTO ResultAttempt
+    (AcademicYear, ID, Session, Attempt, YearCredits, YearMean,
+     CreditsL3Lt30, CreditsL33039)
+    SELECT s.AcademicYear, s.ID, s.Session, s.Attempt, s.YearCredits,
+     s.YearMean, s.CreditsL3Lt30, s.CreditsL33039
+    FROM ImportBatchResultAttempt s
+    WHERE s.Batch=?1 AND EXISTS (
+        SELECT 1 FROM Result r
+        WHERE r.ImportBatch=?1 AND r.ID=s.ID AND r.AcademicYear=s.AcademicYear
+    )
+    ",
+    "
+    INSERT OR REPLACE INTO Result
+    (AcademicYear, ID, YearOfStudy, AutumnCredits, AutumnMean, SpringCredits,
+     SpringMean, YearCredits, YearMean, Progression, Remarks, SummerCredits,
+     SummerMean, ImportBatch)
+    SELECT s.AcademicYear, s.ID, s.YearOfStudy, s.AutumnCredits, s.AutumnMean,
+     s.SpringCredits, s.SpringMean, s.YearCredits, s.YearMean, s.Progression,
+     s.Remarks, s.SummerCredits, s.SummerMean, s.ImportBatch
+    FROM ImportBatchResult s
+    WHERE s.Batch=?1 AND EXISTS (
+        SELECT 1 FROM Result r
+        WHERE r.ImportBatch=?1 AND r.ID=s.ID AND r.AcademicYear=s.AcademicYear
+    )
+    ",
+    "DELETE FROM Result WHERE ImportBatch=?1",
+    "
+    INSERT OR REPLACE INTO StudentInfo
+    (ID, LastName, FirstName, CareerNo, Program, ProgramDesc, Plan, PlanDesc,
+     INTAKE, QAA, CalcModel, RawMark, TruncatedMark, FinalMark, Borderline,
+     Calculation, DegreeAward, Selected, ExceptionData, Recommendation,
+     IntakeYear, GraduationYear, ImportBatch)
+    SELECT s.ID, s.LastName, s.FirstName, s.CareerNo, s.Program,
+     s.ProgramDesc, s.Plan, s.PlanDesc, s.INTAKE, s.QAA, s.CalcModel,
+     s.RawMark, s.TruncatedMark, s.FinalMark, s.Borderline, s.Calculation,
+     s.DegreeAward, s.Selected, s.ExceptionData, s.Recommendation,
+     s.IntakeYear, s.GraduationYear, s.ImportBatch
+    FROM ImportBatchStudentInfo s
+    WHERE s.Batch=?1 AND EXISTS (
+        SELECT 1 FROM StudentInfo c WHERE c.ImportBatch=?1 AND c.ID=s.ID
+    )
+    ",
+    "
+    DELETE FROM StudentInfo
+    WHERE ImportBatch=?1
+     AND NOT EXISTS (SELECT 1 FROM Result r WHERE r.ID=StudentInfo.ID)
+     AND NOT EXISTS (SELECT 1 FROM ResultAttempt a WHERE a.ID=StudentInfo.ID)
+     AND NOT EXISTS (SELECT 1 FROM Mark m WHERE m.ID=StudentInfo.ID)
+    ",
+    // The backups are no longer needed
+    "DELETE FROM ImportBatchMarkAttempt WHERE Batch=?1",
+    "DELETE FROM ImportBatchMark WHERE Batch=?1",
+    "DELETE FROM ImportBatchModuleCreditConflict WHERE Batch=?1",
+    "DELETE FROM ImportBatchModule WHERE Batch=?1",
+    "DELETE FROM ImportBatchResultAttempt WHERE Batch=?1",
+    "DELETE FROM ImportBatchResult WHERE Batch=?1",
+    "DELETE FROM ImportBatchStudentInfo WHERE Batch=?1",
+    "UPDATE ImportBatch SET RolledBackAt=CURRENT_TIMESTAMP WHERE ID=?1",
+];
+
+/// Checks if the import batch `batch_id` can be rolled back from the time it
+/// was rolled back, [`None`] if the batch does not exist.
+#[cfg(any(feature = "sync", feature = "async"))]
+fn check_rollback(batch_id: i64, rolled_back: Option<Option<String>>) -> Result<(), Error> {
+    match rolled_back {
+        None => Err(Error::Rollback(RollbackError::NotFound(batch_id))),
+        Some(Some(time)) => Err(Error::Rollback(RollbackError::RolledBack(batch_id, time))),
+        Some(None) => Ok(()),
+    }
+}
+
+/// Rolls back the import batch `batch_id` using a database connection.
+///
+/// The rows updated by the batch are restored and the rows inserted by the
+/// batch are deleted, except the rows updated by a later batch.
+#[cfg(feature = "sync")]
+pub fn rollback_import(conn: &mut Connection, batch_id: i64) -> Result<(), Error> {
+    let trans = conn.transaction()?;
+    rollback_import_transaction(&trans, batch_id)?;
+    trans.commit()?;
+    Ok(())
+}
+
+/// Rolls back the import batch `batch_id` using a database transaction.
+/// *Note*: This function does not commit the changes to the database.
+#[cfg(feature = "sync")]
+pub fn rollback_import_transaction(trans: &Transaction, batch_id: i64) -> Result<(), Error> {
+    let rolled_back = trans
+        .query_row(
+            "SELECT RolledBackAt FROM ImportBatch WHERE ID=?1",
+            params![batch_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    check_rollback(batch_id, rolled_back)?;
+
+    for statement in ROLLBACK_STATEMENTS {
+        trans.execute(statement, params![batch_id])?;
+    }
+
+    Ok(())
+}
+
+/// Rolls back the import batch `batch_id` using a database connection.
+///
+/// The rows updated by the batch are restored and the rows inserted by the
+/// batch are deleted, except the rows updated by a later batch.
+#[cfg(feature = "async")]
+pub async fn rollback_import_async(conn: &mut SqlitePool, batch_id: i64) -> Result<(), Error> {
+    let mut trans = conn.begin().await?;
+    rollback_import_transaction_async(&mut trans, batch_id).await?;
+    trans.commit().await?;
+    Ok(())
+}
+
+/// Rolls back the import batch `batch_id` using a database transaction.
+/// *Note*: This function does not commit the changes to the database.
+#[cfg(feature = "async")]
+pub async fn rollback_import_transaction_async(
+    trans: &mut AsyncTransaction<'_, Sqlite>,
+    batch_id: i64,
+) -> Result<(), Error> {
+    let rolled_back =
+        sqlx::query_as::<_, (Option<String>,)>("SELECT RolledBackAt FROM ImportBatch WHERE ID=?1")
+            .bind(batch_id)
+            .fetch_optional(&mut **trans)
+            .await?;
+    check_rollback(batch_id, rolled_back.map(|(time,)| time))?;
+
+    for statement in ROLLBACK_STATEMENTS {
+        sqlx::query(statement)
+            .bind(batch_id)
+            .execute(&mut **trans)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// The statement to find the time of the history as of the import batch `?1`,
+/// the import time is used for the batches without a completion time.
+#[cfg(any(feature = "sync", feature = "async"))]
+const BATCH_TIME_STATEMENT: &str = "
+    SELECT COALESCE(CompletedAt, strftime('%Y-%m-%d %H:%M:%f', ImportedAt))
+    FROM ImportBatch WHERE ID=?1";
+
+/// The statement to find the versions of the results of the student `?1`
+/// valid at the time `?2`.
+#[cfg(any(feature = "sync", feature = "async"))]
+const RESULT_HISTORY_STATEMENT: &str = "
+    SELECT AcademicYear, YearOfStudy, AutumnCredits, AutumnMean, SpringCredits, SpringMean,
+    SummerCredits, SummerMean, YearCredits, YearMean, Progression, Remarks, Batch, ValidFrom,
+    ValidTo
+    FROM ResultHistory
+    WHERE ID=?1 AND ValidFrom<=?2 AND (ValidTo IS NULL OR ValidTo>?2)
+    ORDER BY AcademicYear";
+
+/// The statement to find the versions of the marks of the student `?1` valid
+/// at the time `?2`.
+#[cfg(any(feature = "sync", feature = "async"))]
+const MARK_HISTORY_STATEMENT: &str = "
+    SELECT AcademicYear, Module, Mark, MarkCode, Fill, Status, Batch, ValidFrom, ValidTo
+    FROM MarkHistory
+    WHERE ID=?1 AND ValidFrom<=?2 AND (ValidTo IS NULL OR ValidTo>?2)
+    ORDER BY AcademicYear, Module";
+
+/// The statement to find the versions of the attempts of the marks of the
+/// student `?1` valid at the time `?2`.
+#[cfg(any(feature = "sync", feature = "async"))]
+const MARK_ATTEMPT_HISTORY_STATEMENT: &str = "
+    SELECT AcademicYear, Module, Attempt, Session, Mark, MarkCode, Capped, Status, Batch,
+    ValidFrom, ValidTo
+    FROM MarkAttemptHistory
+    WHERE ID=?1 AND ValidFrom<=?2 AND (ValidTo IS NULL OR ValidTo>?2)
+    ORDER BY AcademicYear, Module, Attempt";
+
+/// Formats the time of the history as stored in the database.
+#[cfg(any(feature = "sync", feature = "async"))]
+fn history_time(time: &chrono::NaiveDateTime) -> String {
+    time.format("%Y-%m-%d %H:%M:%S%.3f").to_string()
+}
+
+#[cfg(feature = "sync")]
+impl ResultVersion {
+    fn from_row(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(Self {
+            academic_year: row.get("AcademicYear")?,
+            year_of_study: row.get("YearOfStudy")?,
+            autumn_credits: row.get("AutumnCredits")?,
+            autumn_mean: row.get("AutumnMean")?,
+            spring_credits: row.get("SpringCredits")?,
+            spring_mean: row.get("SpringMean")?,
+            summer_credits: row.get("SummerCredits")?,
+            summer_mean: row.get("SummerMean")?,
+            year_credits: row.get("YearCredits")?,
+            year_mean: row.get("YearMean")?,
+            progression: row.get("Progression")?,
+            remarks: row.get("Remarks")?,
+            batch: row.get("Batch")?,
+            valid_from: row.get("ValidFrom")?,
+            valid_to: row.get("ValidTo")?,
+        })
+    }
+}
+
+#[cfg(feature = "sync")]
+impl MarkAttemptVersion {
+    fn from_row(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(Self {
+            academic_year: row.get("AcademicYear")?,
+            module: row.get("Module")?,
+            attempt: row.get("Attempt")?,
+            session: row.get("Session")?,
+            mark: row.get("Mark")?,
+            mark_code: row.get("MarkCode")?,
+            capped: row.get("Capped")?,
+            status: row.get("Status")?,
+            batch: row.get("Batch")?,
+            valid_from: row.get("ValidFrom")?,
+            valid_to: row.get("ValidTo")?,
+        })
+    }
+}
+
+#[cfg(feature = "sync")]
+impl MarkVersion {
+    fn from_row(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(Self {
+            academic_year: row.get("AcademicYear")?,
+            module: row.get("Module")?,
+            mark: row.get("Mark")?,
+            mark_code: row.get("MarkCode")?,
+            fill: row.get("Fill")?,
+            status: row.get("Status")?,
+            batch: row.get("Batch")?,
+            valid_from: row.get("ValidFrom")?,
+            valid_to: row.get("ValidTo")?,
+        })
+    }
+}
+
+/// Reads the results, marks and attempts of the student `id` as of a point in
+/// the history of the database using a database connection or transaction.
+///
+/// The history as of an import batch is the history right after the last row
+/// of the batch was written, so it includes the batch even if it was rolled
+/// back later.
+#[cfg(feature = "sync")]
+pub fn student_record_as_of(
+    conn: &Connection,
+    id: i64,
+    as_of: AsOf,
+) -> Result<StudentRecord, Error> {
+    let time: String = match as_of {
+        AsOf::Time(time) => history_time(&time),
+        AsOf::Batch(batch) => conn
+            .query_row(BATCH_TIME_STATEMENT, params![batch], |row| row.get(0))
+            .optional()?
+            .ok_or(Error::ImportNotFound(batch))?,
+    };
+
+    let results = conn
+        .prepare(RESULT_HISTORY_STATEMENT)?
+        .query_map(params![id, time], ResultVersion::from_row)?
+        .collect::<Result<_, _>>()?;
+    let marks = conn
+        .prepare(MARK_HISTORY_STATEMENT)?
+        .query_map(params![id, time], MarkVersion::from_row)?
+        .collect::<Result<_, _>>()?;
+    let attempts = conn
+        .prepare(MARK_ATTEMPT_HISTORY_STATEMENT)?
+        .query_map(params![id, time], MarkAttemptVersion::from_row)?
+        .collect::<Result<_, _>>()?;
+
+    Ok(StudentRecord {
+        id,
+        results,
+        marks,
+        attempts,
+    })
+}
+
+/// Reads the results, marks and attempts of the student `id` as of a point in
+/// the history of the database using a database connection.
+///
+/// The history as of an import batch is the history right after the last row
+/// of the batch was written, so it includes the batch even if it was rolled
+/// back later.
+#[cfg(feature = "async")]
+pub async fn student_record_as_of_async(
+    conn: &mut SqlitePool,
+    id: i64,
+    as_of: AsOf,
+) -> Result<StudentRecord, Error> {
+    let time = match as_of {
+        AsOf::Time(time) => history_time(&time),
+        AsOf::Batch(batch) => {
+            sqlx::query_as::<_, (String,)>(BATCH_TIME_STATEMENT)
+                .bind(batch)
+                .fetch_optional(&*conn)
+                .await?
+                .ok_or(Error::ImportNotFound(batch))?
+                .0
+        }
+    };
+
+    let results = sqlx::query_as(RESULT_HISTORY_STATEMENT)
+        .bind(id)
+        .bind(&time)
+        .fetch_all(&*conn)
+        .await?;
+    let marks = sqlx::query_as(MARK_HISTORY_STATEMENT)
+        .bind(id)
+        .bind(&time)
+        .fetch_all(&*conn)
+        .await?;
+    let attempts = sqlx::query_as(MARK_ATTEMPT_HISTORY_STATEMENT)
+        .bind(id)
+        .bind(&time)
+        .fetch_all(&*conn)
+        .await?;
+
+    Ok(StudentRecord {
+        id,
+        results,
+        marks,
+        attempts,
+    })
+}
+
 #[cfg(feature = "sync")]
 impl ToSql for ModuleStatus {
     fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
@@ -75,47 +1066,150 @@ impl ToSql for ModuleStatus {
     }
 }
 
+#[cfg(feature = "sync")]
+impl ToSql for AttemptSession {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Owned(self.to_string().into()))
+    }
+}
+
+/// Gets the session of a [`StudentResult`] stored in the ResultAttempt table,
+/// `Main` for the result report (0A).
+#[cfg(any(feature = "sync", feature = "async"))]
+fn session_name(result: &StudentResult) -> &'static str {
+    result.session.map_or("Main", |session| session.name())
+}
+
+/// Compares the credits of `module` with the `stored` credits of the module.
+///
+/// Returns the conflict if the credits are different, or the conflict as an
+/// error if the `policy` is [`CreditPolicy::Fail`].
+#[cfg(any(feature = "sync", feature = "async"))]
+fn credit_conflict(
+    module: &Mark,
+    stored: i64,
+    academic_year: &AcademicYear,
+    policy: CreditPolicy,
+) -> Result<Option<CreditConflict>, Error> {
+    if module.credit == stored {
+        return Ok(None);
+    }
+
+    let conflict = CreditConflict {
+        module: module.code.clone(),
+        academic_year: academic_year.to_string(),
+        stored,
+        incoming: module.credit,
+    };
+    match policy {
+        CreditPolicy::Fail => Err(Error::CreditConflict(conflict)),
+        CreditPolicy::Keep | CreditPolicy::Overwrite => Ok(Some(conflict)),
+    }
+}
+
 /// Insert [`StudentResult`] into a database using a database connection.
+///
+/// Modules with different credits from the stored credits are resolved with
+/// the `credit_policy` and students with different information from the
+/// stored information with the `conflict_policy`, the conflicts and the
+/// changed fields are returned. The inserted and updated rows are linked to
+/// the import batch `batch`, see [`ImportBatch`].
+///
+/// New students are inserted with the intake year inferred from their year of
+/// study in the `academic_year`, or an unknown intake year if it cannot be
+/// inferred, the stored students with a different intake year are returned as
+/// intake conflicts.
+///
+/// Returns [`Error::MissingAttempts`] if a module has no attempts, as it has
+/// no mark to be stored.
 #[cfg(feature = "sync")]
 pub fn insert_student_result(
     conn: &mut Connection,
     data: &[StudentResult],
-    intake: &AcademicYear,
-) -> Result<(), rusqlite::Error> {
+    academic_year: &AcademicYear,
+    credit_policy: CreditPolicy,
+    conflict_policy: ConflictPolicy,
+    batch: i64,
+) -> Result<InsertReport, Error> {
     let trans = conn.transaction()?;
-    insert_student_result_transaction(&trans, data, intake)?;
+    let report = insert_student_result_transaction(
+        &trans,
+        data,
+        academic_year,
+        credit_policy,
+        conflict_policy,
+        batch,
+    )?;
     trans.commit()?;
-    Ok(())
+    Ok(report)
 }
 
 /// Insert [`StudentResult`] into database using a database transaction.
 /// *Note*: This function does not commit the changes to the database.
+///
+/// Modules with different credits from the stored credits are resolved with
+/// the `credit_policy` and students with different information from the
+/// stored information with the `conflict_policy`, the conflicts and the
+/// changed fields are returned. The inserted and updated rows are linked to
+/// the import batch `batch`, see [`ImportBatch`].
+///
+/// New students are inserted with the intake year inferred from their year of
+/// study in the `academic_year`, or an unknown intake year if it cannot be
+/// inferred, the stored students with a different intake year are returned as
+/// intake conflicts.
+///
+/// Returns [`Error::MissingAttempts`] if a module has no attempts, as it has
+/// no mark to be stored.
 #[cfg(feature = "sync")]
 pub fn insert_student_result_transaction(
     trans: &Transaction,
     data: &[StudentResult],
-    intake: &AcademicYear,
-) -> Result<(), rusqlite::Error> {
+    academic_year: &AcademicYear,
+    credit_policy: CreditPolicy,
+    conflict_policy: ConflictPolicy,
+    batch: i64,
+) -> Result<InsertReport, Error> {
+    let mut backup_result_attempts = trans.prepare(BACKUP_RESULT_STATEMENTS[0])?;
+    let mut backup_result = trans.prepare(BACKUP_RESULT_STATEMENTS[1])?;
     let mut insert_result = trans.prepare(
         "INSERT OR REPLACE INTO Result
          (ID, AcademicYear, YearOfStudy, AutumnCredits, AutumnMean,
           SpringCredits, SpringMean, YearCredits, YearMean, Progression,
-          Remarks)
+          Remarks, SummerCredits, SummerMean, ImportBatch)
          VALUES 
-         (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
-    )?;
-    let mut insert_student = trans.prepare(
-        "INSERT OR IGNORE INTO StudentInfo
-         (ID, FirstName, LastName, Plan, IntakeYear) VALUES (?1, ?2, ?3, ?4, ?5)",
+         (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
     )?;
-    let mut insert_module = trans.prepare(
-        "INSERT OR IGNORE INTO Module
-         (Code, Credit) VALUES (?1, ?2)",
+    let mut backup_student = trans.prepare(BACKUP_STUDENT_INFO_STATEMENT)?;
+    let mut insert_student = trans.prepare(INSERT_RESULT_STUDENT_STATEMENT)?;
+    let mut get_module_credit = trans.prepare(
+        "SELECT Credit FROM Module
+         WHERE Code=?1",
     )?;
+    let mut insert_module = trans.prepare(INSERT_MODULE_STATEMENT)?;
+    let mut backup_module = trans.prepare(BACKUP_MODULE_STATEMENTS[0])?;
+    let mut update_module_credit = trans.prepare(UPDATE_MODULE_CREDIT_STATEMENT)?;
+    let mut backup_credit_conflict = trans.prepare(BACKUP_MODULE_STATEMENTS[1])?;
+    let mut insert_credit_conflict = trans.prepare(INSERT_CREDIT_CONFLICT_STATEMENT)?;
+    let mut backup_mark_attempts = trans.prepare(BACKUP_MARK_STATEMENTS[0])?;
+    let mut backup_mark = trans.prepare(BACKUP_MARK_STATEMENTS[1])?;
     let mut insert_mark = trans.prepare(
-        "INSERT OR REPLACE INTO Mark
-         (ID, Module, Mark, Retake1, Retake2, Status, Fill)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        "INSERT INTO Mark
+         (ID, AcademicYear, Module, Mark, MarkCode, Status, Fill, ImportBatch)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT DO UPDATE SET
+         Mark=?4, MarkCode=?5, Status=?6, Fill=?7, ImportBatch=?8",
+    )?;
+    let mut delete_mark_attempts = trans.prepare(DELETE_MARK_ATTEMPTS_STATEMENT)?;
+    let mut insert_mark_attempt = trans.prepare(INSERT_MARK_ATTEMPT_STATEMENT)?;
+    let mut delete_attempts = trans.prepare(
+        "DELETE FROM ResultAttempt
+         WHERE ID=?1 AND AcademicYear=?2 AND Session=?3",
+    )?;
+    let mut insert_attempt = trans.prepare(
+        "INSERT INTO ResultAttempt
+         (ID, AcademicYear, Session, Attempt, YearCredits, YearMean,
+          CreditsL3Lt30, CreditsL33039)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
     )?;
     let mut colour_insert = trans.prepare(
         "
@@ -135,19 +1229,41 @@ pub fn insert_student_result_transaction(
         WHERE Alpha=?1 AND Red=?2 AND Green=?3 AND Blue=?4
         ",
     )?;
+    let mut conflicts = vec![];
+    let mut changes = vec![];
+    let mut intake_conflicts = vec![];
+    let mut unknown_intakes = vec![];
 
     for result in data {
+        let id = result.student_info.id;
+        let intake = academic_year.intake_of(&result.year_of_program);
+        if let Some(intake) = &intake {
+            intake.insert_db_transaction_sync(trans)?;
+        }
+
+        let old = student_fields_sync(trans, id)?;
+        if old.is_none() && intake.is_none() {
+            unknown_intakes.push(id);
+        }
+        backup_student.execute(params![batch, id])?;
         insert_student.execute(params![
-            result.student_info.id,
+            id,
             result.student_info.first_name,
             result.student_info.last_name,
             result.student_info.plan,
             intake,
+            batch,
+            conflict_policy.overwrites(),
+            conflict_policy.updates(),
         ])?;
+        let new = student_fields_sync(trans, id)?;
+        changes.extend(student_changes(id, old, new, conflict_policy)?);
 
+        backup_result_attempts.execute(params![batch, result.student_info.id, academic_year])?;
+        backup_result.execute(params![batch, result.student_info.id, academic_year])?;
         insert_result.insert(params![
             result.student_info.id,
-            intake,
+            academic_year,
             result.year_of_program,
             result.autumn_credit,
             result.autumn_mean,
@@ -157,10 +1273,70 @@ pub fn insert_student_result_transaction(
             result.year_prog_average,
             result.progression,
             result.remarks,
+            result.summer_credit,
+            result.summer_mean,
+            batch,
         ])?;
+        intake_conflicts.extend(intake_conflicts_sync(trans, Some(id))?);
+
+        // Replacing the attempts of the session in case the report is
+        // imported again
+        let session = session_name(result);
+        delete_attempts.execute(params![result.student_info.id, academic_year, session])?;
+        for (attempt, figures) in result.attempts.iter().enumerate() {
+            insert_attempt.execute(params![
+                result.student_info.id,
+                academic_year,
+                session,
+                attempt,
+                figures.year_credit,
+                figures.year_prog_average,
+                figures.credits_l3_lt30,
+                figures.credits_l3_30_39,
+            ])?;
+        }
 
         for module in &result.modules {
-            insert_module.execute(params![module.code, module.credit])?;
+            let stored: Option<i64> = get_module_credit
+                .query_row(params![module.code], |row| row.get(0))
+                .optional()?;
+            match stored {
+                Some(stored) => {
+                    if let Some(conflict) =
+                        credit_conflict(module, stored, academic_year, credit_policy)?
+                    {
+                        if credit_policy == CreditPolicy::Overwrite {
+                            backup_module.execute(params![batch, module.code])?;
+                            update_module_credit.execute(params![
+                                module.code,
+                                module.credit,
+                                batch
+                            ])?;
+                        }
+                        backup_credit_conflict.execute(params![
+                            batch,
+                            conflict.module,
+                            academic_year,
+                            conflict.stored,
+                            conflict.incoming,
+                        ])?;
+                        insert_credit_conflict.execute(params![
+                            conflict.module,
+                            academic_year,
+                            conflict.stored,
+                            conflict.incoming,
+                            credit_policy.to_string(),
+                            batch,
+                        ])?;
+                        if !conflicts.contains(&conflict) {
+                            conflicts.push(conflict);
+                        }
+                    }
+                }
+                None => {
+                    insert_module.execute(params![module.code, module.credit, batch])?;
+                }
+            }
             let colour_id: Option<i64> = match &module.fill {
                 Some(fill) => {
                     colour_insert.execute(params![fill.alpha, fill.red, fill.green, fill.blue,])?;
@@ -172,65 +1348,182 @@ pub fn insert_student_result_transaction(
                 None => None,
             };
 
+            backup_mark_attempts.execute(params![
+                batch,
+                result.student_info.id,
+                academic_year,
+                module.code
+            ])?;
+            backup_mark.execute(params![
+                batch,
+                result.student_info.id,
+                academic_year,
+                module.code
+            ])?;
+            let first_mark = module.first_mark().ok_or_else(|| Error::MissingAttempts {
+                id: result.student_info.id,
+                module: module.code.clone(),
+            })?;
             insert_mark.insert(params![
                 result.student_info.id,
+                academic_year,
                 module.code,
-                module.mark,
-                module.retake1,
-                module.retake2,
+                first_mark.as_f64(),
+                first_mark.code(),
                 module.status,
-                colour_id
+                colour_id,
+                batch,
+            ])?;
+
+            // Replacing the attempts in case the report is imported again
+            for (i, attempt) in module.attempts.iter().enumerate() {
+                insert_mark_attempt.execute(params![
+                    result.student_info.id,
+                    academic_year,
+                    module.code,
+                    i,
+                    attempt.session,
+                    attempt.mark.as_f64(),
+                    attempt.mark.code(),
+                    attempt.capped,
+                    attempt.status,
+                    batch,
+                ])?;
+            }
+            delete_mark_attempts.execute(params![
+                result.student_info.id,
+                academic_year,
+                module.code,
+                module.attempts.len(),
             ])?;
         }
     }
 
-    Ok(())
+    trans.execute(COMPLETE_BATCH_STATEMENT, params![batch])?;
+    Ok(InsertReport {
+        credit_conflicts: conflicts,
+        changes,
+        intake_conflicts,
+        unknown_intakes,
+    })
 }
 
 /// Insert [`StudentResult`] into a database using a database connection.
+///
+/// Modules with different credits from the stored credits are resolved with
+/// the `credit_policy` and students with different information from the
+/// stored information with the `conflict_policy`, the conflicts and the
+/// changed fields are returned. The inserted and updated rows are linked to
+/// the import batch `batch`, see [`ImportBatch`].
+///
+/// New students are inserted with the intake year inferred from their year of
+/// study in the `academic_year`, or an unknown intake year if it cannot be
+/// inferred, the stored students with a different intake year are returned as
+/// intake conflicts.
+///
+/// Returns [`Error::MissingAttempts`] if a module has no attempts, as it has
+/// no mark to be stored.
 #[cfg(feature = "async")]
 pub async fn insert_student_result_async(
     conn: &mut SqlitePool,
     data: &[StudentResult],
-    intake: &AcademicYear,
-) -> Result<(), sqlx::Error> {
+    academic_year: &AcademicYear,
+    credit_policy: CreditPolicy,
+    conflict_policy: ConflictPolicy,
+    batch: i64,
+) -> Result<InsertReport, Error> {
     let mut trans = conn.begin().await?;
-    insert_student_result_transaction_async(&mut trans, data, intake).await?;
+    let report = insert_student_result_transaction_async(
+        &mut trans,
+        data,
+        academic_year,
+        credit_policy,
+        conflict_policy,
+        batch,
+    )
+    .await?;
     trans.commit().await?;
-    Ok(())
+    Ok(report)
 }
 
 /// Insert [`StudentResult`] into database using a database transaction.
 /// *Note*: This function does not commit the changes to the database.
+///
+/// Modules with different credits from the stored credits are resolved with
+/// the `credit_policy` and students with different information from the
+/// stored information with the `conflict_policy`, the conflicts and the
+/// changed fields are returned. The inserted and updated rows are linked to
+/// the import batch `batch`, see [`ImportBatch`].
+///
+/// New students are inserted with the intake year inferred from their year of
+/// study in the `academic_year`, or an unknown intake year if it cannot be
+/// inferred, the stored students with a different intake year are returned as
+/// intake conflicts.
+///
+/// Returns [`Error::MissingAttempts`] if a module has no attempts, as it has
+/// no mark to be stored.
 #[cfg(feature = "async")]
 pub async fn insert_student_result_transaction_async(
     trans: &mut AsyncTransaction<'_, Sqlite>,
     data: &[StudentResult],
-    intake: &AcademicYear,
-) -> Result<(), sqlx::Error> {
+    academic_year: &AcademicYear,
+    credit_policy: CreditPolicy,
+    conflict_policy: ConflictPolicy,
+    batch: i64,
+) -> Result<InsertReport, Error> {
+    let mut conflicts = vec![];
+    let mut changes = vec![];
+    let mut intake_conflicts = vec![];
+    let mut unknown_intakes = vec![];
+
     for result in data {
-        sqlx::query(
-            "INSERT OR IGNORE INTO StudentInfo
-             (ID, FirstName, LastName, Plan, IntakeYear) VALUES (?1, ?2, ?3, ?4, ?5)",
-        )
-        .bind(result.student_info.id)
-        .bind(&result.student_info.first_name)
-        .bind(&result.student_info.last_name)
-        .bind(&result.student_info.plan)
-        .bind(intake.to_string())
-        .execute(&mut **trans)
-        .await?;
+        let id = result.student_info.id;
+        let intake = academic_year.intake_of(&result.year_of_program);
+        if let Some(intake) = &intake {
+            intake.insert_db_transaction_async(trans).await?;
+        }
+
+        let old = student_fields_async(trans, id).await?;
+        if old.is_none() && intake.is_none() {
+            unknown_intakes.push(id);
+        }
+        sqlx::query(BACKUP_STUDENT_INFO_STATEMENT)
+            .bind(batch)
+            .bind(id)
+            .execute(&mut **trans)
+            .await?;
+        sqlx::query(INSERT_RESULT_STUDENT_STATEMENT)
+            .bind(id)
+            .bind(&result.student_info.first_name)
+            .bind(&result.student_info.last_name)
+            .bind(&result.student_info.plan)
+            .bind(intake.map(|intake| intake.to_string()))
+            .bind(batch)
+            .bind(conflict_policy.overwrites())
+            .bind(conflict_policy.updates())
+            .execute(&mut **trans)
+            .await?;
+        let new = student_fields_async(trans, id).await?;
+        changes.extend(student_changes(id, old, new, conflict_policy)?);
 
+        for statement in BACKUP_RESULT_STATEMENTS {
+            sqlx::query(statement)
+                .bind(batch)
+                .bind(result.student_info.id)
+                .bind(academic_year.to_string())
+                .execute(&mut **trans)
+                .await?;
+        }
         sqlx::query(
             "INSERT OR REPLACE INTO Result
              (ID, AcademicYear, YearOfStudy, AutumnCredits, AutumnMean,
               SpringCredits, SpringMean, YearCredits, YearMean, Progression,
-              Remarks)
+              Remarks, SummerCredits, SummerMean, ImportBatch)
              VALUES 
-             (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+             (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
         )
         .bind(result.student_info.id)
-        .bind(intake.to_string())
+        .bind(academic_year.to_string())
         .bind(&result.year_of_program)
         .bind(result.autumn_credit)
         .bind(result.autumn_mean)
@@ -240,18 +1533,101 @@ pub async fn insert_student_result_transaction_async(
         .bind(result.year_prog_average)
         .bind(&result.progression)
         .bind(&result.remarks)
+        .bind(result.summer_credit)
+        .bind(result.summer_mean)
+        .bind(batch)
         .execute(&mut **trans)
         .await?;
+        intake_conflicts.extend(intake_conflicts_async(trans, Some(id)).await?);
 
-        for module in &result.modules {
+        // Replacing the attempts of the session in case the report is
+        // imported again
+        let session = session_name(result);
+        sqlx::query(
+            "DELETE FROM ResultAttempt
+             WHERE ID=?1 AND AcademicYear=?2 AND Session=?3",
+        )
+        .bind(result.student_info.id)
+        .bind(academic_year.to_string())
+        .bind(session)
+        .execute(&mut **trans)
+        .await?;
+        for (attempt, figures) in result.attempts.iter().enumerate() {
             sqlx::query(
-                "INSERT OR IGNORE INTO Module
-                 (Code, Credit) VALUES (?1, ?2)",
+                "INSERT INTO ResultAttempt
+                 (ID, AcademicYear, Session, Attempt, YearCredits, YearMean,
+                  CreditsL3Lt30, CreditsL33039)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             )
-            .bind(&module.code)
-            .bind(module.credit)
+            .bind(result.student_info.id)
+            .bind(academic_year.to_string())
+            .bind(session)
+            .bind(attempt as i64)
+            .bind(figures.year_credit)
+            .bind(figures.year_prog_average)
+            .bind(figures.credits_l3_lt30)
+            .bind(figures.credits_l3_30_39)
             .execute(&mut **trans)
             .await?;
+        }
+
+        for module in &result.modules {
+            let stored = sqlx::query_as::<_, (i64,)>(
+                "SELECT Credit FROM Module
+                 WHERE Code=?1",
+            )
+            .bind(&module.code)
+            .fetch_optional(&mut **trans)
+            .await?;
+            match stored {
+                Some((stored,)) => {
+                    if let Some(conflict) =
+                        credit_conflict(module, stored, academic_year, credit_policy)?
+                    {
+                        if credit_policy == CreditPolicy::Overwrite {
+                            sqlx::query(BACKUP_MODULE_STATEMENTS[0])
+                                .bind(batch)
+                                .bind(&module.code)
+                                .execute(&mut **trans)
+                                .await?;
+                            sqlx::query(UPDATE_MODULE_CREDIT_STATEMENT)
+                                .bind(&module.code)
+                                .bind(module.credit)
+                                .bind(batch)
+                                .execute(&mut **trans)
+                                .await?;
+                        }
+                        sqlx::query(BACKUP_MODULE_STATEMENTS[1])
+                            .bind(batch)
+                            .bind(&conflict.module)
+                            .bind(academic_year.to_string())
+                            .bind(conflict.stored)
+                            .bind(conflict.incoming)
+                            .execute(&mut **trans)
+                            .await?;
+                        sqlx::query(INSERT_CREDIT_CONFLICT_STATEMENT)
+                            .bind(&conflict.module)
+                            .bind(academic_year.to_string())
+                            .bind(conflict.stored)
+                            .bind(conflict.incoming)
+                            .bind(credit_policy.to_string())
+                            .bind(batch)
+                            .execute(&mut **trans)
+                            .await?;
+                        if !conflicts.contains(&conflict) {
+                            conflicts.push(conflict);
+                        }
+                    }
+                }
+                None => {
+                    sqlx::query(INSERT_MODULE_STATEMENT)
+                        .bind(&module.code)
+                        .bind(module.credit)
+                        .bind(batch)
+                        .execute(&mut **trans)
+                        .await?;
+                }
+            }
             let colour_id: Option<i64> = match &module.fill {
                 Some(fill) => {
                     sqlx::query(
@@ -286,27 +1662,81 @@ pub async fn insert_student_result_transaction_async(
                 None => None,
             };
 
+            for statement in BACKUP_MARK_STATEMENTS {
+                sqlx::query(statement)
+                    .bind(batch)
+                    .bind(result.student_info.id)
+                    .bind(academic_year.to_string())
+                    .bind(&module.code)
+                    .execute(&mut **trans)
+                    .await?;
+            }
+            let first_mark = module.first_mark().ok_or_else(|| Error::MissingAttempts {
+                id: result.student_info.id,
+                module: module.code.clone(),
+            })?;
             sqlx::query(
-                "INSERT OR REPLACE INTO Mark
-              (ID, Module, Mark, Retake1, Retake2, Status, Fill)
-              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                "INSERT INTO Mark
+                 (ID, AcademicYear, Module, Mark, MarkCode, Status, Fill,
+                  ImportBatch)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                 ON CONFLICT DO UPDATE SET
+                 Mark=?4, MarkCode=?5, Status=?6, Fill=?7, ImportBatch=?8",
             )
             .bind(result.student_info.id)
+            .bind(academic_year.to_string())
             .bind(&module.code)
-            .bind(module.mark)
-            .bind(module.retake1)
-            .bind(module.retake2)
+            .bind(first_mark.as_f64())
+            .bind(first_mark.code())
             .bind(module.status.to_string())
             .bind(colour_id)
+            .bind(batch)
             .execute(&mut **trans)
             .await?;
+
+            // Replacing the attempts in case the report is imported again
+            for (i, attempt) in module.attempts.iter().enumerate() {
+                sqlx::query(INSERT_MARK_ATTEMPT_STATEMENT)
+                    .bind(result.student_info.id)
+                    .bind(academic_year.to_string())
+                    .bind(&module.code)
+                    .bind(i as i64)
+                    .bind(attempt.session.to_string())
+                    .bind(attempt.mark.as_f64())
+                    .bind(attempt.mark.code())
+                    .bind(attempt.capped)
+                    .bind(attempt.status.as_ref().map(ToString::to_string))
+                    .bind(batch)
+                    .execute(&mut **trans)
+                    .await?;
+            }
+            sqlx::query(DELETE_MARK_ATTEMPTS_STATEMENT)
+                .bind(result.student_info.id)
+                .bind(academic_year.to_string())
+                .bind(&module.code)
+                .bind(module.attempts.len() as i64)
+                .execute(&mut **trans)
+                .await?;
         }
     }
 
-    Ok(())
+    sqlx::query(COMPLETE_BATCH_STATEMENT)
+        .bind(batch)
+        .execute(&mut **trans)
+        .await?;
+    Ok(InsertReport {
+        credit_conflicts: conflicts,
+        changes,
+        intake_conflicts,
+        unknown_intakes,
+    })
 }
 
 impl StudentInfo {
+    /// The statement to insert a student or to update the stored student. The
+    /// stored fields are overwritten if `?24` is set, otherwise only the
+    /// missing fields are filled, and the stored student is only updated if
+    /// `?25` is set, see [`ConflictPolicy`].
     pub const INSERT_STATEMENT: &'static str = "
         INSERT INTO StudentInfo
         (
@@ -331,7 +1761,8 @@ impl StudentInfo {
             ExceptionData,
             Recommendation,
             IntakeYear,
-            GraduationYear
+            GraduationYear,
+            ImportBatch
         )
         VALUES (
             ?1,
@@ -355,54 +1786,88 @@ impl StudentInfo {
             ?19,
             ?20,
             ?21,
-            ?22
+            ?22,
+            ?23
         )
         ON CONFLICT DO UPDATE SET
-        FirstName=?2,
-        LastName=?3,
-        Plan=?4,
-        PlanDesc=?5,
-        Program=?6,
-        ProgramDesc=?7,
-        INTAKE=?8,
-        CareerNo=?9,
-        QAA=?10,
-        CalcModel=?11,
-        RawMark=?12,
-        TruncatedMark=?13,
-        FinalMark=?14,
-        Borderline=?15,
-        Calculation=?16,
-        DegreeAward=?17,
-        Selected=?18,
-        ExceptionData=?19,
-        Recommendation=?20,
-        GraduationYear=?22
+        FirstName=CASE WHEN ?24 THEN ?2 ELSE COALESCE(FirstName, ?2) END,
+        LastName=CASE WHEN ?24 THEN ?3 ELSE COALESCE(LastName, ?3) END,
+        Plan=CASE WHEN ?24 THEN ?4 ELSE COALESCE(Plan, ?4) END,
+        PlanDesc=CASE WHEN ?24 THEN ?5 ELSE COALESCE(PlanDesc, ?5) END,
+        Program=CASE WHEN ?24 THEN ?6 ELSE COALESCE(Program, ?6) END,
+        ProgramDesc=CASE WHEN ?24 THEN ?7 ELSE COALESCE(ProgramDesc, ?7) END,
+        INTAKE=CASE WHEN ?24 THEN ?8 ELSE COALESCE(INTAKE, ?8) END,
+        CareerNo=CASE WHEN ?24 THEN ?9 ELSE COALESCE(CareerNo, ?9) END,
+        QAA=CASE WHEN ?24 THEN ?10 ELSE COALESCE(QAA, ?10) END,
+        CalcModel=CASE WHEN ?24 THEN ?11 ELSE COALESCE(CalcModel, ?11) END,
+        RawMark=CASE WHEN ?24 THEN ?12 ELSE COALESCE(RawMark, ?12) END,
+        TruncatedMark=CASE WHEN ?24 THEN ?13 ELSE COALESCE(TruncatedMark, ?13) END,
+        FinalMark=CASE WHEN ?24 THEN ?14 ELSE COALESCE(FinalMark, ?14) END,
+        Borderline=CASE WHEN ?24 THEN ?15 ELSE COALESCE(Borderline, ?15) END,
+        Calculation=CASE WHEN ?24 THEN ?16 ELSE COALESCE(Calculation, ?16) END,
+        DegreeAward=CASE WHEN ?24 THEN ?17 ELSE COALESCE(DegreeAward, ?17) END,
+        Selected=CASE WHEN ?24 THEN ?18 ELSE COALESCE(Selected, ?18) END,
+        ExceptionData=CASE WHEN ?24 THEN ?19 ELSE COALESCE(ExceptionData, ?19) END,
+        Recommendation=CASE WHEN ?24 THEN ?20 ELSE COALESCE(Recommendation, ?20) END,
+        GraduationYear=CASE WHEN ?24 THEN ?22 ELSE COALESCE(GraduationYear, ?22) END,
+        ImportBatch=?23
+        WHERE ?25
         ";
 
+    /// Gets the intake year of the student from the intake in the award
+    /// report (0B), or `None` if the intake is not given or invalid.
+    #[cfg(any(feature = "sync", feature = "async"))]
+    fn intake_year(&self) -> Option<AcademicYear> {
+        self.intake.as_deref().and_then(AcademicYear::from_intake)
+    }
+
     /// Insert [`StudentInfo`] into a database using a database connection.
+    ///
+    /// A stored student is updated according to the `policy` and the changed
+    /// fields are returned. The inserted or updated student is linked to the
+    /// import batch `batch`, see [`ImportBatch`].
+    ///
+    /// A new student is inserted with the intake year from the intake column,
+    /// or an unknown intake year if it is not given, a stored student with a
+    /// different intake year is returned as an intake conflict.
     #[cfg(feature = "sync")]
     pub fn insert_db_sync(
         &self,
         conn: &mut Connection,
-        intake: &AcademicYear,
+        academic_year: &AcademicYear,
         award: bool,
-    ) -> Result<(), rusqlite::Error> {
+        policy: ConflictPolicy,
+        batch: i64,
+    ) -> Result<InsertReport, Error> {
         let trans = conn.transaction()?;
-        self.insert_db_transaction_sync(&trans, intake, award)?;
+        let report =
+            self.insert_db_transaction_sync(&trans, academic_year, award, policy, batch)?;
         trans.commit()?;
-        Ok(())
+        Ok(report)
     }
 
     /// Insert [`StudentInfo`] into database using a database transaction.
     /// *Note*: This function does not commit the changes to the database.
+    ///
+    /// A stored student is updated according to the `policy` and the changed
+    /// fields are returned, with the intake conflict of a stored student.
     #[cfg(feature = "sync")]
     pub fn insert_db_transaction_sync(
         &self,
         trans: &Transaction,
-        intake: &AcademicYear,
+        academic_year: &AcademicYear,
         award: bool,
-    ) -> Result<(), rusqlite::Error> {
+        policy: ConflictPolicy,
+        batch: i64,
+    ) -> Result<InsertReport, Error> {
+        let intake = self.intake_year();
+        if let Some(intake) = &intake {
+            intake.insert_db_transaction_sync(trans)?;
+        }
+
+        let old = student_fields_sync(trans, self.id)?;
+        let unknown_intake = old.is_none() && intake.is_none();
+        trans.execute(BACKUP_STUDENT_INFO_STATEMENT, params![batch, self.id])?;
         trans.execute(
             Self::INSERT_STATEMENT,
             params![
@@ -428,37 +1893,74 @@ impl StudentInfo {
                 self.exception_data,
                 self.recommendation,
                 intake,
-                if award { Some(intake) } else { None }
+                if award { Some(academic_year) } else { None },
+                batch,
+                policy.overwrites(),
+                policy.updates(),
             ],
         )?;
+        let new = student_fields_sync(trans, self.id)?;
 
-        Ok(())
+        Ok(InsertReport {
+            credit_conflicts: vec![],
+            changes: student_changes(self.id, old, new, policy)?,
+            intake_conflicts: intake_conflicts_sync(trans, Some(self.id))?,
+            unknown_intakes: unknown_intake.then_some(self.id).into_iter().collect(),
+        })
     }
 
     /// Insert [`StudentInfo`] into a database using a database connection.
+    ///
+    /// A stored student is updated according to the `policy` and the changed
+    /// fields are returned. The inserted or updated student is linked to the
+    /// import batch `batch`, see [`ImportBatch`].
+    ///
+    /// A new student is inserted with the intake year from the intake column,
+    /// or an unknown intake year if it is not given, a stored student with a
+    /// different intake year is returned as an intake conflict.
     #[cfg(feature = "async")]
     pub async fn insert_db_async(
         &self,
         conn: &mut SqlitePool,
-        intake: &AcademicYear,
+        academic_year: &AcademicYear,
         award: bool,
-    ) -> Result<(), sqlx::Error> {
+        policy: ConflictPolicy,
+        batch: i64,
+    ) -> Result<InsertReport, Error> {
         let mut trans = conn.begin().await?;
-        self.insert_db_transaction_async(&mut trans, intake, award)
+        let report = self
+            .insert_db_transaction_async(&mut trans, academic_year, award, policy, batch)
             .await?;
         trans.commit().await?;
-        Ok(())
+        Ok(report)
     }
 
     /// Insert [`StudentInfo`] into database using a database transaction.
     /// *Note*: This function does not commit the changes to the database.
+    ///
+    /// A stored student is updated according to the `policy` and the changed
+    /// fields are returned, with the intake conflict of a stored student.
     #[cfg(feature = "async")]
     pub async fn insert_db_transaction_async(
         &self,
         trans: &mut AsyncTransaction<'_, Sqlite>,
-        intake: &AcademicYear,
+        academic_year: &AcademicYear,
         award: bool,
-    ) -> Result<(), sqlx::Error> {
+        policy: ConflictPolicy,
+        batch: i64,
+    ) -> Result<InsertReport, Error> {
+        let intake = self.intake_year();
+        if let Some(intake) = &intake {
+            intake.insert_db_transaction_async(trans).await?;
+        }
+
+        let old = student_fields_async(trans, self.id).await?;
+        let unknown_intake = old.is_none() && intake.is_none();
+        sqlx::query(BACKUP_STUDENT_INFO_STATEMENT)
+            .bind(batch)
+            .bind(self.id)
+            .execute(&mut **trans)
+            .await?;
         sqlx::query(Self::INSERT_STATEMENT)
             .bind(self.id)
             .bind(&self.first_name)
@@ -483,76 +1985,435 @@ impl StudentInfo {
             .bind(self.selected)
             .bind(&self.exception_data)
             .bind(&self.recommendation)
-            .bind(intake.to_string())
+            .bind(intake.map(|intake| intake.to_string()))
             .bind(if award {
-                Some(intake.to_string())
+                Some(academic_year.to_string())
             } else {
                 None
             })
+            .bind(batch)
+            .bind(policy.overwrites())
+            .bind(policy.updates())
             .execute(&mut **trans)
             .await?;
+        let new = student_fields_async(trans, self.id).await?;
 
-        Ok(())
+        Ok(InsertReport {
+            credit_conflicts: vec![],
+            changes: student_changes(self.id, old, new, policy)?,
+            intake_conflicts: intake_conflicts_async(trans, Some(self.id)).await?,
+            unknown_intakes: unknown_intake.then_some(self.id).into_iter().collect(),
+        })
+    }
+}
+
+#[cfg(any(feature = "sync", feature = "async"))]
+impl InsertReport {
+    /// Adds the changes and conflicts of `other` to this report.
+    fn extend(&mut self, other: InsertReport) {
+        self.credit_conflicts.extend(other.credit_conflicts);
+        self.changes.extend(other.changes);
+        self.intake_conflicts.extend(other.intake_conflicts);
+        self.unknown_intakes.extend(other.unknown_intakes);
     }
 }
 
 /// Insert [`StudentInfo`] into a database using a database connection.
+///
+/// Stored students are updated according to the `policy` and the changed
+/// fields are returned. The inserted and updated students are linked to the
+/// import batch `batch`, see [`ImportBatch`].
+///
+/// New students are inserted with the intake year from the intake column, or
+/// an unknown intake year if it is not given, the stored students with a
+/// different intake year are returned as intake conflicts.
 #[cfg(feature = "sync")]
 pub fn insert_student_info(
     data: &[StudentInfo],
     conn: &mut Connection,
-    intake: &AcademicYear,
+    academic_year: &AcademicYear,
     award: bool,
-) -> Result<(), rusqlite::Error> {
+    policy: ConflictPolicy,
+    batch: i64,
+) -> Result<InsertReport, Error> {
     let trans = conn.transaction()?;
-    insert_student_info_transaction(data, &trans, intake, award)?;
+    let report =
+        insert_student_info_transaction(data, &trans, academic_year, award, policy, batch)?;
     trans.commit()?;
-    Ok(())
+    Ok(report)
 }
 
 /// Insert [`StudentInfo`] into database using a database transaction.
 /// *Note*: This function does not commit the changes to the database.
+///
+/// Stored students are updated according to the `policy` and the changed
+/// fields are returned, with the intake conflicts of the stored students.
 #[cfg(feature = "sync")]
 pub fn insert_student_info_transaction(
     data: &[StudentInfo],
     trans: &Transaction,
-    intake: &AcademicYear,
+    academic_year: &AcademicYear,
     award: bool,
-) -> Result<(), rusqlite::Error> {
+    policy: ConflictPolicy,
+    batch: i64,
+) -> Result<InsertReport, Error> {
+    let mut report = InsertReport::default();
     for info in data {
-        info.insert_db_transaction_sync(trans, intake, award)?;
+        report.extend(info.insert_db_transaction_sync(
+            trans,
+            academic_year,
+            award,
+            policy,
+            batch,
+        )?);
     }
 
-    Ok(())
+    trans.execute(COMPLETE_BATCH_STATEMENT, params![batch])?;
+    Ok(report)
 }
 
 /// Insert [`StudentInfo`] into a database using a database connection.
+///
+/// Stored students are updated according to the `policy` and the changed
+/// fields are returned. The inserted and updated students are linked to the
+/// import batch `batch`, see [`ImportBatch`].
+///
+/// New students are inserted with the intake year from the intake column, or
+/// an unknown intake year if it is not given, the stored students with a
+/// different intake year are returned as intake conflicts.
 #[cfg(feature = "async")]
 pub async fn insert_student_info_async(
     conn: &mut SqlitePool,
     data: &[StudentInfo],
-    intake: &AcademicYear,
+    academic_year: &AcademicYear,
     award: bool,
-) -> Result<(), sqlx::Error> {
+    policy: ConflictPolicy,
+    batch: i64,
+) -> Result<InsertReport, Error> {
     let mut trans = conn.begin().await?;
-    insert_student_info_transaction_async(&mut trans, data, intake, award).await?;
+    let report = insert_student_info_transaction_async(
+        &mut trans,
+        data,
+        academic_year,
+        award,
+        policy,
+        batch,
+    )
+    .await?;
     trans.commit().await?;
-    Ok(())
+    Ok(report)
 }
 
 /// Insert [`StudentInfo`] into database using a database transaction.
 /// *Note*: This function does not commit the changes to the database.
+///
+/// Stored students are updated according to the `policy` and the changed
+/// fields are returned, with the intake conflicts of the stored students.
 #[cfg(feature = "async")]
 pub async fn insert_student_info_transaction_async(
     trans: &mut AsyncTransaction<'_, Sqlite>,
     data: &[StudentInfo],
-    intake: &AcademicYear,
+    academic_year: &AcademicYear,
     award: bool,
-) -> Result<(), sqlx::Error> {
+    policy: ConflictPolicy,
+    batch: i64,
+) -> Result<InsertReport, Error> {
+    let mut report = InsertReport::default();
     for info in data {
-        info.insert_db_transaction_async(trans, intake, award)
+        report.extend(
+            info.insert_db_transaction_async(trans, academic_year, award, policy, batch)
+                .await?,
+        );
+    }
+
+    sqlx::query(COMPLETE_BATCH_STATEMENT)
+        .bind(batch)
+        .execute(&mut **trans)
+        .await?;
+    Ok(report)
+}
+
+/// Recomputes the intake years of all the students in a database using a
+/// database connection, e.g. the students inserted with the academic year of
+/// the report as their intake year by an earlier version.
+///
+/// The intake year is inferred from the intake in the award report (0B), or
+/// the earliest intake year inferred from the year of study of the results.
+/// The students whose intake year cannot be inferred are not changed.
+///
+/// Returns the students whose intake year was updated.
+#[cfg(feature = "sync")]
+pub fn repair_intake_years(conn: &mut Connection) -> Result<Vec<IntakeConflict>, Error> {
+    let trans = conn.transaction()?;
+    let repaired = repair_intake_years_transaction(&trans)?;
+    trans.commit()?;
+    Ok(repaired)
+}
+
+/// Recomputes the intake years of all the students in a database using a
+/// database transaction, see [`repair_intake_years`].
+/// *Note*: This function does not commit the changes to the database.
+#[cfg(feature = "sync")]
+pub fn repair_intake_years_transaction(trans: &Transaction) -> Result<Vec<IntakeConflict>, Error> {
+    let repaired = intake_conflicts_sync(trans, None)?;
+    for conflict in &repaired {
+        trans.execute(AcademicYear::INSERT_STATEMENT, params![conflict.inferred])?;
+        trans.execute(
+            UPDATE_INTAKE_STATEMENT,
+            params![conflict.id, conflict.inferred],
+        )?;
+    }
+
+    Ok(repaired)
+}
+
+/// Recomputes the intake years of all the students in a database using a
+/// database connection, see [`repair_intake_years_transaction_async`].
+#[cfg(feature = "async")]
+pub async fn repair_intake_years_async(
+    conn: &mut SqlitePool,
+) -> Result<Vec<IntakeConflict>, Error> {
+    let mut trans = conn.begin().await?;
+    let repaired = repair_intake_years_transaction_async(&mut trans).await?;
+    trans.commit().await?;
+    Ok(repaired)
+}
+
+/// Recomputes the intake years of all the students in a database using a
+/// database transaction.
+/// *Note*: This function does not commit the changes to the database.
+///
+/// The intake year is inferred from the intake in the award report (0B), or
+/// the earliest intake year inferred from the year of study of the results.
+/// The students whose intake year cannot be inferred are not changed.
+///
+/// Returns the students whose intake year was updated.
+#[cfg(feature = "async")]
+pub async fn repair_intake_years_transaction_async(
+    trans: &mut AsyncTransaction<'_, Sqlite>,
+) -> Result<Vec<IntakeConflict>, Error> {
+    let repaired = intake_conflicts_async(trans, None).await?;
+    for conflict in &repaired {
+        sqlx::query(AcademicYear::INSERT_STATEMENT)
+            .bind(&conflict.inferred)
+            .execute(&mut **trans)
+            .await?;
+        sqlx::query(UPDATE_INTAKE_STATEMENT)
+            .bind(conflict.id)
+            .bind(&conflict.inferred)
+            .execute(&mut **trans)
             .await?;
     }
 
-    Ok(())
+    Ok(repaired)
+}
+
+/// The statement to insert a [`ComponentMark`], or to update the stored mark
+/// of the component if `?9` is set, see [`ConflictPolicy`].
+#[cfg(any(feature = "sync", feature = "async"))]
+const INSERT_COMPONENT_STATEMENT: &str = "
+    INSERT INTO ComponentMark
+    (ID, AcademicYear, Module, Attempt, Component, Weight, Mark, MarkCode)
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+    ON CONFLICT DO UPDATE SET Weight=?6, Mark=?7, MarkCode=?8
+    WHERE ?9";
+
+/// The statement to read the weight and the mark of a stored
+/// [`ComponentMark`], to find the fields changed by an import.
+#[cfg(any(feature = "sync", feature = "async"))]
+const COMPONENT_FIELDS_STATEMENT: &str = "
+    SELECT Weight, Mark, MarkCode FROM ComponentMark
+    WHERE ID=?1 AND AcademicYear=?2 AND Module=?3 AND Attempt=?4 AND Component=?5";
+
+/// A row of [`COMPONENT_FIELDS_STATEMENT`], the weight, the numeric mark and
+/// the code of the mark.
+#[cfg(any(feature = "sync", feature = "async"))]
+type ComponentFields = (f64, Option<f64>, Option<String>);
+
+/// Finds the fields of the stored `component` changed from `old`, the stored
+/// weight and mark, according to the `policy`. The stored components are only
+/// changed by [`ConflictPolicy::Overwrite`], as no field of a stored
+/// component is missing.
+///
+/// Returns [`Error::StudentConflict`] if a stored field would be changed and
+/// the `policy` is [`ConflictPolicy::Fail`].
+#[cfg(any(feature = "sync", feature = "async"))]
+fn component_changes(
+    component: &ComponentMark,
+    old: Option<ComponentFields>,
+    policy: ConflictPolicy,
+) -> Result<Vec<FieldChange>, Error> {
+    // The component was inserted instead of updated
+    let Some((weight, mark, code)) = old else {
+        return Ok(vec![]);
+    };
+
+    let old_mark = mark
+        .map(|mark| MarkValue::Number(mark).to_string())
+        .or(code);
+    let field = |column: &str| match component.attempt {
+        0 => format!("{} {} {column}", component.module, component.name),
+        attempt => format!(
+            "{} {} {column} (resit {attempt})",
+            component.module, component.name
+        ),
+    };
+    let changes: Vec<_> = [
+        ("Weight", weight.to_string(), component.weight.to_string()),
+        (
+            "Mark",
+            old_mark.unwrap_or_default(),
+            component.mark.to_string(),
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, old, new)| old != new)
+    .map(|(column, old, new)| FieldChange {
+        id: component.id,
+        field: field(column),
+        old: Some(old),
+        new: Some(new),
+    })
+    .collect();
+
+    match policy {
+        ConflictPolicy::Overwrite => Ok(changes),
+        ConflictPolicy::Fail => match changes.into_iter().next() {
+            Some(change) => Err(Error::StudentConflict(change)),
+            None => Ok(vec![]),
+        },
+        ConflictPolicy::FillMissing | ConflictPolicy::KeepExisting => Ok(vec![]),
+    }
+}
+
+/// Insert [`ComponentMark`] into a database using a database connection.
+///
+/// The marks of the modules of the components in the academic year must be
+/// inserted first. Stored components are updated according to the `policy`
+/// and the changed fields are returned.
+#[cfg(feature = "sync")]
+pub fn insert_component_marks(
+    conn: &mut Connection,
+    data: &[ComponentMark],
+    academic_year: &AcademicYear,
+    policy: ConflictPolicy,
+) -> Result<InsertReport, Error> {
+    let trans = conn.transaction()?;
+    let report = insert_component_marks_transaction(&trans, data, academic_year, policy)?;
+    trans.commit()?;
+    Ok(report)
+}
+
+/// Insert [`ComponentMark`] into database using a database transaction.
+/// *Note*: This function does not commit the changes to the database.
+///
+/// Stored components are updated according to the `policy` and the changed
+/// fields are returned.
+#[cfg(feature = "sync")]
+pub fn insert_component_marks_transaction(
+    trans: &Transaction,
+    data: &[ComponentMark],
+    academic_year: &AcademicYear,
+    policy: ConflictPolicy,
+) -> Result<InsertReport, Error> {
+    let mut component_fields = trans.prepare(COMPONENT_FIELDS_STATEMENT)?;
+    let mut insert_component = trans.prepare(INSERT_COMPONENT_STATEMENT)?;
+    let mut changes = vec![];
+
+    for component in data {
+        let old = component_fields
+            .query_row(
+                params![
+                    component.id,
+                    academic_year,
+                    component.module,
+                    component.attempt,
+                    component.name,
+                ],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        changes.extend(component_changes(component, old, policy)?);
+
+        insert_component.execute(params![
+            component.id,
+            academic_year,
+            component.module,
+            component.attempt,
+            component.name,
+            component.weight,
+            component.mark.as_f64(),
+            component.mark.code(),
+            policy.overwrites(),
+        ])?;
+    }
+
+    Ok(InsertReport {
+        changes,
+        ..Default::default()
+    })
+}
+
+/// Insert [`ComponentMark`] into a database using a database connection.
+///
+/// The marks of the modules of the components in the academic year must be
+/// inserted first. Stored components are updated according to the `policy`
+/// and the changed fields are returned.
+#[cfg(feature = "async")]
+pub async fn insert_component_marks_async(
+    conn: &mut SqlitePool,
+    data: &[ComponentMark],
+    academic_year: &AcademicYear,
+    policy: ConflictPolicy,
+) -> Result<InsertReport, Error> {
+    let mut trans = conn.begin().await?;
+    let report =
+        insert_component_marks_transaction_async(&mut trans, data, academic_year, policy).await?;
+    trans.commit().await?;
+    Ok(report)
+}
+
+/// Insert [`ComponentMark`] into database using a database transaction.
+/// *Note*: This function does not commit the changes to the database.
+///
+/// Stored components are updated according to the `policy` and the changed
+/// fields are returned.
+#[cfg(feature = "async")]
+pub async fn insert_component_marks_transaction_async(
+    trans: &mut AsyncTransaction<'_, Sqlite>,
+    data: &[ComponentMark],
+    academic_year: &AcademicYear,
+    policy: ConflictPolicy,
+) -> Result<InsertReport, Error> {
+    let mut changes = vec![];
+
+    for component in data {
+        let old = sqlx::query_as::<_, ComponentFields>(COMPONENT_FIELDS_STATEMENT)
+            .bind(component.id)
+            .bind(academic_year.to_string())
+            .bind(&component.module)
+            .bind(component.attempt as i64)
+            .bind(&component.name)
+            .fetch_optional(&mut **trans)
+            .await?;
+        changes.extend(component_changes(component, old, policy)?);
+
+        sqlx::query(INSERT_COMPONENT_STATEMENT)
+            .bind(component.id)
+            .bind(academic_year.to_string())
+            .bind(&component.module)
+            .bind(component.attempt as i64)
+            .bind(&component.name)
+            .bind(component.weight)
+            .bind(component.mark.as_f64())
+            .bind(component.mark.code())
+            .bind(policy.overwrites())
+            .execute(&mut **trans)
+            .await?;
+    }
+
+    Ok(InsertReport {
+        changes,
+        ..Default::default()
+    })
 }
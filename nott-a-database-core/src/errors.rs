@@ -1,9 +1,12 @@
 //! The errors returned by the parsers.
-use std::{error::Error, fmt::Display};
+use std::{error::Error as StdError, fmt::Display};
 
-use calamine::XlsxError;
+use calamine::{Data, XlsxError};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
 use zip::result::ZipError;
 
+use crate::{ReportKind, ResitSession};
+
 #[derive(Debug)]
 /// Errors when parsing a [`StudentResult`](crate::StudentResult) from the raw data.
 pub enum ParseResultRowError {
@@ -38,7 +41,22 @@ impl Display for ParseResultRowError {
     }
 }
 
-impl Error for ParseResultRowError {}
+impl StdError for ParseResultRowError {}
+
+impl ParseResultRowError {
+    /// Gets the stable error code of the error, e.g. `E0A-ROW-MODULE`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidID => "E0A-ROW-ID",
+            Self::InvalidLastName => "E0A-ROW-LAST-NAME",
+            Self::InvalidFirstName => "E0A-ROW-FIRST-NAME",
+            Self::InvalidPlan => "E0A-ROW-PLAN",
+            Self::InvalidYearOfProgram => "E0A-ROW-YEAR-OF-PROGRAM",
+            Self::InvalidProgression => "E0A-ROW-PROGRESSION",
+            Self::InvalidModule => "E0A-ROW-MODULE",
+        }
+    }
+}
 
 #[derive(Debug)]
 pub enum ParseStyleError {
@@ -75,7 +93,19 @@ impl Display for ParseStyleError {
     }
 }
 
-impl Error for ParseStyleError {}
+impl StdError for ParseStyleError {}
+
+impl ParseStyleError {
+    /// Gets the stable error code of the error, e.g. `E-XLSX-ARCHIVE`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::WorkbookError(_) => "E-XLSX-OPEN",
+            Self::ArchiveError(_) => "E-XLSX-ARCHIVE",
+            Self::ReadArchiveError(_) => "E-XLSX-READ",
+            Self::DeserialiseError(_) => "E-XLSX-XML",
+        }
+    }
+}
 
 #[derive(Debug)]
 /// Errors when parsing the result report (0A) raw data.
@@ -91,7 +121,7 @@ pub enum ParseResultError {
     /// Invalid header column found in the data.
     InvalidHeader(String),
     /// Invalid result entry in the data.
-    InvalidRow(usize, ParseResultRowError),
+    InvalidRow(Box<CellContext>, ParseResultRowError),
 }
 
 impl Display for ParseResultError {
@@ -106,12 +136,26 @@ impl Display for ParseResultError {
             Self::NoHeaders => write!(f, "Unable to find headers."),
             Self::NoSubheaders => write!(f, "Unable to find subheaders"),
             Self::InvalidHeader(header) => write!(f, "Invalid Header Found: {}", header),
-            Self::InvalidRow(row, err) => write!(f, "{err} at row {row}"),
+            Self::InvalidRow(context, err) => write!(f, "{err}\n{context}"),
         }
     }
 }
 
-impl Error for ParseResultError {}
+impl StdError for ParseResultError {}
+
+impl ParseResultError {
+    /// Gets the stable error code of the error, e.g. `E0A-ROW-MODULE`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::WorkbookError(_) => "E0A-WORKBOOK",
+            Self::StyleError(e) => e.code(),
+            Self::NoHeaders => "E0A-NO-HEADERS",
+            Self::NoSubheaders => "E0A-NO-SUBHEADERS",
+            Self::InvalidHeader(_) => "E0A-HEADER",
+            Self::InvalidRow(_, e) => e.code(),
+        }
+    }
+}
 
 /// Errors when parsing a row of award report (0B) raw data.
 #[derive(Debug)]
@@ -210,7 +254,35 @@ impl Display for ParseAwardRowError {
     }
 }
 
-impl Error for ParseAwardRowError {}
+impl StdError for ParseAwardRowError {}
+
+impl ParseAwardRowError {
+    /// Gets the stable error code of the error, e.g. `E0B-ROW-SELECTED`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidId => "E0B-ROW-ID",
+            Self::InvalidLastName => "E0B-ROW-LAST-NAME",
+            Self::InvalidFirstName => "E0B-ROW-FIRST-NAME",
+            Self::InvalidCareerNumber => "E0B-ROW-CAREER-NUMBER",
+            Self::InvalidAcademicProgram => "E0B-ROW-ACADEMIC-PROGRAM",
+            Self::InvalidProgramDescription => "E0B-ROW-PROGRAM-DESCRIPTION",
+            Self::InvalidAcademicPlan => "E0B-ROW-ACADEMIC-PLAN",
+            Self::InvalidPlanDescription => "E0B-ROW-PLAN-DESCRIPTION",
+            Self::InvalidIntake => "E0B-ROW-INTAKE",
+            Self::InvalidQAAEffectiveDate => "E0B-ROW-QAA-EFFECTIVE-DATE",
+            Self::InvalidDegreeCalculationModel => "E0B-ROW-DEGREE-CALCULATION-MODEL",
+            Self::InvalidRawFinalMark => "E0B-ROW-RAW-FINAL-MARK",
+            Self::InvalidTruncatedFinalMark => "E0B-ROW-TRUNCATED-FINAL-MARK",
+            Self::InvalidFinalMark => "E0B-ROW-FINAL-MARK",
+            Self::InvalidBorderline => "E0B-ROW-BORDERLINE",
+            Self::InvalidCalculationReviewRqd => "E0B-ROW-CALCULATION-REVIEW-RQD",
+            Self::InvalidDegreeAward => "E0B-ROW-DEGREE-AWARD",
+            Self::InvalidSelected => "E0B-ROW-SELECTED",
+            Self::InvalidExceptionData => "E0B-ROW-EXCEPTION-DATA",
+            Self::InvalidRecommendation => "E0B-ROW-RECOMMENDATION",
+        }
+    }
+}
 
 /// Errors when parsing award report (0B) raw data.
 #[derive(Debug)]
@@ -224,7 +296,7 @@ pub enum ParseAwardError {
     /// Invalid header column found in the data.
     InvalidHeader(String),
     /// Found an invalid row in raw data.
-    InvalidRow(usize, ParseAwardRowError),
+    InvalidRow(Box<CellContext>, ParseAwardRowError),
 }
 
 impl Display for ParseAwardError {
@@ -241,16 +313,41 @@ impl Display for ParseAwardError {
             }
             Self::NoHeaders => write!(f, "Unable to find headers."),
             Self::InvalidHeader(header) => write!(f, "Invalid Header Found: {}", header),
-            Self::InvalidRow(row, err) => write!(f, "{err} at row {row}"),
+            Self::InvalidRow(context, err) => write!(f, "{err}\n{context}"),
         }
     }
 }
 
-impl Error for ParseAwardError {}
+impl StdError for ParseAwardError {}
 
-/// Errors when parsing a row of May resit report (0C) raw data.
-#[derive(Debug)]
-pub enum ParseMayResitRowError {
+impl ParseAwardError {
+    /// Gets the stable error code of the error, e.g. `E0B-ROW-SELECTED`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::WorkbookError(_) => "E0B-WORKBOOK",
+            Self::InvalidWorksheet(_) => "E0B-WORKSHEET",
+            Self::NoHeaders => "E0B-NO-HEADERS",
+            Self::InvalidHeader(_) => "E0B-HEADER",
+            Self::InvalidRow(_, e) => e.code(),
+        }
+    }
+}
+
+/// Gets the stable error code of a resit report error from the code without
+/// the report prefix, e.g. `E0C-ROW-COURSE` for `"ROW-COURSE"` in the May
+/// resit report.
+macro_rules! resit_code {
+    ($session:expr, $code:literal) => {
+        match $session {
+            ResitSession::May => concat!("E0C-", $code),
+            ResitSession::August => concat!("E0D-", $code),
+        }
+    };
+}
+
+/// Errors when parsing a row of resit report (0C, 0D) raw data.
+#[derive(Clone, Copy, Debug)]
+pub enum ParseResitRowError {
     /// No/Invalid student ID found in data.
     InvalidID,
     /// No/Invalid student last name found in data.
@@ -265,6 +362,10 @@ pub enum ParseMayResitRowError {
     InvalidAutumnCredit,
     /// The average/mean marks of the student in the Autumn Semester.
     InvalidAutumnMean,
+    /// No/Invalid summer credit found in data.
+    InvalidSummerCredit,
+    /// No/Invalid summer mean found in data.
+    InvalidSummerMean,
     /// The amount of credits taken by the student in the Spring Semester.
     InvalidFullCredit,
     /// The amount of credits taken by the student in the Spring Semester.
@@ -289,7 +390,7 @@ pub enum ParseMayResitRowError {
     InvalidRemarks,
 }
 
-impl Display for ParseMayResitRowError {
+impl Display for ParseResitRowError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let output = match self {
             Self::InvalidID => "No/Invalid Student ID column.",
@@ -299,6 +400,8 @@ impl Display for ParseMayResitRowError {
             Self::InvalidYearOfProgram => "No/Invalid Year Of Program column.",
             Self::InvalidAutumnCredit => "No/Invalid Autumn Credit column.",
             Self::InvalidAutumnMean => "No/Invalid Autumn Mean column.",
+            Self::InvalidSummerCredit => "No/Invalid Summer Credit column.",
+            Self::InvalidSummerMean => "No/Invalid Summer Mean column.",
             Self::InvalidFullCredit => "No/Invalid Full Credit column.",
             Self::InvalidFullMean => "No/Invalid Full Mean column.",
             Self::InvalidSpringCredit => "No/Invalid Spring Credit column.",
@@ -315,164 +418,833 @@ impl Display for ParseMayResitRowError {
     }
 }
 
-impl Error for ParseMayResitRowError {}
+impl StdError for ParseResitRowError {}
 
-/// Errors when parsing May resit report (0C) raw data.
+impl ParseResitRowError {
+    /// Gets the stable error code of the error in a resit report of the
+    /// `session`, e.g. `E0C-ROW-COURSE`.
+    pub fn code(&self, session: ResitSession) -> &'static str {
+        match self {
+            Self::InvalidID => resit_code!(session, "ROW-ID"),
+            Self::InvalidLastName => resit_code!(session, "ROW-LAST-NAME"),
+            Self::InvalidFirstName => resit_code!(session, "ROW-FIRST-NAME"),
+            Self::InvalidPlan => resit_code!(session, "ROW-PLAN"),
+            Self::InvalidYearOfProgram => resit_code!(session, "ROW-YEAR-OF-PROGRAM"),
+            Self::InvalidAutumnCredit => resit_code!(session, "ROW-AUTUMN-CREDIT"),
+            Self::InvalidAutumnMean => resit_code!(session, "ROW-AUTUMN-MEAN"),
+            Self::InvalidSummerCredit => resit_code!(session, "ROW-SUMMER-CREDIT"),
+            Self::InvalidSummerMean => resit_code!(session, "ROW-SUMMER-MEAN"),
+            Self::InvalidFullCredit => resit_code!(session, "ROW-FULL-CREDIT"),
+            Self::InvalidFullMean => resit_code!(session, "ROW-FULL-MEAN"),
+            Self::InvalidSpringCredit => resit_code!(session, "ROW-SPRING-CREDIT"),
+            Self::InvalidSpringMean => resit_code!(session, "ROW-SPRING-MEAN"),
+            Self::InvalidYearCredit => resit_code!(session, "ROW-YEAR-CREDIT"),
+            Self::InvalidYearProgAverage => resit_code!(session, "ROW-YEAR-PROG-AVERAGE"),
+            Self::InvalidCreditsL3Lt30 => resit_code!(session, "ROW-CREDITS-L3-LT30"),
+            Self::InvalidCreditsL33039 => resit_code!(session, "ROW-CREDITS-L3-30-39"),
+            Self::InvalidProgression => resit_code!(session, "ROW-PROGRESSION"),
+            Self::InvalidCourse => resit_code!(session, "ROW-COURSE"),
+            Self::InvalidRemarks => resit_code!(session, "ROW-REMARKS"),
+        }
+    }
+}
+
+/// The reason parsing resit report (0C, 0D) raw data failed.
 #[derive(Debug)]
-pub enum ParseMayResitError {
+pub enum ParseResitErrorKind {
     /// An error occured when opening the row data workbook.
     WorkbookError(XlsxError),
-    /// Invalid amount of worksheets found in raw data.
+    /// An error occured when parsing styles from workbook.
+    StyleError(ParseStyleError),
+    /// No visible worksheet found in raw data.
     InvalidWorksheet,
     /// Unable to find headers.
     NoHeaders,
-    /// Invalid headers found when parsing May resit report.
+    /// Invalid headers found when parsing resit report.
     InvalidHeaders(String),
     /// Unable to find subheaders.
     NoSubheader,
     /// Found an invalid row in raw data.
-    InvalidDataRow(usize, ParseMayResitRowError),
+    InvalidDataRow(Box<CellContext>, ParseResitRowError),
 }
 
-impl Display for ParseMayResitError {
+/// Errors when parsing resit report (0C, 0D) raw data.
+#[derive(Debug)]
+pub struct ParseResitError {
+    /// The session of the resit report.
+    pub session: ResitSession,
+    /// The reason parsing the resit report failed.
+    pub kind: ParseResitErrorKind,
+}
+
+impl ParseResitError {
+    /// Creates a new [`ParseResitError`] for a resit report of the `session`.
+    pub fn new(session: ResitSession, kind: ParseResitErrorKind) -> Self {
+        Self { session, kind }
+    }
+
+    /// Gets the stable error code of the error, e.g. `E0C-ROW-COURSE`.
+    pub fn code(&self) -> &'static str {
+        match &self.kind {
+            ParseResitErrorKind::WorkbookError(_) => resit_code!(self.session, "WORKBOOK"),
+            ParseResitErrorKind::StyleError(e) => e.code(),
+            ParseResitErrorKind::InvalidWorksheet => resit_code!(self.session, "WORKSHEET"),
+            ParseResitErrorKind::NoHeaders => resit_code!(self.session, "NO-HEADERS"),
+            ParseResitErrorKind::InvalidHeaders(_) => resit_code!(self.session, "HEADER"),
+            ParseResitErrorKind::NoSubheader => resit_code!(self.session, "NO-SUBHEADERS"),
+            ParseResitErrorKind::InvalidDataRow(_, e) => e.code(self.session),
+        }
+    }
+}
+
+impl Display for ParseResitError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::WorkbookError(e) => {
-                write!(f, "Error: {e} occured when opening May resit report.")
+        let session = self.session;
+        match &self.kind {
+            ParseResitErrorKind::WorkbookError(e) => {
+                write!(f, "Error: {e} occured when opening {session} resit report.")
             }
-            Self::InvalidWorksheet => {
-                write!(f, "No worksheet \"Sheet1\" found in the workbook.")
+            ParseResitErrorKind::StyleError(e) => {
+                write!(
+                    f,
+                    "Error: {e} occured when parsing styles in {session} resit report"
+                )
+            }
+            ParseResitErrorKind::InvalidWorksheet => {
+                write!(f, "No visible worksheet found in {session} resit report.")
             }
-            Self::NoHeaders => {
-                write!(f, "No header row found when parsing May resit report")
+            ParseResitErrorKind::NoHeaders => {
+                write!(f, "No header row found when parsing {session} resit report")
             }
-            Self::InvalidHeaders(s) => {
+            ParseResitErrorKind::InvalidHeaders(s) => {
                 write!(
                     f,
-                    "No/Invalid headers {s} found when spring parsing May resit report"
+                    "No/Invalid headers {s} found when parsing {session} resit report"
                 )
             }
-            Self::NoSubheader => {
+            ParseResitErrorKind::NoSubheader => {
                 write!(
                     f,
-                    "No subheader row found when parsing spring May resit report"
+                    "No subheader row found when parsing {session} resit report"
                 )
             }
-            Self::InvalidDataRow(row, e) => write!(f, "{e} at data {row}"),
+            ParseResitErrorKind::InvalidDataRow(context, e) => write!(f, "{e}\n{context}"),
         }
     }
 }
 
-impl Error for ParseMayResitError {}
+impl StdError for ParseResitError {}
 
-/// Errors when parsing a row of August resit report (0D) raw data.
-#[derive(Debug)]
-pub enum ParseAugResitRowError {
+/// Errors when parsing a row of a component mark sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseComponentRowError {
     /// No/Invalid student ID found in data.
     InvalidID,
-    /// No/Invalid student last name found in data.
-    InvalidLastName,
-    /// No/Invalid student first name found in data.
-    InvalidFirstName,
-    /// No/Invalid student study plan found in data.
-    InvalidPlan,
-    /// No/Invalid year of program found in data.
-    InvalidYearOfProgram,
-    /// No/Invalid autumn credit found in data.
-    InvalidAutumnCredit,
-    /// The average/mean marks of the student in the Autumn Semester.
-    InvalidAutumnMean,
-    /// The amount of credits taken by the student in the Spring Semester.
-    InvalidFullCredit,
-    /// The amount of credits taken by the student in the Spring Semester.
-    InvalidFullMean,
-    /// The amount of credits taken by the student in the entire year.
-    InvalidSpringCredit,
-    /// The average/mean marks of the student in the entire year.
-    InvalidSpringMean,
-    /// The amount of credits taken by the student in the entire year.
-    InvalidYearCredit,
-    /// The average/mean marks of the student in the entire year.
-    InvalidYearProgAverage,
-    /// Credits (L3) <30
-    InvalidCreditsL3Lt30,
-    /// Credits (L3) 30-39
-    InvalidCreditsL33039,
-    /// No/Invalid progression information found in data.
-    InvalidProgression,
-    /// No/Invalid module information found in data.
-    InvalidCourse,
-    /// No/Invalid remarks found in data.
-    InvalidRemarks,
+    /// Invalid attempt found in data.
+    InvalidAttempt,
+    /// Invalid component mark found in data.
+    InvalidMark,
 }
 
-impl Display for ParseAugResitRowError {
+impl Display for ParseComponentRowError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let output = match self {
             Self::InvalidID => "No/Invalid Student ID column.",
-            Self::InvalidLastName => "No/Invalid Last Name column.",
-            Self::InvalidFirstName => "No/Invalid First Name column.",
-            Self::InvalidPlan => "No/Invalid Plan column.",
-            Self::InvalidYearOfProgram => "No/Invalid Year Of Program column.",
-            Self::InvalidAutumnCredit => "No/Invalid Autumn Credit column.",
-            Self::InvalidAutumnMean => "No/Invalid Autumn Mean column.",
-            Self::InvalidFullCredit => "No/Invalid Full Credit column.",
-            Self::InvalidFullMean => "No/Invalid Full Mean column.",
-            Self::InvalidSpringCredit => "No/Invalid Spring Credit column.",
-            Self::InvalidSpringMean => "No/Invalid Spring Mean column.",
-            Self::InvalidYearCredit => "No/Invalid Year Credit column.",
-            Self::InvalidYearProgAverage => "No/Invalid Year Mean column.",
-            Self::InvalidCreditsL3Lt30 => "No/Invalid Credits <30 column.",
-            Self::InvalidCreditsL33039 => "No/Invalid Credits 30-39 column.",
-            Self::InvalidProgression => "No/Invalid Progression column.",
-            Self::InvalidCourse => "No/Invalid Course column.",
-            Self::InvalidRemarks => "No/Invalid Remarks column.",
+            Self::InvalidAttempt => "Invalid Attempt column.",
+            Self::InvalidMark => "Invalid component mark.",
         };
         write!(f, "{}", output)
     }
 }
 
-impl Error for ParseAugResitRowError {}
+impl StdError for ParseComponentRowError {}
 
-/// Errors when parsing August resit report (0D) raw data.
+impl ParseComponentRowError {
+    /// Gets the stable error code of the error, e.g. `ECM-ROW-MARK`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidID => "ECM-ROW-ID",
+            Self::InvalidAttempt => "ECM-ROW-ATTEMPT",
+            Self::InvalidMark => "ECM-ROW-MARK",
+        }
+    }
+}
+
+/// Errors when parsing a component mark sheet.
 #[derive(Debug)]
-pub enum ParseAugResitError {
-    /// An error occured when opening the row data workbook.
+pub enum ParseComponentError {
+    /// An error occured when opening the component mark sheet.
     WorkbookError(XlsxError),
-    /// Invalid amount of worksheets found in raw data.
-    InvalidWorksheet(usize),
-    /// Invalid headers found when parsing August resit report.
-    InvalidHeaders,
-    /// Unable to find subheaders.
-    NoSubheader,
-    /// Found an invalid row in raw data.
-    InvalidDataRow(usize, ParseAugResitRowError),
+    /// No headers row found in the worksheet.
+    NoHeaders(String),
+    /// A required column is missing in the worksheet.
+    InvalidHeader(String, String),
+    /// The weight of a component is not a number.
+    InvalidWeight(Box<CellContext>),
+    /// Found an invalid row in the component mark sheet.
+    InvalidRow(Box<CellContext>, ParseComponentRowError),
 }
 
-impl Display for ParseAugResitError {
+impl Display for ParseComponentError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::WorkbookError(e) => {
-                write!(f, "Error: {e} occured when opening August resit report.")
+                write!(f, "Error: {e} occured when opening component mark sheet.")
             }
-            Self::InvalidWorksheet(count) => {
-                write!(
-                    f,
-                    "Invalid amount of worksheets found in August resit report, expected 1 found {count}"
-                )
+            Self::NoHeaders(sheet) => write!(f, "Unable to find headers in {sheet}."),
+            Self::InvalidHeader(sheet, header) => {
+                write!(f, "Missing {header} column in {sheet}.")
             }
-            Self::InvalidHeaders => {
-                write!(
-                    f,
-                    "No/Invalid headers found when parsing August resit report"
-                )
+            Self::InvalidWeight(context) => write!(f, "Invalid component weight.\n{context}"),
+            Self::InvalidRow(context, err) => write!(f, "{err}\n{context}"),
+        }
+    }
+}
+
+impl StdError for ParseComponentError {}
+
+impl ParseComponentError {
+    /// Gets the stable error code of the error, e.g. `ECM-ROW-MARK`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::WorkbookError(_) => "ECM-WORKBOOK",
+            Self::NoHeaders(_) => "ECM-NO-HEADERS",
+            Self::InvalidHeader(..) => "ECM-HEADER",
+            Self::InvalidWeight(_) => "ECM-WEIGHT",
+            Self::InvalidRow(_, e) => e.code(),
+        }
+    }
+}
+
+/// Errors when detecting the type of report in a workbook.
+#[derive(Debug)]
+pub enum DetectReportError {
+    /// An error occured when opening the workbook.
+    WorkbookError(XlsxError),
+    /// The workbook does not look like any of the known reports.
+    UnknownReport,
+}
+
+impl Display for DetectReportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::WorkbookError(e) => {
+                write!(f, "Error: {e} occured when opening workbook.")
+            }
+            Self::UnknownReport => {
+                write!(f, "Unable to detect the type of report in the workbook.")
+            }
+        }
+    }
+}
+
+impl StdError for DetectReportError {}
+
+impl DetectReportError {
+    /// Gets the stable error code of the error, e.g. `E-DETECT-UNKNOWN`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::WorkbookError(_) => "E-DETECT-WORKBOOK",
+            Self::UnknownReport => "E-DETECT-UNKNOWN",
+        }
+    }
+}
+
+/// Errors when loading a [`StatusPalette`](crate::palette::StatusPalette).
+#[derive(Debug)]
+pub enum ParsePaletteError {
+    /// An error occured when reading the palette file.
+    Io(std::io::Error),
+    /// The palette is not a valid TOML palette.
+    InvalidToml(toml::de::Error),
+}
+
+impl Display for ParsePaletteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Error: {e} occured when reading status palette."),
+            Self::InvalidToml(e) => write!(f, "Invalid status palette: {e}"),
+        }
+    }
+}
+
+impl StdError for ParsePaletteError {}
+
+impl ParsePaletteError {
+    /// Gets the stable error code of the error, e.g. `E-PALETTE-TOML`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "E-PALETTE-IO",
+            Self::InvalidToml(_) => "E-PALETTE-TOML",
+        }
+    }
+}
+
+/// Errors when loading a [`ReportLayout`](crate::layout::ReportLayout).
+#[derive(Debug)]
+pub enum ParseLayoutError {
+    /// An error occured when reading the layout file.
+    Io(std::io::Error),
+    /// The layout is not a valid TOML layout.
+    InvalidToml(toml::de::Error),
+    /// The layout is for a different type of report.
+    WrongKind {
+        /// The type of report expected.
+        expected: ReportKind,
+        /// The type of report of the layout.
+        found: ReportKind,
+    },
+    /// The layout has an unsupported number of header rows.
+    InvalidHeaderRows(usize),
+}
+
+impl Display for ParseLayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Error: {e} occured when reading report layout."),
+            Self::InvalidToml(e) => write!(f, "Invalid report layout: {e}"),
+            Self::WrongKind { expected, found } => write!(
+                f,
+                "Invalid report layout, expected a layout for {expected} but found {found}."
+            ),
+            Self::InvalidHeaderRows(rows) => write!(
+                f,
+                "Invalid report layout, expected 1 or 2 header rows but found {rows}."
+            ),
+        }
+    }
+}
+
+impl StdError for ParseLayoutError {}
+
+impl ParseLayoutError {
+    /// Gets the stable error code of the error, e.g. `E-LAYOUT-TOML`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "E-LAYOUT-IO",
+            Self::InvalidToml(_) => "E-LAYOUT-TOML",
+            Self::WrongKind { .. } => "E-LAYOUT-KIND",
+            Self::InvalidHeaderRows(_) => "E-LAYOUT-HEADER-ROWS",
+        }
+    }
+}
+
+/// The location and content of an invalid cell in the raw data.
+#[derive(Debug, Clone, Serialize)]
+pub struct CellContext {
+    /// The name of the worksheet of the cell.
+    pub sheet: String,
+    /// The A1 reference of the cell, e.g. `B3`.
+    pub cell: String,
+    /// The row number of the cell in the worksheet, starting from 1.
+    pub row: usize,
+    /// The header of the column the cell was mapped to.
+    pub header: String,
+    /// The type of value expected in the cell.
+    pub expected: &'static str,
+    /// The raw value found in the cell.
+    #[serde(serialize_with = "serialize_data")]
+    pub value: Data,
+}
+
+impl CellContext {
+    /// Gets the A1 reference of the cell including the worksheet name, e.g.
+    /// `'Award Report'!B3`.
+    pub fn reference(&self) -> String {
+        if self
+            .sheet
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            format!("{}!{}", self.sheet, self.cell)
+        } else {
+            format!("'{}'!{}", self.sheet.replace('\'', "''"), self.cell)
+        }
+    }
+}
+
+impl Display for CellContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let found = match &self.value {
+            Data::Empty => String::from("an empty cell"),
+            Data::String(s) => format!("text {:?}", s),
+            Data::Int(_) | Data::Float(_) => format!("number {}", self.value),
+            Data::Bool(_) => format!("boolean {}", self.value),
+            Data::DateTime(_) | Data::DateTimeIso(_) => format!("date {}", self.value),
+            Data::DurationIso(_) => format!("duration {}", self.value),
+            Data::Error(e) => format!("error {e}"),
+        };
+
+        writeln!(f, "  --> {} [{}]", self.reference(), self.header)?;
+        writeln!(f, "   | expected: {}", self.expected)?;
+        write!(f, "   | found:    {found}")
+    }
+}
+
+/// Serializes a raw cell value as its type and value.
+fn serialize_data<S: Serializer>(data: &Data, serializer: S) -> Result<S::Ok, S::Error> {
+    /// The serialized form of [`Data`].
+    #[derive(Serialize)]
+    #[serde(tag = "type", content = "value", rename_all = "kebab-case")]
+    enum RawValue<'a> {
+        Int(i64),
+        Float(f64),
+        String(&'a str),
+        Bool(bool),
+        DateTime(String),
+        Duration(&'a str),
+        Error(String),
+        Empty,
+    }
+
+    let value = match data {
+        Data::Int(v) => RawValue::Int(*v),
+        Data::Float(v) => RawValue::Float(*v),
+        Data::String(v) => RawValue::String(v),
+        Data::Bool(v) => RawValue::Bool(*v),
+        Data::DateTime(v) => RawValue::DateTime(v.to_string()),
+        Data::DateTimeIso(v) => RawValue::DateTime(v.clone()),
+        Data::DurationIso(v) => RawValue::Duration(v),
+        Data::Error(v) => RawValue::Error(v.to_string()),
+        Data::Empty => RawValue::Empty,
+    };
+    value.serialize(serializer)
+}
+
+/// A module found with a different number of credits from the credits stored
+/// in the database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditConflict {
+    /// The module code of the module.
+    pub module: String,
+    /// The academic year of the data with the new credits.
+    pub academic_year: String,
+    /// The credits stored in the database.
+    pub stored: i64,
+    /// The new credits of the module.
+    pub incoming: i64,
+}
+
+impl Display for CreditConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} has {} credits in {} but {} credits are stored.",
+            self.module, self.incoming, self.academic_year, self.stored
+        )
+    }
+}
+
+/// A field of a stored student changed by an import.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    /// The ID of the student.
+    pub id: i64,
+    /// The name of the column of the field, e.g. `Plan`, or the module, the
+    /// component and the column of a component mark, e.g. `COMP1001 Exam Mark`.
+    pub field: String,
+    /// The stored value of the field.
+    pub old: Option<String>,
+    /// The new value of the field.
+    pub new: Option<String>,
+}
+
+impl Display for FieldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of student {} changed from {} to {}.",
+            self.field,
+            self.id,
+            self.old.as_deref().unwrap_or("(empty)"),
+            self.new.as_deref().unwrap_or("(empty)")
+        )
+    }
+}
+
+/// A student with a stored intake year different from the intake year
+/// inferred from the year of study or the intake in the award report (0B).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntakeConflict {
+    /// The ID of the student.
+    pub id: i64,
+    /// The intake year stored in the database, `None` if it is unknown.
+    pub stored: Option<String>,
+    /// The inferred intake year.
+    pub inferred: String,
+}
+
+impl Display for IntakeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Student {} has the intake year {} but the intake year {} is stored.",
+            self.id,
+            self.inferred,
+            self.stored.as_deref().unwrap_or("(unknown)")
+        )
+    }
+}
+
+/// A previous import of a file with the same content, which was not rolled
+/// back.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateImport {
+    /// The ID of the previous import batch.
+    pub batch: i64,
+    /// The name of the file of the previous import.
+    pub file_name: String,
+    /// The time of the previous import.
+    pub imported_at: String,
+    /// The academic year of the previous import.
+    pub academic_year: String,
+}
+
+impl Display for DuplicateImport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Already imported on {} for {} from {} (import {}).",
+            self.imported_at, self.academic_year, self.file_name, self.batch
+        )
+    }
+}
+
+/// Errors when rolling back an import batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RollbackError {
+    /// No import batch with the ID found.
+    NotFound(i64),
+    /// The import batch with the ID was already rolled back at the time.
+    RolledBack(i64, String),
+}
+
+impl Display for RollbackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound(id) => write!(f, "Unable to find import {id}."),
+            Self::RolledBack(id, time) => {
+                write!(f, "Import {id} was already rolled back at {time}.")
             }
-            Self::NoSubheader => {
-                write!(f, "No subheader row found when parsing August resit report")
+        }
+    }
+}
+
+impl StdError for RollbackError {}
+
+impl RollbackError {
+    /// Gets the stable error code of the error, e.g. `E-DB-BATCH-NOT-FOUND`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotFound(_) => "E-DB-BATCH-NOT-FOUND",
+            Self::RolledBack(..) => "E-DB-BATCH-ROLLED-BACK",
+        }
+    }
+}
+
+/// An invalid row skipped when parsing raw data leniently.
+#[derive(Debug, Clone, Serialize)]
+pub struct RowDiagnostic {
+    /// The invalid cell in the row.
+    #[serde(flatten)]
+    pub context: CellContext,
+    /// The reason the cell is invalid.
+    pub message: String,
+}
+
+impl Display for RowDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\n{}", self.message, self.context)
+    }
+}
+
+/// The category of an [`Error`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorCategory {
+    /// Unable to read a file.
+    Io,
+    /// Unable to open or read the workbook.
+    Workbook,
+    /// The worksheets or headers of the workbook are not in the expected
+    /// layout.
+    Layout,
+    /// Invalid data found in a row of the workbook.
+    Row,
+    /// Unable to detect the type of report of the workbook.
+    Detection,
+    /// Invalid configuration, e.g. the status palette.
+    Config,
+    /// An error occured in the database.
+    Database,
+}
+
+impl Display for ErrorCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let output = match self {
+            Self::Io => "io",
+            Self::Workbook => "workbook",
+            Self::Layout => "layout",
+            Self::Row => "row",
+            Self::Detection => "detection",
+            Self::Config => "config",
+            Self::Database => "database",
+        };
+        write!(f, "{}", output)
+    }
+}
+
+/// All the errors returned by the library.
+///
+/// Every error has a stable [`code`](Error::code), e.g. `E0A-ROW-MODULE`, and
+/// a [`category`](Error::category) so that it can be handled by the
+/// frontend or scripts. It is serialized as an object with the `code`,
+/// `category`, `message` and the invalid `cell` if there is one.
+///
+/// # Examples
+///
+/// ```rust
+/// use nott_a_database_core::{errors::ErrorCategory, Error, StudentResult};
+///
+/// let error: Error = StudentResult::from_result("./missing.xlsx")
+///     .expect_err("The file should not exist")
+///     .into();
+/// assert_eq!(error.code(), "E0A-WORKBOOK");
+/// assert_eq!(error.category(), ErrorCategory::Workbook);
+/// ```
+#[derive(Debug)]
+pub enum Error {
+    /// An error occured when reading a file.
+    Io(std::io::Error),
+    /// An error occured when parsing the styles of a workbook.
+    Style(ParseStyleError),
+    /// An error occured when parsing result report (0A) raw data.
+    Result(ParseResultError),
+    /// An error occured when parsing award report (0B) raw data.
+    Award(ParseAwardError),
+    /// An error occured when parsing resit report (0C, 0D) raw data.
+    Resit(ParseResitError),
+    /// An error occured when parsing a component mark sheet.
+    Component(ParseComponentError),
+    /// An error occured when detecting the type of report.
+    Detect(DetectReportError),
+    /// An error occured when loading the status palette.
+    Palette(ParsePaletteError),
+    /// An error occured when loading a report layout.
+    Layout(ParseLayoutError),
+    /// A module has different credits from the stored credits, see
+    /// [`crate::CreditPolicy::Fail`].
+    CreditConflict(CreditConflict),
+    /// The stored information of a student would be changed, see
+    /// [`crate::ConflictPolicy::Fail`].
+    StudentConflict(FieldChange),
+    /// An error occured when rolling back an import batch.
+    Rollback(RollbackError),
+    /// No import batch with the ID found when reading the history as of the
+    /// batch, see [`crate::AsOf::Batch`].
+    ImportNotFound(i64),
+    /// A module of a student has no attempts, so it has no mark to be stored.
+    MissingAttempts {
+        /// The ID of the student.
+        id: i64,
+        /// The module code of the module.
+        module: String,
+    },
+    /// An error occured in the SQLite database.
+    #[cfg(feature = "sync")]
+    Sqlite(rusqlite::Error),
+    /// An error occured in the SQLite database.
+    #[cfg(feature = "async")]
+    Sqlx(sqlx::Error),
+}
+
+impl Error {
+    /// Gets the stable error code of the error, e.g. `E0A-ROW-MODULE`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "E-IO",
+            Self::Style(e) => e.code(),
+            Self::Result(e) => e.code(),
+            Self::Award(e) => e.code(),
+            Self::Resit(e) => e.code(),
+            Self::Component(e) => e.code(),
+            Self::Detect(e) => e.code(),
+            Self::Palette(e) => e.code(),
+            Self::Layout(e) => e.code(),
+            Self::CreditConflict(_) => "E-DB-CREDIT-CONFLICT",
+            Self::StudentConflict(_) => "E-DB-STUDENT-CONFLICT",
+            Self::Rollback(e) => e.code(),
+            Self::ImportNotFound(_) => "E-DB-IMPORT-NOT-FOUND",
+            Self::MissingAttempts { .. } => "E-DB-MISSING-ATTEMPTS",
+            #[cfg(feature = "sync")]
+            Self::Sqlite(_) => "E-DB-SQLITE",
+            #[cfg(feature = "async")]
+            Self::Sqlx(_) => "E-DB-SQLX",
+        }
+    }
+
+    /// Gets the category of the error.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::Io(_) => ErrorCategory::Io,
+            Self::Style(_)
+            | Self::Result(ParseResultError::WorkbookError(_))
+            | Self::Result(ParseResultError::StyleError(_))
+            | Self::Award(ParseAwardError::WorkbookError(_))
+            | Self::Component(ParseComponentError::WorkbookError(_))
+            | Self::Resit(ParseResitError {
+                kind: ParseResitErrorKind::WorkbookError(_) | ParseResitErrorKind::StyleError(_),
+                ..
+            })
+            | Self::Detect(DetectReportError::WorkbookError(_)) => ErrorCategory::Workbook,
+            Self::Result(ParseResultError::InvalidRow(..))
+            | Self::Award(ParseAwardError::InvalidRow(..))
+            | Self::Component(ParseComponentError::InvalidRow(..))
+            | Self::Resit(ParseResitError {
+                kind: ParseResitErrorKind::InvalidDataRow(..),
+                ..
+            }) => ErrorCategory::Row,
+            Self::Result(_) | Self::Award(_) | Self::Resit(_) | Self::Component(_) => {
+                ErrorCategory::Layout
+            }
+            Self::Detect(DetectReportError::UnknownReport) => ErrorCategory::Detection,
+            Self::Palette(_) | Self::Layout(_) => ErrorCategory::Config,
+            Self::CreditConflict(_)
+            | Self::StudentConflict(_)
+            | Self::Rollback(_)
+            | Self::ImportNotFound(_)
+            | Self::MissingAttempts { .. } => ErrorCategory::Database,
+            #[cfg(feature = "sync")]
+            Self::Sqlite(_) => ErrorCategory::Database,
+            #[cfg(feature = "async")]
+            Self::Sqlx(_) => ErrorCategory::Database,
+        }
+    }
+
+    /// Gets the invalid cell of the error if there is one.
+    pub fn cell(&self) -> Option<&CellContext> {
+        match self {
+            Self::Result(ParseResultError::InvalidRow(context, _))
+            | Self::Award(ParseAwardError::InvalidRow(context, _))
+            | Self::Component(ParseComponentError::InvalidRow(context, _))
+            | Self::Component(ParseComponentError::InvalidWeight(context))
+            | Self::Resit(ParseResitError {
+                kind: ParseResitErrorKind::InvalidDataRow(context, _),
+                ..
+            }) => Some(context),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Error: {e} occured when reading file."),
+            Self::Style(e) => write!(f, "{e}"),
+            Self::Result(e) => write!(f, "{e}"),
+            Self::Award(e) => write!(f, "{e}"),
+            Self::Resit(e) => write!(f, "{e}"),
+            Self::Component(e) => write!(f, "{e}"),
+            Self::Detect(e) => write!(f, "{e}"),
+            Self::Palette(e) => write!(f, "{e}"),
+            Self::Layout(e) => write!(f, "{e}"),
+            Self::CreditConflict(e) => write!(f, "Credit conflict: {e}"),
+            Self::StudentConflict(e) => write!(f, "Student conflict: {e}"),
+            Self::Rollback(e) => write!(f, "{e}"),
+            Self::ImportNotFound(id) => write!(f, "Unable to find import {id}."),
+            Self::MissingAttempts { id, module } => {
+                write!(f, "Module {module} of student {id} has no attempts.")
             }
-            Self::InvalidDataRow(row, e) => write!(f, "{e} at data {row}"),
+            #[cfg(feature = "sync")]
+            Self::Sqlite(e) => write!(f, "Error: {e} occured in the database."),
+            #[cfg(feature = "async")]
+            Self::Sqlx(e) => write!(f, "Error: {e} occured in the database."),
         }
     }
 }
 
-impl Error for ParseAugResitError {}
+impl StdError for Error {}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Error", 4)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("category", &self.category())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("cell", &self.cell())?;
+        state.end()
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ParseStyleError> for Error {
+    fn from(value: ParseStyleError) -> Self {
+        Self::Style(value)
+    }
+}
+
+impl From<ParseResultError> for Error {
+    fn from(value: ParseResultError) -> Self {
+        Self::Result(value)
+    }
+}
+
+impl From<ParseAwardError> for Error {
+    fn from(value: ParseAwardError) -> Self {
+        Self::Award(value)
+    }
+}
+
+impl From<ParseResitError> for Error {
+    fn from(value: ParseResitError) -> Self {
+        Self::Resit(value)
+    }
+}
+
+impl From<ParseComponentError> for Error {
+    fn from(value: ParseComponentError) -> Self {
+        Self::Component(value)
+    }
+}
+
+impl From<DetectReportError> for Error {
+    fn from(value: DetectReportError) -> Self {
+        Self::Detect(value)
+    }
+}
+
+impl From<ParsePaletteError> for Error {
+    fn from(value: ParsePaletteError) -> Self {
+        Self::Palette(value)
+    }
+}
+
+impl From<ParseLayoutError> for Error {
+    fn from(value: ParseLayoutError) -> Self {
+        Self::Layout(value)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl From<rusqlite::Error> for Error {
+    fn from(value: rusqlite::Error) -> Self {
+        Self::Sqlite(value)
+    }
+}
+
+#[cfg(feature = "async")]
+impl From<sqlx::Error> for Error {
+    fn from(value: sqlx::Error) -> Self {
+        Self::Sqlx(value)
+    }
+}
@@ -0,0 +1,301 @@
+//! Declarative layouts of the header rows of each type of report.
+//!
+//! A layout maps the columns of a report to the fields of the parser from the
+//! text of the header rows, so renamed columns only need an updated layout
+//! instead of a new release. The layouts of the reports are defined in TOML,
+//! e.g. the built-in layout of the award report (0B) starts with:
+//!
+//! ```toml
+//! # The type of report: "result", "award", "resit-may" or "resit-aug".
+//! kind = "award"
+//! # The number of header rows, 1 (headers) or 2 (headers and sub-headers).
+//! header-rows = 1
+//!
+//! # The names (aliases) of the columns of each field.
+//! [fields]
+//! no = ["No"]
+//! id = ["Student ID"]
+//! ```
+//!
+//! A header spans the number of columns given by `span` (1 by default), the
+//! following columns with an empty header are treated as part of the header.
+//! Headers spanning a different number of columns are given in `groups`, a
+//! group without a `span` spans all the columns until the next header:
+//!
+//! ```toml
+//! span = 1
+//!
+//! [[groups]]
+//! names = ["Autumn", "Spring"]
+//! span = 2
+//!
+//! [[groups]]
+//! names = ["Modules"]
+//! ```
+//!
+//! The name of a column is its header followed by its sub-header, e.g.
+//! `Autumn Credit`. Names are matched case-insensitively and line breaks are
+//! treated as spaces.
+//!
+//! The built-in layouts in the `layouts` directory of this crate list all the
+//! fields of each report.
+use std::{collections::HashMap, fs, hash::Hash, path::Path};
+
+use serde::Deserialize;
+
+use crate::{
+    award::AwardHeader, errors::ParseLayoutError, marks::ResultHeaders, resit::ResitHeader,
+    ReportKind, ResitSession,
+};
+
+/// A column header of a report which is mapped with a [`ReportLayout`].
+pub(crate) trait LayoutHeader: for<'de> Deserialize<'de> + Clone + Eq + Hash {}
+
+impl LayoutHeader for ResultHeaders {}
+
+impl LayoutHeader for AwardHeader {}
+
+impl LayoutHeader for ResitHeader {}
+
+/// Gets the built-in layout of the type of report as TOML.
+fn builtin(kind: ReportKind) -> &'static str {
+    match kind {
+        ReportKind::Result => include_str!("../layouts/result.toml"),
+        ReportKind::Award => include_str!("../layouts/award.toml"),
+        ReportKind::Resit(ResitSession::May) => include_str!("../layouts/resit_may.toml"),
+        ReportKind::Resit(ResitSession::August) => include_str!("../layouts/resit_aug.toml"),
+    }
+}
+
+/// The layout of the header rows of a report.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", bound(deserialize = "H: LayoutHeader"))]
+pub(crate) struct ReportLayout<H> {
+    /// The type of report of the layout.
+    kind: ReportKind,
+    /// The number of header rows, 1 (headers) or 2 (headers and
+    /// sub-headers).
+    header_rows: usize,
+    /// The number of columns spanned by a header.
+    #[serde(default = "default_span")]
+    span: usize,
+    /// The headers spanning a different number of columns.
+    #[serde(default)]
+    groups: Vec<HeaderGroup>,
+    /// The names of the columns of each field.
+    fields: HashMap<H, Vec<String>>,
+}
+
+/// Headers spanning a different number of columns.
+#[derive(Clone, Debug, Deserialize)]
+struct HeaderGroup {
+    /// The names of the headers.
+    names: Vec<String>,
+    /// The number of columns spanned, all the columns until the next header
+    /// are spanned if it is not given.
+    span: Option<usize>,
+}
+
+fn default_span() -> usize {
+    1
+}
+
+/// Replaces the line breaks and repeated spaces in the name of a column with
+/// a single space, e.g. `Year of \r\nProgram` to `Year of Program`.
+fn collapse(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Checks if the names of two columns are the same, ignoring case and
+/// whitespaces.
+fn same_name(a: &str, b: &str) -> bool {
+    collapse(a).to_lowercase() == collapse(b).to_lowercase()
+}
+
+impl<H: LayoutHeader> ReportLayout<H> {
+    /// Gets the built-in [`ReportLayout`] of the type of report `kind`.
+    fn builtin(kind: ReportKind) -> Self {
+        Self::from_toml(builtin(kind), kind).expect("The built-in layout should be valid")
+    }
+
+    /// Parses a [`ReportLayout`] of the type of report `kind` from a TOML
+    /// string.
+    pub(crate) fn from_toml(s: &str, kind: ReportKind) -> Result<Self, ParseLayoutError> {
+        let layout: Self = toml::from_str(s).map_err(ParseLayoutError::InvalidToml)?;
+
+        if layout.kind != kind {
+            return Err(ParseLayoutError::WrongKind {
+                expected: kind,
+                found: layout.kind,
+            });
+        }
+        if !(1..=2).contains(&layout.header_rows) {
+            return Err(ParseLayoutError::InvalidHeaderRows(layout.header_rows));
+        }
+
+        Ok(layout)
+    }
+
+    /// Gets the number of header rows.
+    pub(crate) fn header_rows(&self) -> usize {
+        self.header_rows
+    }
+
+    /// Gets the number of columns spanned by the header `header`.
+    fn span(&self, header: &str) -> Option<usize> {
+        self.groups
+            .iter()
+            .find(|group| group.names.iter().any(|name| same_name(name, header)))
+            .map_or(Some(self.span), |group| group.span)
+    }
+
+    /// Gets the field of the column with the name `name`.
+    fn field(&self, name: &str) -> Option<&H> {
+        self.fields
+            .iter()
+            .find(|(_, names)| names.iter().any(|n| same_name(n, name)))
+            .map(|(field, _)| field)
+    }
+
+    /// Gets the field of every column from the header and sub-header rows.
+    ///
+    /// The sub-headers are ignored if the layout only has a single header
+    /// row.
+    ///
+    /// Returns the name of the first column without a field as the error.
+    pub(crate) fn headers(
+        &self,
+        headers: &[String],
+        sub_headers: Option<&[String]>,
+    ) -> Result<Vec<H>, String> {
+        // The current header and the number of columns it still spans
+        let mut current: Option<(String, Option<usize>)> = None;
+        let mut output = vec![];
+
+        for (col, header) in headers.iter().enumerate() {
+            let header = collapse(header);
+            let header = if !header.is_empty() {
+                let span = self.span(&header);
+                current = Some((header.clone(), span.map(|s| s.saturating_sub(1))));
+                header
+            } else {
+                let spanned = match &mut current {
+                    Some((header, None)) => Some(header.clone()),
+                    Some((header, Some(remaining))) if *remaining > 0 => {
+                        *remaining -= 1;
+                        Some(header.clone())
+                    }
+                    _ => None,
+                };
+                if spanned.is_none() {
+                    current = None;
+                }
+                spanned.unwrap_or_default()
+            };
+
+            let sub_header = sub_headers
+                .filter(|_| self.header_rows > 1)
+                .and_then(|s| s.get(col))
+                .map(|s| collapse(s))
+                .unwrap_or_default();
+            let name = match (header.is_empty(), sub_header.is_empty()) {
+                (_, true) => header,
+                (true, false) => sub_header,
+                (false, false) => format!("{header} {sub_header}"),
+            };
+
+            output.push(self.field(&name).cloned().ok_or(name)?);
+        }
+
+        Ok(output)
+    }
+}
+
+/// The layouts of all the types of reports.
+///
+/// The built-in layouts are used by default, a user layout replaces the
+/// layout of the same type of report.
+///
+/// # Examples
+///
+/// ```rust
+/// use nott_a_database_core::{layout::ReportLayouts, ReportKind};
+///
+/// let mut layouts = ReportLayouts::default();
+/// let kind = layouts
+///     .load_toml(
+///         r#"
+///         kind = "award"
+///         header-rows = 1
+///
+///         [fields]
+///         id = ["Student ID"]
+///         last_name = ["Surname", "Last Name"]
+///         first_name = ["First Name"]
+///         "#,
+///     )
+///     .expect("Invalid layout");
+/// assert_eq!(kind, ReportKind::Award);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ReportLayouts {
+    /// The layout of the result report (0A).
+    pub(crate) result: ReportLayout<ResultHeaders>,
+    /// The layout of the award report (0B).
+    pub(crate) award: ReportLayout<AwardHeader>,
+    /// The layouts of the resit reports (0C, 0D) of each session.
+    resits: HashMap<ResitSession, ReportLayout<ResitHeader>>,
+}
+
+impl Default for ReportLayouts {
+    fn default() -> Self {
+        Self {
+            result: ReportLayout::builtin(ReportKind::Result),
+            award: ReportLayout::builtin(ReportKind::Award),
+            resits: ResitSession::ALL
+                .into_iter()
+                .map(|session| (session, ReportLayout::builtin(session.kind())))
+                .collect(),
+        }
+    }
+}
+
+impl ReportLayouts {
+    /// Gets the layout of the resit report of the `session`.
+    pub(crate) fn resit(&self, session: ResitSession) -> &ReportLayout<ResitHeader> {
+        &self.resits[&session]
+    }
+
+    /// Loads a user layout from a TOML string, replacing the layout of the
+    /// same type of report.
+    ///
+    /// Returns the type of report of the layout.
+    pub fn load_toml(&mut self, s: &str) -> Result<ReportKind, ParseLayoutError> {
+        /// The type of report of a layout.
+        #[derive(Deserialize)]
+        struct Kind {
+            kind: ReportKind,
+        }
+
+        let Kind { kind } = toml::from_str(s).map_err(ParseLayoutError::InvalidToml)?;
+        match kind {
+            ReportKind::Result => self.result = ReportLayout::from_toml(s, kind)?,
+            ReportKind::Award => self.award = ReportLayout::from_toml(s, kind)?,
+            ReportKind::Resit(session) => {
+                self.resits
+                    .insert(session, ReportLayout::from_toml(s, kind)?);
+            }
+        }
+
+        Ok(kind)
+    }
+
+    /// Loads a user layout from a TOML file, replacing the layout of the same
+    /// type of report.
+    ///
+    /// Returns the type of report of the layout.
+    pub fn load<P: AsRef<Path>>(&mut self, file: P) -> Result<ReportKind, ParseLayoutError> {
+        let content = fs::read_to_string(file).map_err(ParseLayoutError::Io)?;
+        self.load_toml(&content)
+    }
+}
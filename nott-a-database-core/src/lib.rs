@@ -2,17 +2,23 @@
 
 pub mod database;
 pub mod errors;
+pub mod layout;
+pub mod palette;
 pub mod spreadsheet_ml;
 
 mod award;
+mod component;
 mod marks;
-mod resit_aug;
-mod resit_may;
+mod report;
+mod resit;
+
+pub use errors::Error;
 
 use std::{fmt::Display, str::FromStr};
 
 use chrono::NaiveDateTime;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 /// A struct representing an academic year.
 ///
@@ -33,7 +39,7 @@ use serde::Deserialize;
 /// let year = AcademicYear::new(2024);
 /// assert_eq!(year.to_string(), "2024/2025");
 /// ```
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
 #[serde(try_from = "&str")]
 pub struct AcademicYear {
     start: isize,
@@ -57,6 +63,52 @@ impl AcademicYear {
             end: start + 1,
         }
     }
+
+    /// Gets the academic year of an intake in the award report (0B), the
+    /// year and month the student started, e.g. `202109` for September 2021.
+    ///
+    /// An academic year starts in September, so a February intake is in the
+    /// academic year which started in the previous September.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use nott_a_database_core::AcademicYear;
+    ///
+    /// let year = AcademicYear::from_intake("202109").expect("Invalid intake");
+    /// assert_eq!(year, AcademicYear::new(2021));
+    /// let year = AcademicYear::from_intake("202202").expect("Invalid intake");
+    /// assert_eq!(year, AcademicYear::new(2021));
+    /// assert_eq!(AcademicYear::from_intake("2021"), None);
+    /// ```
+    pub fn from_intake(intake: &str) -> Option<Self> {
+        let intake = intake.trim();
+        if intake.len() != 6 || !intake.is_ascii() {
+            return None;
+        }
+
+        let year: isize = intake[..4].parse().ok()?;
+        let month: u8 = intake[4..].parse().ok()?;
+        match month {
+            1..=8 => Some(Self::new(year - 1)),
+            9..=12 => Some(Self::new(year)),
+            _ => None,
+        }
+    }
+
+    /// Gets the intake year of a student in the year of study
+    /// `year_of_program` in this academic year, e.g. the 2022/2023 intake for
+    /// a year 3 student in 2024/2025.
+    ///
+    /// Returns `None` if the year of study is not a year from 1.
+    pub fn intake_of(&self, year_of_program: &str) -> Option<Self> {
+        let year: isize = year_of_program.trim().parse().ok()?;
+        if year < 1 {
+            return None;
+        }
+
+        Some(Self::new(self.start - (year - 1)))
+    }
 }
 
 impl Default for AcademicYear {
@@ -190,27 +242,209 @@ pub struct Mark {
     pub status: ModuleStatus,
     /// The fill of the cell.
     pub fill: Option<ColourValue>,
-    /// The first result of the user taken from the student.
-    pub mark: f64,
-    /// The second result of the user taken from the student.
-    pub retake1: Option<f64>,
-    /// The third result of the user taken from the student.
-    pub retake2: Option<f64>,
+    /// Every attempt of the module taken by the student, starting from the
+    /// first sit.
+    pub attempts: Vec<Attempt>,
 }
 
-/// The status of the module taken by the student.
+impl Mark {
+    /// Gets the mark of the first sit of the module.
+    pub fn first_mark(&self) -> Option<MarkValue> {
+        self.attempts.first().map(|attempt| attempt.mark)
+    }
+
+    /// Gets the newest attempt of the module.
+    pub fn newest_attempt(&self) -> Option<&Attempt> {
+        self.attempts.last()
+    }
+
+    /// Sets the current status of the module and the status of its newest
+    /// attempt.
+    ///
+    /// The fill colour of a module only shows the current status, so the
+    /// status of the earlier attempts are unknown and left as [`None`].
+    pub(crate) fn set_status(&mut self, status: ModuleStatus) {
+        if let Some(newest) = self.attempts.last_mut() {
+            newest.status = Some(status.clone());
+        }
+        self.status = status;
+    }
+}
+
+/// The value of a mark, either a number or a code for an attempt without a
+/// mark.
+///
+/// The codes are stored as a mark code without a numeric mark, so they are
+/// excluded from the averages computed by the database, e.g. `AVG(Mark)`.
 ///
-/// The [`ModuleStatus`] colour code is as folows:
+/// # Examples
+///
+/// ```rust
+/// use nott_a_database_core::MarkValue;
 ///
-/// Orange (255, 255, 235, 156) => Component Fail (CF)
+/// assert_eq!("62".parse(), Ok(MarkValue::Number(62.0)));
+/// assert_eq!("ABS".parse(), Ok(MarkValue::Absent));
+/// assert_eq!(MarkValue::Deferred.as_f64(), None);
+/// ```
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum MarkValue {
+    /// A numeric mark.
+    Number(f64),
+    /// The student is absent from the assessment (ABS).
+    Absent,
+    /// The assessment is deferred to a later session (DEF).
+    Deferred,
+    /// The assessment is affected by extenuating circumstances (EC).
+    ExtenuatingCircumstances,
+    /// The student is exempt from the assessment (EX).
+    Exempt,
+    /// The mark is withheld, e.g. pending an investigation (WH).
+    Withheld,
+}
+
+impl Default for MarkValue {
+    fn default() -> Self {
+        Self::Number(0.0)
+    }
+}
+
+impl MarkValue {
+    /// Gets the numeric mark, [`None`] for the codes.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            MarkValue::Number(mark) => Some(*mark),
+            _ => None,
+        }
+    }
+
+    /// Gets the code of the value, [`None`] for numeric marks.
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            MarkValue::Number(_) => None,
+            MarkValue::Absent => Some("ABS"),
+            MarkValue::Deferred => Some("DEF"),
+            MarkValue::ExtenuatingCircumstances => Some("EC"),
+            MarkValue::Exempt => Some("EX"),
+            MarkValue::Withheld => Some("WH"),
+        }
+    }
+}
+
+impl FromStr for MarkValue {
+    type Err = std::num::ParseFloatError;
+
+    /// Parses a numeric mark or a code, the codes are case-insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        Ok(match s.to_uppercase().as_str() {
+            "ABS" => MarkValue::Absent,
+            "DEF" => MarkValue::Deferred,
+            "EC" => MarkValue::ExtenuatingCircumstances,
+            "EX" => MarkValue::Exempt,
+            "WH" => MarkValue::Withheld,
+            _ => MarkValue::Number(s.parse()?),
+        })
+    }
+}
+
+impl Display for MarkValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MarkValue::Number(mark) => write!(f, "{mark}"),
+            _ => write!(f, "{}", self.code().unwrap_or_default()),
+        }
+    }
+}
+
+/// Gets the pass mark of the module with the code `module`, 50 for level 4
+/// (masters) modules and 40 otherwise.
+pub(crate) fn pass_mark(module: &str) -> f64 {
+    if module.chars().nth(4) == Some('4') {
+        50.0
+    } else {
+        40.0
+    }
+}
+
+/// An attempt of a module taken by the student.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Attempt {
+    /// The session the attempt is taken in.
+    pub session: AttemptSession,
+    /// The mark of the attempt.
+    pub mark: MarkValue,
+    /// Whether the mark of the attempt is capped at the pass mark, e.g. a
+    /// resit.
+    pub capped: bool,
+    /// The status of the module after the attempt, [`None`] if the report
+    /// does not state it, e.g. for the attempts before the newest attempt.
+    pub status: Option<ModuleStatus>,
+}
+
+/// The mark of an assessment component of a module, e.g. the coursework or
+/// the exam, parsed from a component mark sheet.
 ///
-/// Green (255, 198, 235, 156) or (255, 198, 239, 206) => Soft Fail
-/// (SF)
+/// The components explain the status of a module, e.g. which component is
+/// failed in a component-fail (CF).
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct ComponentMark {
+    /// The student ID of the student.
+    pub id: i64,
+    /// The module code of the module of the component.
+    pub module: String,
+    /// The name of the component, e.g. `Exam`.
+    pub name: String,
+    /// The weight of the component in the module in percent.
+    pub weight: f64,
+    /// The mark of the component.
+    pub mark: MarkValue,
+    /// The attempt of the module the mark is from, starting from 0 for the
+    /// first sit.
+    pub attempt: usize,
+}
+
+/// The session an [`Attempt`] of a module is taken in.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AttemptSession {
+    /// The first sit of the module in the academic year.
+    #[default]
+    FirstSit,
+    /// A resit in the May resit session (0C).
+    MayResit,
+    /// A resit in the August resit session (0D).
+    AugustResit,
+    /// A retake of the module when repeating the year.
+    RepeatYear,
+    /// A retake from an unknown session, e.g. the retakes stored before the
+    /// sessions were recorded.
+    Unknown,
+}
+
+impl Display for AttemptSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                AttemptSession::FirstSit => "First Sit",
+                AttemptSession::MayResit => "May Resit",
+                AttemptSession::AugustResit => "August Resit",
+                AttemptSession::RepeatYear => "Repeat Year",
+                AttemptSession::Unknown => "Unknown",
+            }
+        )
+    }
+}
+
+/// The status of the module taken by the student.
 ///
-/// Red (255, 255, 199, 206) => Hard Fail (HF)
-#[derive(Clone, Debug, Deserialize, PartialEq)]
+/// The status is taken from the fill colour of the module, see
+/// [`StatusPalette`](palette::StatusPalette) for the colour code.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
 pub enum ModuleStatus {
     /// The student passes the module (No Fill).
+    #[default]
     Pass,
     /// The student soft-failed the module (Green).
     SoftFail,
@@ -220,12 +454,6 @@ pub enum ModuleStatus {
     ComponentFail,
 }
 
-impl Default for ModuleStatus {
-    fn default() -> Self {
-        Self::Pass
-    }
-}
-
 impl Display for ModuleStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -254,6 +482,10 @@ pub struct StudentResult {
     pub autumn_credit: Option<f64>,
     /// The average/mean marks of the student in the Autumn Semester.
     pub autumn_mean: Option<f64>,
+    /// The amount of credits taken by the student in the Summer Semester.
+    pub summer_credit: Option<f64>,
+    /// The average/mean marks of the student in the Summer Semester.
+    pub summer_mean: Option<f64>,
     /// The amount of credits taken by the student in the entire year.
     pub full_credit: Option<f64>,
     /// The average/mean marks of the student in the entire year.
@@ -262,13 +494,16 @@ pub struct StudentResult {
     pub spring_credit: Option<f64>,
     /// The amount of credits taken by the student in the Spring Semester.
     pub spring_mean: Option<f64>,
-    /// The amount of credits taken by the student in the entire year.
+    /// The amount of credits taken by the student in the entire year after
+    /// the newest attempt.
     pub year_credit: Option<f64>,
-    /// The average/mean marks of the student in the entire year.
+    /// The average/mean marks of the student in the entire year after the
+    /// newest attempt.
     pub year_prog_average: Option<f64>,
-    /// The number of credits that has mark <30
+    /// The number of credits that has mark <30 after the newest attempt.
     pub credits_l3_lt30: Option<f64>,
-    /// The number of credits that has mark between 30-39.
+    /// The number of credits that has mark between 30-39 after the newest
+    /// attempt.
     pub credits_l3_30_39: Option<f64>,
     /// The number of credits that has mark <40
     pub credits_l4_lt40: Option<f64>,
@@ -280,6 +515,12 @@ pub struct StudentResult {
     pub modules: Vec<Mark>,
     /// Remarks regardding the students result.
     pub remarks: Option<String>,
+    /// The resit session of the report the result is parsed from, [`None`]
+    /// for the result report (0A).
+    pub session: Option<ResitSession>,
+    /// The year figures after every attempt, starting from the first
+    /// attempt before any resits.
+    pub attempts: Vec<ResultAttempt>,
 }
 
 impl StudentResult {
@@ -287,4 +528,550 @@ impl StudentResult {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Gets the figures of the `attempt`, adding the attempts before it if
+    /// they are missing.
+    pub(crate) fn attempt_mut(&mut self, attempt: usize) -> &mut ResultAttempt {
+        if self.attempts.len() <= attempt {
+            self.attempts.resize_with(attempt + 1, Default::default);
+        }
+        &mut self.attempts[attempt]
+    }
+}
+
+/// The year figures of a [`StudentResult`] after an attempt.
+///
+/// The resit reports (0C, 0D) list the figures of the first attempt followed
+/// by the figures after each resit, the result report (0A) only has the
+/// first attempt.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct ResultAttempt {
+    /// The amount of credits taken by the student in the entire year.
+    pub year_credit: Option<f64>,
+    /// The average/mean marks of the student in the entire year.
+    pub year_prog_average: Option<f64>,
+    /// The number of credits that has mark <30
+    pub credits_l3_lt30: Option<f64>,
+    /// The number of credits that has mark between 30-39.
+    pub credits_l3_30_39: Option<f64>,
+}
+
+/// The different type of reports (raw data) that can be parsed.
+///
+/// The types are named `result`, `award`, `resit-may` and `resit-aug`, e.g.
+/// in the layouts and the import batches.
+///
+/// # Examples
+///
+/// ```rust
+/// use nott_a_database_core::{ReportKind, ResitSession};
+///
+/// assert_eq!("resit-aug".parse(), Ok(ReportKind::Resit(ResitSession::August)));
+/// assert_eq!(ReportKind::Resit(ResitSession::May).name(), "resit-may");
+/// assert_eq!(ReportKind::Resit(ResitSession::May).to_string(), "May Resit (0C)");
+/// ```
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(try_from = "String", into = "&'static str")]
+pub enum ReportKind {
+    /// The result report (0A), parsed with [`StudentResult::from_result`].
+    Result,
+    /// The award report (0B), parsed with [`StudentInfo::from_award`].
+    Award,
+    /// The resit report of the session, parsed with
+    /// [`StudentResult::from_resit`].
+    Resit(ResitSession),
+}
+
+impl Display for ReportKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportKind::Result => write!(f, "Result (0A)"),
+            ReportKind::Award => write!(f, "Award (0B)"),
+            ReportKind::Resit(session) => write!(f, "{session} Resit ({})", session.report()),
+        }
+    }
+}
+
+impl FromStr for ReportKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "result" => Ok(ReportKind::Result),
+            "award" => Ok(ReportKind::Award),
+            _ => ResitSession::ALL
+                .into_iter()
+                .map(ReportKind::Resit)
+                .find(|kind| kind.name() == s)
+                .ok_or_else(|| format!("Unknown type of report {s}")),
+        }
+    }
+}
+
+impl TryFrom<String> for ReportKind {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ReportKind> for &'static str {
+    fn from(kind: ReportKind) -> Self {
+        kind.name()
+    }
+}
+
+impl ReportKind {
+    /// Gets the name of the type of report, e.g. `resit-may`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReportKind::Result => "result",
+            ReportKind::Award => "award",
+            ReportKind::Resit(session) => session.kind_name(),
+        }
+    }
+
+    /// Gets the [`ResitSession`] of a resit report, [`None`] for the other
+    /// reports.
+    pub fn resit_session(&self) -> Option<ResitSession> {
+        match self {
+            ReportKind::Resit(session) => Some(*session),
+            ReportKind::Result | ReportKind::Award => None,
+        }
+    }
+}
+
+/// The session of a resit report.
+///
+/// The resit reports of every session are parsed by the same parser, see
+/// [`StudentResult::from_resit`]. The values of a session, e.g. its name in
+/// the database and the type of its report, are all taken from the session.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResitSession {
+    /// The May resit session (0C).
+    May,
+    /// The August resit session (0D).
+    August,
+}
+
+impl ResitSession {
+    /// Every resit session in the order of the academic year.
+    pub const ALL: [ResitSession; 2] = [ResitSession::May, ResitSession::August];
+
+    /// Gets the [`ReportKind`] of the resit report of the session.
+    pub fn kind(&self) -> ReportKind {
+        ReportKind::Resit(*self)
+    }
+
+    /// Gets the name of the type of the resit report of the session, see
+    /// [`ReportKind::name`].
+    fn kind_name(&self) -> &'static str {
+        match self {
+            ResitSession::May => "resit-may",
+            ResitSession::August => "resit-aug",
+        }
+    }
+
+    /// Gets the code of the resit report of the session, e.g. `0C`.
+    pub fn report(&self) -> &'static str {
+        match self {
+            ResitSession::May => "0C",
+            ResitSession::August => "0D",
+        }
+    }
+
+    /// Gets the name of the session stored in the database, e.g. `May`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResitSession::May => "May",
+            ResitSession::August => "August",
+        }
+    }
+
+    /// Gets the [`AttemptSession`] of the resits in the session.
+    pub fn attempt_session(&self) -> AttemptSession {
+        match self {
+            ResitSession::May => AttemptSession::MayResit,
+            ResitSession::August => AttemptSession::AugustResit,
+        }
+    }
+}
+
+impl Display for ResitSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// How confident the detected [`ReportKind`] of a workbook is.
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum DetectionConfidence {
+    /// Only the layout of the workbook (worksheet names or key columns)
+    /// matches the report.
+    Low,
+    /// All the headers matches the report but not the layout of the workbook.
+    Medium,
+    /// Both the headers and the layout of the workbook matches the report.
+    High,
+}
+
+impl Display for DetectionConfidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                DetectionConfidence::Low => "Low",
+                DetectionConfidence::Medium => "Medium",
+                DetectionConfidence::High => "High",
+            }
+        )
+    }
+}
+
+/// The result of detecting the [`ReportKind`] of a workbook.
+#[derive(Clone, Debug, Serialize)]
+pub struct ReportDetection {
+    /// The detected type of report.
+    pub kind: ReportKind,
+    /// How confident the detection is.
+    pub confidence: DetectionConfidence,
+    /// A human readable explanation of why the report was detected.
+    pub explanation: String,
+}
+
+/// The options used when parsing a report.
+///
+/// # Examples
+///
+/// ```rust
+/// use nott_a_database_core::{ParseOptions, StudentResult};
+///
+/// let options = ParseOptions {
+///     lenient: true,
+///     ..Default::default()
+/// };
+/// let (results, diagnostics) = StudentResult::from_result_with_options("./sample_0A.xlsx", &options)
+///     .expect("Invalid workbook");
+/// assert_eq!(results.len(), 45);
+/// assert!(diagnostics.is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+    /// Skips and reports invalid rows instead of returning the first invalid
+    /// row as an error. The invalid resits of the resit reports are always
+    /// skipped and reported without skipping their row.
+    pub lenient: bool,
+    /// The palette used to get the [`ModuleStatus`] from the fill colours.
+    pub palette: palette::StatusPalette,
+    /// The palette used to get the [`ModuleStatus`] from the fill colours of
+    /// the resit reports, see [`StatusPalette::resit`](palette::StatusPalette::resit).
+    pub resit_palette: palette::StatusPalette,
+    /// The layouts of the header rows of the reports.
+    pub layouts: layout::ReportLayouts,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            lenient: false,
+            palette: palette::StatusPalette::default(),
+            resit_palette: palette::StatusPalette::resit(),
+            layouts: layout::ReportLayouts::default(),
+        }
+    }
+}
+
+/// The resolution of a module credit conflict, a module found with a
+/// different number of credits from the credits stored in the database.
+///
+/// # Examples
+///
+/// ```rust
+/// use nott_a_database_core::CreditPolicy;
+///
+/// let policy: CreditPolicy = "overwrite".parse().expect("Invalid policy");
+/// assert_eq!(policy, CreditPolicy::Overwrite);
+/// assert_eq!(CreditPolicy::default(), CreditPolicy::Keep);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CreditPolicy {
+    /// Keeps the stored credits of the module.
+    #[default]
+    Keep,
+    /// Overwrites the stored credits of the module with the new credits.
+    Overwrite,
+    /// Fails the import with [`errors::Error::CreditConflict`].
+    Fail,
+}
+
+impl Display for CreditPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                CreditPolicy::Keep => "Keep",
+                CreditPolicy::Overwrite => "Overwrite",
+                CreditPolicy::Fail => "Fail",
+            }
+        )
+    }
+}
+
+impl FromStr for CreditPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "keep" => Ok(CreditPolicy::Keep),
+            "overwrite" => Ok(CreditPolicy::Overwrite),
+            "fail" => Ok(CreditPolicy::Fail),
+            _ => Err(String::from(
+                "The credit policy should be one of \"keep\", \"overwrite\" or \"fail\"",
+            )),
+        }
+    }
+}
+
+/// The resolution of a student found with different information from the
+/// information stored in the database, e.g. a new plan, or of a component
+/// mark found with a different weight or mark from the stored component.
+///
+/// The intake year of a student is never updated. A stored component mark has
+/// no missing fields, so it is only updated by [`ConflictPolicy::Overwrite`].
+///
+/// # Examples
+///
+/// ```rust
+/// use nott_a_database_core::ConflictPolicy;
+///
+/// let policy: ConflictPolicy = "fill-missing".parse().expect("Invalid policy");
+/// assert_eq!(policy, ConflictPolicy::FillMissing);
+/// assert_eq!(ConflictPolicy::default(), ConflictPolicy::Overwrite);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictPolicy {
+    /// Overwrites the stored information with the new information.
+    #[default]
+    Overwrite,
+    /// Only fills the information which is not stored yet.
+    FillMissing,
+    /// Keeps the stored information of the student.
+    KeepExisting,
+    /// Fails the import with [`errors::Error::StudentConflict`] if the stored
+    /// information would be changed, the missing information is filled.
+    Fail,
+}
+
+impl Display for ConflictPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ConflictPolicy::Overwrite => "Overwrite",
+                ConflictPolicy::FillMissing => "Fill Missing",
+                ConflictPolicy::KeepExisting => "Keep Existing",
+                ConflictPolicy::Fail => "Fail",
+            }
+        )
+    }
+}
+
+impl FromStr for ConflictPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "overwrite" => Ok(ConflictPolicy::Overwrite),
+            "fill-missing" => Ok(ConflictPolicy::FillMissing),
+            "keep-existing" => Ok(ConflictPolicy::KeepExisting),
+            "fail" => Ok(ConflictPolicy::Fail),
+            _ => Err(String::from(
+                "The conflict policy should be one of \"overwrite\", \"fill-missing\", \"keep-existing\" or \"fail\"",
+            )),
+        }
+    }
+}
+
+/// An import of a report into the database, recorded so the rows inserted or
+/// updated by the import can be rolled back.
+///
+/// # Examples
+///
+/// ```rust
+/// use nott_a_database_core::{AcademicYear, ImportBatch, ReportKind};
+///
+/// let batch = ImportBatch::new(
+///     "sample_0A.xlsx",
+///     b"abc",
+///     ReportKind::Result,
+///     AcademicYear::new(2024),
+/// );
+/// assert_eq!(
+///     batch.sha256,
+///     "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct ImportBatch {
+    /// The name of the imported file.
+    pub file_name: String,
+    /// The SHA-256 hash of the content of the file in hex.
+    pub sha256: String,
+    /// The type of report imported.
+    pub kind: ReportKind,
+    /// The academic year of the report.
+    pub academic_year: AcademicYear,
+    /// The number of rows imported.
+    pub rows: usize,
+    /// The number of invalid rows skipped when parsing leniently.
+    pub skipped: usize,
+}
+
+impl ImportBatch {
+    /// Creates a new [`ImportBatch`] of the file `file_name` with the
+    /// `content`, without any rows.
+    pub fn new(
+        file_name: impl Into<String>,
+        content: &[u8],
+        kind: ReportKind,
+        academic_year: AcademicYear,
+    ) -> Self {
+        Self {
+            file_name: file_name.into(),
+            sha256: format!("{:x}", Sha256::digest(content)),
+            kind,
+            academic_year,
+            rows: 0,
+            skipped: 0,
+        }
+    }
+}
+
+/// A point in the history of the marks and results of the database.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AsOf {
+    /// The date and time in UTC.
+    Time(NaiveDateTime),
+    /// Right after the import batch with the ID, see [`ImportBatch`].
+    Batch(i64),
+}
+
+/// A version of the mark of a module taken by a student in the history of the
+/// database.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[cfg_attr(feature = "async", derive(sqlx::FromRow))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "async", sqlx(rename_all = "PascalCase"))]
+pub struct MarkVersion {
+    /// The academic year the module was taken.
+    pub academic_year: String,
+    /// The module code of the module.
+    pub module: String,
+    /// The numeric mark, if the mark is not a code.
+    pub mark: Option<f64>,
+    /// The mark code, e.g. `ABS`, if the mark is not numeric.
+    pub mark_code: Option<String>,
+    /// The fill colour of the mark in the report.
+    pub fill: Option<i64>,
+    /// The status of the module, e.g. `Pass`.
+    pub status: String,
+    /// The import batch which imported the version, if it is known.
+    pub batch: Option<i64>,
+    /// The time the version was inserted or updated in UTC.
+    pub valid_from: String,
+    /// The time the version was replaced or deleted in UTC, if it was.
+    pub valid_to: Option<String>,
+}
+
+/// A version of an attempt of the mark of a module taken by a student in the
+/// history of the database.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[cfg_attr(feature = "async", derive(sqlx::FromRow))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "async", sqlx(rename_all = "PascalCase"))]
+pub struct MarkAttemptVersion {
+    /// The academic year the module was taken.
+    pub academic_year: String,
+    /// The module code of the module.
+    pub module: String,
+    /// The number of the attempt, the first sit is `0`.
+    pub attempt: i64,
+    /// The session of the attempt, e.g. `May Resit`.
+    pub session: String,
+    /// The numeric mark, if the mark is not a code.
+    pub mark: Option<f64>,
+    /// The mark code, e.g. `ABS`, if the mark is not numeric.
+    pub mark_code: Option<String>,
+    /// Whether the mark is capped at the pass mark.
+    pub capped: bool,
+    /// The status of the module after the attempt, if it is known.
+    pub status: Option<String>,
+    /// The import batch which imported the version, if it is known.
+    pub batch: Option<i64>,
+    /// The time the version was inserted or updated in UTC.
+    pub valid_from: String,
+    /// The time the version was replaced or deleted in UTC, if it was.
+    pub valid_to: Option<String>,
+}
+
+/// A version of the result of a student in an academic year in the history of
+/// the database.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[cfg_attr(feature = "async", derive(sqlx::FromRow))]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "async", sqlx(rename_all = "PascalCase"))]
+pub struct ResultVersion {
+    /// The academic year of the result.
+    pub academic_year: String,
+    /// The year of studies of the student.
+    pub year_of_study: i64,
+    /// The amount of credits taken in the Autumn Semester.
+    pub autumn_credits: Option<i64>,
+    /// The mean marks in the Autumn Semester.
+    pub autumn_mean: Option<f64>,
+    /// The amount of credits taken in the Spring Semester.
+    pub spring_credits: Option<i64>,
+    /// The mean marks in the Spring Semester.
+    pub spring_mean: Option<f64>,
+    /// The amount of credits taken in the Summer Semester.
+    pub summer_credits: Option<i64>,
+    /// The mean marks in the Summer Semester.
+    pub summer_mean: Option<f64>,
+    /// The amount of credits taken in the entire year.
+    pub year_credits: Option<i64>,
+    /// The mean marks in the entire year.
+    pub year_mean: Option<f64>,
+    /// The progression status of the student.
+    pub progression: Option<String>,
+    /// The remarks about the student.
+    pub remarks: Option<String>,
+    /// The import batch which imported the version, if it is known.
+    pub batch: Option<i64>,
+    /// The time the version was inserted or updated in UTC.
+    pub valid_from: String,
+    /// The time the version was replaced or deleted in UTC, if it was.
+    pub valid_to: Option<String>,
+}
+
+/// The results, marks and attempts of a student at a point in the history of
+/// the database, see [`AsOf`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StudentRecord {
+    /// The ID of the student.
+    pub id: i64,
+    /// The results of the student in each academic year.
+    pub results: Vec<ResultVersion>,
+    /// The marks of the modules taken by the student.
+    pub marks: Vec<MarkVersion>,
+    /// The attempts of the marks of the modules taken by the student.
+    pub attempts: Vec<MarkAttemptVersion>,
 }
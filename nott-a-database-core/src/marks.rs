@@ -1,16 +1,29 @@
 //! Parser for student marks data.
-use std::{path::Path, str::FromStr};
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek},
+    path::Path,
+    str::FromStr,
+};
+
+use calamine::{Data, DataType, Range, Reader, XlsxError};
 
-use calamine::{open_workbook, Data, DataType, Range, Reader, Xlsx};
+use serde::Deserialize;
 
 use crate::{
-    errors::{ParseResultError, ParseResultRowError},
-    spreadsheet_ml::{get_data, Relationships, SheetRow, Styles, Workbook, Worksheet, XlsxColumns},
-    ColourValue, Mark, ModuleStatus, StudentResult,
+    errors::{ParseResultError, ParseResultRowError, RowDiagnostic},
+    palette::StatusPalette,
+    report::SheetHeaders,
+    spreadsheet_ml::{
+        conditional_fill, ConditionalFormatting, SheetRow, SpreadsheetArchive, Styles, Worksheet,
+        XlsxColumns,
+    },
+    Attempt, ColourValue, Mark, MarkValue, ModuleStatus, ParseOptions, StudentResult,
 };
 
 /// All the possible header column possible for [`Mark`] data.
-#[derive(Debug)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
 pub enum ResultHeaders {
     /// The entry number in the sheet.
     No,
@@ -28,6 +41,10 @@ pub enum ResultHeaders {
     AutumnCredit,
     /// The average/mean marks of the student in the Autumn Semester.
     AutumnMean,
+    /// The amount of credits taken by the student in the Summer Semester.
+    SummerCredit,
+    /// The average/mean marks of the student in the Summer Semester.
+    SummerMean,
     /// The amount of credits taken by the student in the Spring Semester.
     FullCredit,
     /// The amount of credits taken by the student in the Spring Semester.
@@ -43,10 +60,12 @@ pub enum ResultHeaders {
     /// Credits (L3) <30
     CreditsL3Lt30,
     /// Credits (L3) 30-39
+    #[serde(rename = "credits_l3_30_39")]
     CreditsL33039,
     /// Credits (L4) <40
     CreditsL4Lt40,
     /// Credits (L4) 40-49
+    #[serde(rename = "credits_l4_40_49")]
     CreditsL44049,
     /// The progression status of the student, e.g. requires retake.
     Progression,
@@ -57,314 +76,424 @@ pub enum ResultHeaders {
 }
 
 impl ResultHeaders {
-    /// Gets the header vector from the headers and sub-headers.
-    pub fn get_headers(
-        headers: &[String],
-        sub_headers: &[String],
-    ) -> Result<Vec<ResultHeaders>, ParseResultError> {
-        /// All the possible status when parsing the headers of the raw data.
-        ///
-        /// The status determine what the next header should be based on the sub-headers.
-        #[derive(Debug)]
-        enum HeaderStatus {
-            /// No special treatment needed for the next header.
-            Continue,
-            /// Concatenate the current and next header with the sub-header.
-            ConcatSubheader,
-            /// Treat the next header as the "Module" header.
-            Module,
+    /// Gets the type of value expected in the column.
+    pub fn expected(&self) -> &'static str {
+        match self {
+            Self::No => "an integer or an empty cell",
+            Self::Id => "an integer",
+            Self::LastName
+            | Self::FirstName
+            | Self::Plan
+            | Self::YearOfProgram
+            | Self::Progression => "text",
+            Self::AutumnCredit
+            | Self::AutumnMean
+            | Self::SummerCredit
+            | Self::SummerMean
+            | Self::FullCredit
+            | Self::FullMean
+            | Self::SpringCredit
+            | Self::SpringMean
+            | Self::YearCredit
+            | Self::YearProgAverage
+            | Self::CreditsL3Lt30
+            | Self::CreditsL33039
+            | Self::CreditsL4Lt40
+            | Self::CreditsL44049 => "a number or an empty cell",
+            Self::Modules => "the module code, credits and mark (or code) on separate lines",
+            Self::Remarks => "text or an empty cell",
         }
-
-        // Creating Headers
-        let mut status = (HeaderStatus::Continue, String::new());
-        let mut output = vec![];
-        for (header, sub_header) in headers.iter().zip(sub_headers.iter()) {
-            let sub_header = sub_header
-                // Convert to Lowercase
-                .to_lowercase()
-                // Fix "<" Credits (L3) and Credits (L4)
-                .replace("<", "lt")
-                // Fix "-" Credits (L3) and Credits (L4)
-                .replace("-", "_")
-                // Replace Space with _
-                .replace(" ", "_");
-
-            // Convert to Lowercase
-            let mut header = header
-                .to_lowercase()
-                // Fix Year of Program
-                .replace("\r\n", "")
-                // Fix Credits (L3) and Credits (L4)
-                .replace("(", "")
-                .replace(")", "")
-                // Replace Space with _
-                .replace(" ", "_");
-
-            match status.0 {
-                HeaderStatus::Continue => {
-                    if header == "autumn"
-                        || header == "full"
-                        || header == "spring"
-                        || header == "year"
-                        || header == "credits_l3"
-                        || header == "credits_l4"
-                    {
-                        status.0 = HeaderStatus::ConcatSubheader;
-                        status.1 = header.to_owned();
-                        header += "_";
-                        header += &sub_header;
-                    } else if header == "modules" {
-                        status.0 = HeaderStatus::Module;
-                        status.1 = header.to_owned();
-                    }
-                }
-                HeaderStatus::ConcatSubheader => {
-                    header = status.1.to_owned();
-                    header += "_";
-                    header += &sub_header;
-                    status.0 = HeaderStatus::Continue;
-                    status.1 = String::new();
-                }
-                HeaderStatus::Module => {
-                    if !header.is_empty() {
-                        status.0 = HeaderStatus::Continue;
-                        status.1 = String::new();
-                        continue;
-                    }
-                    header = status.1.to_owned();
-                }
-            }
-            output.push(self::ResultHeaders::from_str(&header)?);
-        }
-
-        Ok(output)
     }
 }
 
-impl FromStr for ResultHeaders {
-    type Err = ParseResultError;
+/// The styles of a row of result report (0A) used to get the status of the
+/// modules.
+struct RowStyles<'a> {
+    /// The row number (1-based) of the row in the worksheet.
+    row_no: usize,
+    /// The styles of the workbook.
+    styles: &'a Styles,
+    /// The cells of the row in the worksheet, [`None`] if the row is not
+    /// stored in the worksheet.
+    row_data: Option<&'a SheetRow>,
+    /// The values of the row used to evaluate the conditional formatting.
+    values: &'a [Data],
+    /// The conditional formatting of the worksheet.
+    conditional_formatting: &'a [ConditionalFormatting],
+    /// The palette used to get the status from the fill colour.
+    palette: &'a StatusPalette,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "no" => Ok(Self::No),
-            "id" => Ok(Self::Id),
-            "last_name" => Ok(Self::LastName),
-            "first_name" => Ok(Self::FirstName),
-            "plan" => Ok(Self::Plan),
-            "year_of_program" => Ok(Self::YearOfProgram),
-            "autumn_credit" => Ok(Self::AutumnCredit),
-            "autumn_mean" => Ok(Self::AutumnMean),
-            "full_credit" => Ok(Self::FullCredit),
-            "full_mean" => Ok(Self::FullMean),
-            "spring_credit" => Ok(Self::SpringCredit),
-            "spring_mean" => Ok(Self::SpringMean),
-            "year_credit" => Ok(Self::YearCredit),
-            "year_prog_average" => Ok(Self::YearProgAverage),
-            "credits_l3_lt30" => Ok(Self::CreditsL3Lt30),
-            "credits_l3_30_39" => Ok(Self::CreditsL33039),
-            "credits_l4_lt40" => Ok(Self::CreditsL4Lt40),
-            "credits_l4_40_49" => Ok(Self::CreditsL44049),
-            "progression" => Ok(Self::Progression),
-            "modules" => Ok(Self::Modules),
-            "remarks" => Ok(Self::Remarks),
-            _ => Err(ParseResultError::InvalidHeader(s.to_owned())),
-        }
+impl RowStyles<'_> {
+    /// Gets the fill colour applied by the conditional formatting to the
+    /// cell `cell` of the row.
+    ///
+    /// The rules are evaluated against the raw values of the cells in the
+    /// row, cells in other rows are treated as empty.
+    fn conditional_fill(&self, cell: &str) -> Option<ColourValue> {
+        conditional_fill(
+            self.conditional_formatting,
+            self.styles,
+            cell,
+            |reference: &str| {
+                let (index, _) = XlsxColumns::new()
+                    .take(self.values.len())
+                    .enumerate()
+                    .find(|(_, column)| *reference == format!("{column}{}", self.row_no))?;
+                self.values.get(index).cloned()
+            },
+        )
     }
 }
 
 impl StudentResult {
     /// Parse a row of data from result report (0A).
+    ///
+    /// The `row_no` is the row number (1-based) of the row in the `sheet`.
+    /// The status of the modules is taken from the fill of their cells,
+    /// including the fill of the conditional formatting of the `sheet`, with
+    /// the palette of the `options`.
     pub fn from_result_row(
         headers: &[ResultHeaders],
         row: &[Data],
         row_no: usize,
         styles: &Styles,
-        row_data: &SheetRow,
+        sheet: &Worksheet,
+        options: &ParseOptions,
     ) -> Result<Self, ParseResultRowError> {
         let mut output = Self::new();
+        let row_styles = RowStyles {
+            row_no,
+            styles,
+            row_data: sheet.row(row_no),
+            values: row,
+            conditional_formatting: &sheet.conditional_formatting,
+            palette: &options.palette,
+        };
 
         for (col, (header, data)) in headers.iter().zip(row).enumerate() {
-            match header {
-                ResultHeaders::No => output.no = data.as_i64(),
-                ResultHeaders::Id => {
-                    output.student_info.id = data.as_i64().ok_or(ParseResultRowError::InvalidID)?
-                }
-                ResultHeaders::LastName => {
-                    output.student_info.last_name = data
-                        .as_string()
-                        .ok_or(ParseResultRowError::InvalidLastName)?
-                }
-                ResultHeaders::FirstName => {
-                    output.student_info.first_name = data
-                        .as_string()
-                        .ok_or(ParseResultRowError::InvalidFirstName)?
-                }
-                ResultHeaders::Plan => {
-                    output.student_info.plan =
-                        data.as_string().ok_or(ParseResultRowError::InvalidPlan)?
-                }
-                ResultHeaders::YearOfProgram => {
-                    output.year_of_program = data
-                        .as_string()
-                        .ok_or(ParseResultRowError::InvalidYearOfProgram)?
-                }
-                ResultHeaders::AutumnCredit => output.autumn_credit = data.as_f64(),
-                ResultHeaders::AutumnMean => output.autumn_mean = data.as_f64(),
-                ResultHeaders::SpringCredit => output.spring_credit = data.as_f64(),
-                ResultHeaders::SpringMean => output.spring_mean = data.as_f64(),
-                ResultHeaders::FullCredit => output.full_credit = data.as_f64(),
-                ResultHeaders::FullMean => output.full_mean = data.as_f64(),
-                ResultHeaders::YearCredit => output.year_credit = data.as_f64(),
-                ResultHeaders::YearProgAverage => output.year_prog_average = data.as_f64(),
-                ResultHeaders::CreditsL3Lt30 => output.credits_l3_lt30 = data.as_f64(),
-                ResultHeaders::CreditsL33039 => output.credits_l3_30_39 = data.as_f64(),
-                ResultHeaders::CreditsL4Lt40 => output.credits_l4_lt40 = data.as_f64(),
-                ResultHeaders::CreditsL44049 => output.credits_l4_40_49 = data.as_f64(),
-                ResultHeaders::Progression => {
-                    output.progression = data
-                        .as_string()
-                        .ok_or(ParseResultRowError::InvalidProgression)?
+            output.set_result_cell(header, data, col, &row_styles)?;
+        }
+
+        Ok(output)
+    }
+
+    /// Parse a row of data from result report (0A), collecting the errors of
+    /// every invalid cell with its column.
+    fn from_result_row_lenient(
+        headers: &[ResultHeaders],
+        row: &[Data],
+        row_styles: &RowStyles,
+    ) -> Result<Self, Vec<(usize, ParseResultRowError)>> {
+        let mut output = Self::new();
+
+        let errors: Vec<_> = headers
+            .iter()
+            .zip(row)
+            .enumerate()
+            .filter_map(|(col, (header, data))| {
+                output
+                    .set_result_cell(header, data, col, row_styles)
+                    .err()
+                    .map(|e| (col, e))
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(output)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Sets the field of `header` from a cell of result report (0A) raw data.
+    fn set_result_cell(
+        &mut self,
+        header: &ResultHeaders,
+        data: &Data,
+        col: usize,
+        row_styles: &RowStyles,
+    ) -> Result<(), ParseResultRowError> {
+        match header {
+            ResultHeaders::No => self.no = data.as_i64(),
+            ResultHeaders::Id => {
+                self.student_info.id = data.as_i64().ok_or(ParseResultRowError::InvalidID)?
+            }
+            ResultHeaders::LastName => {
+                self.student_info.last_name = data
+                    .as_string()
+                    .ok_or(ParseResultRowError::InvalidLastName)?
+            }
+            ResultHeaders::FirstName => {
+                self.student_info.first_name = data
+                    .as_string()
+                    .ok_or(ParseResultRowError::InvalidFirstName)?
+            }
+            ResultHeaders::Plan => {
+                self.student_info.plan = data.as_string().ok_or(ParseResultRowError::InvalidPlan)?
+            }
+            ResultHeaders::YearOfProgram => {
+                self.year_of_program = data
+                    .as_string()
+                    .ok_or(ParseResultRowError::InvalidYearOfProgram)?
+            }
+            ResultHeaders::AutumnCredit => self.autumn_credit = data.as_f64(),
+            ResultHeaders::AutumnMean => self.autumn_mean = data.as_f64(),
+            ResultHeaders::SummerCredit => self.summer_credit = data.as_f64(),
+            ResultHeaders::SummerMean => self.summer_mean = data.as_f64(),
+            ResultHeaders::SpringCredit => self.spring_credit = data.as_f64(),
+            ResultHeaders::SpringMean => self.spring_mean = data.as_f64(),
+            ResultHeaders::FullCredit => self.full_credit = data.as_f64(),
+            ResultHeaders::FullMean => self.full_mean = data.as_f64(),
+            // The result report only has the first attempt
+            ResultHeaders::YearCredit => {
+                self.year_credit = data.as_f64();
+                self.attempt_mut(0).year_credit = self.year_credit;
+            }
+            ResultHeaders::YearProgAverage => {
+                self.year_prog_average = data.as_f64();
+                self.attempt_mut(0).year_prog_average = self.year_prog_average;
+            }
+            ResultHeaders::CreditsL3Lt30 => {
+                self.credits_l3_lt30 = data.as_f64();
+                self.attempt_mut(0).credits_l3_lt30 = self.credits_l3_lt30;
+            }
+            ResultHeaders::CreditsL33039 => {
+                self.credits_l3_30_39 = data.as_f64();
+                self.attempt_mut(0).credits_l3_30_39 = self.credits_l3_30_39;
+            }
+            ResultHeaders::CreditsL4Lt40 => self.credits_l4_lt40 = data.as_f64(),
+            ResultHeaders::CreditsL44049 => self.credits_l4_40_49 = data.as_f64(),
+            ResultHeaders::Progression => {
+                self.progression = data
+                    .as_string()
+                    .ok_or(ParseResultRowError::InvalidProgression)?
+            }
+            ResultHeaders::Modules => {
+                if data.is_empty() {
+                    return Ok(());
                 }
-                ResultHeaders::Modules => {
-                    if data.is_empty() {
-                        continue;
-                    }
-                    let tmp = data.as_string().ok_or(ParseResultRowError::InvalidModule)?;
-                    let mut tmp = Mark::from_str(&tmp)?;
-
-                    let col_name = XlsxColumns::new()
-                        .nth(col)
-                        .expect("There should be an infinite amount of XLSX columns.");
-                    let cell = col_name + &row_no.to_string();
-                    let cell = row_data
-                        .cells
-                        .iter()
-                        .find(|c| c.cell == cell)
-                        .expect("There should be a cell found in row data.");
-                    let style_id: usize = cell
-                        .style
-                        .parse()
-                        .map_err(|_| ParseResultRowError::InvalidModule)?;
-                    let fill_id = styles.cell_xfs.xf[style_id].fill_id;
-                    let fill = &styles.fills.fill[fill_id];
-
-                    if let Some(colour) = &fill.pattern_fill.fg_color {
-                        tmp.status = ModuleStatus::try_from(&colour.rgb)?;
-                        tmp.fill = Some(colour.rgb.clone());
+                let tmp = data.as_string().ok_or(ParseResultRowError::InvalidModule)?;
+                let mut tmp = Mark::from_str(&tmp)?;
+
+                let col_name = XlsxColumns::new()
+                    .nth(col)
+                    .expect("There should be an infinite amount of XLSX columns.");
+                let cell_name = col_name + &row_styles.row_no.to_string();
+                let cell = row_styles
+                    .row_data
+                    .iter()
+                    .flat_map(|row| &row.cells)
+                    .find(|c| c.cell == cell_name)
+                    .ok_or(ParseResultRowError::InvalidModule)?;
+                let style_id: usize = cell
+                    .style
+                    .parse()
+                    .map_err(|_| ParseResultRowError::InvalidModule)?;
+
+                // The fill of the conditional formatting takes precedence
+                // over the fill of the cell style
+                let fill = row_styles
+                    .conditional_fill(&cell_name)
+                    .or_else(|| row_styles.styles.fill(style_id));
+                // Extracting module status from the fill colour, highlights
+                // are kept without changing the status
+                let mut status = ModuleStatus::default();
+                if let Some(colour) = fill {
+                    if !row_styles.palette.is_highlight(&colour) {
+                        status = row_styles
+                            .palette
+                            .status(&colour)
+                            .ok_or(ParseResultRowError::InvalidModule)?;
                     }
-                    output.modules.push(tmp);
+                    tmp.fill = Some(colour);
                 }
-                ResultHeaders::Remarks => output.remarks = data.as_string(),
+                tmp.set_status(status);
+                self.modules.push(tmp);
             }
+            ResultHeaders::Remarks => self.remarks = data.as_string(),
         }
 
-        Ok(output)
+        Ok(())
     }
 
     /// Parse a worksheet in from result report (0A).
-    pub fn from_result_worksheet<P: AsRef<Path>>(
+    ///
+    /// The `archive` is the opened result report (0A) workbook.
+    pub fn from_result_worksheet(
         name: &str,
         range: Range<Data>,
-        file: P,
-        workbook: &Workbook,
-        relationship: &Relationships,
-        styles: &Styles,
+        archive: &SpreadsheetArchive,
     ) -> Result<Vec<StudentResult>, ParseResultError> {
-        // Extract worksheet and relationship metadata
-        let worksheet = workbook
-            .sheets
-            .sheet
-            .iter()
-            .find(|x| x.name == name)
-            .expect("The parsed workbook XML should have the sheet.");
-        let sheet_file = &relationship
-            .relationship
-            .iter()
-            .find(|x| x.id == worksheet.rid)
-            .expect("The parsed relationship XML should have the relationship.")
-            .path;
-
-        // Extract raw worksheet data
-        let worksheet_path = if sheet_file.starts_with("../") {
-            Path::new(
-                sheet_file
-                    .strip_prefix("../")
-                    .expect("Path should have \"../\" prefix"),
-            )
-        } else if sheet_file.starts_with("/") {
-            Path::new(
-                sheet_file
-                    .strip_prefix("/")
-                    .expect("Path should have \"/\" prefix"),
-            )
-        } else {
-            &Path::new("xl/").join(sheet_file)
-        };
+        Self::parse_result_worksheet(name, range, archive, &ParseOptions::default())
+            .map(|(data, _)| data)
+    }
 
-        let sheet: Worksheet = get_data(
-            &file,
-            worksheet_path
-                .to_str()
-                .expect("Invalid path in Workbook archive."),
-        )
-        .map_err(ParseResultError::StyleError)?;
+    /// Parse a worksheet in from result report (0A).
+    ///
+    /// Invalid rows are skipped and reported if `lenient` is set in the
+    /// `options`, otherwise the first invalid row is returned as an error.
+    fn parse_result_worksheet(
+        name: &str,
+        range: Range<Data>,
+        archive: &SpreadsheetArchive,
+        options: &ParseOptions,
+    ) -> Result<(Vec<StudentResult>, Vec<RowDiagnostic>), ParseResultError> {
+        // Extract raw worksheet data and styles
+        let styles = archive.styles().map_err(ParseResultError::StyleError)?;
+        let sheet = archive
+            .worksheet(name)
+            .map_err(ParseResultError::StyleError)?;
 
         // Getting Headers and Subheaders
-        let headers = range
-            .headers()
+        let sheet_headers = SheetHeaders::from_range(name.to_owned(), &range)
             .ok_or("Invalid workbook given, the first row of data must be the headers")
             .map_err(|_| ParseResultError::NoHeaders)?;
-        let sub_headers = range
-            .range((1, 0), range.end().unwrap())
-            .headers()
-            .ok_or("Invalid workbook given, the second row of data must be the sub-headers")
-            .map_err(|_| ParseResultError::NoSubheaders)?;
-        let headers = ResultHeaders::get_headers(&headers, &sub_headers)?;
-
-        let data: Vec<StudentResult> = range
-            .rows()
-            .enumerate()
-            .skip(2)
-            .map(|(row_no, row)| {
-                StudentResult::from_result_row(
-                    &headers,
-                    row,
-                    row_no + 1,
-                    styles,
-                    &sheet.sheet_data.row[row_no],
-                )
-                .map_err(|e| ParseResultError::InvalidRow(row_no + 1, e))
-            })
-            .collect::<Result<_, ParseResultError>>()?;
+        let layout = &options.layouts.result;
+        let sub_headers = sheet_headers.sub_headers.as_deref();
+        if layout.header_rows() > 1 && sub_headers.is_none() {
+            return Err(ParseResultError::NoSubheaders);
+        }
+        let headers = layout
+            .headers(&sheet_headers.headers, sub_headers)
+            .map_err(ParseResultError::InvalidHeader)?;
+
+        let mut data = vec![];
+        let mut diagnostics = vec![];
+        for (row_no, row) in range.rows().enumerate().skip(layout.header_rows()) {
+            let row_styles = RowStyles {
+                row_no: row_no + 1,
+                styles,
+                row_data: sheet.row(row_no + 1),
+                values: row,
+                conditional_formatting: &sheet.conditional_formatting,
+                palette: &options.palette,
+            };
+
+            match StudentResult::from_result_row_lenient(&headers, row, &row_styles) {
+                Ok(result) => data.push(result),
+                Err(mut errors) if !options.lenient => {
+                    let (col, err) = errors.swap_remove(0);
+                    let context = sheet_headers.cell_context(
+                        row_no + 1,
+                        col,
+                        row.get(col),
+                        headers[col].expected(),
+                    );
+                    return Err(ParseResultError::InvalidRow(Box::new(context), err));
+                }
+                Err(errors) => diagnostics.extend(errors.into_iter().map(|(col, err)| {
+                    sheet_headers.diagnostic(
+                        row_no + 1,
+                        col,
+                        row.get(col),
+                        headers[col].expected(),
+                        err,
+                    )
+                })),
+            }
+        }
 
-        Ok(data)
+        Ok((data, diagnostics))
     }
 
     /// Extract all the student from a result report (0A) workbook.
     pub fn from_result<P: AsRef<Path>>(file: P) -> Result<Vec<StudentResult>, ParseResultError> {
-        let mut excel: Xlsx<_> = open_workbook(&file).map_err(ParseResultError::WorkbookError)?;
+        let file =
+            File::open(file).map_err(|e| ParseResultError::WorkbookError(XlsxError::Io(e)))?;
+        Self::from_result_reader(BufReader::new(file))
+    }
+
+    /// Extract all the student from a result report (0A) workbook in a reader.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    ///
+    /// use nott_a_database_core::StudentResult;
+    ///
+    /// let data = std::fs::read("./sample_0A.xlsx").expect("Unable to read file");
+    /// let results = StudentResult::from_result_reader(Cursor::new(data)).expect("Invalid workbook");
+    /// assert_eq!(results.len(), 45);
+    /// ```
+    pub fn from_result_reader<R: Read + Seek>(
+        reader: R,
+    ) -> Result<Vec<StudentResult>, ParseResultError> {
+        Self::parse_result(reader, &ParseOptions::default()).map(|(data, _)| data)
+    }
 
-        let styles: Styles =
-            get_data(&file, "xl/styles.xml").map_err(ParseResultError::StyleError)?;
-        let workbook: Workbook =
-            get_data(&file, "xl/workbook.xml").map_err(ParseResultError::StyleError)?;
-        let relationship: Relationships =
-            get_data(&file, "xl/_rels/workbook.xml.rels").map_err(ParseResultError::StyleError)?;
+    /// Extract all the student from a result report (0A) workbook, skipping
+    /// invalid rows.
+    ///
+    /// A [`RowDiagnostic`] is returned for every invalid cell in the skipped
+    /// rows instead of stopping at the first invalid row.
+    pub fn from_result_lenient<P: AsRef<Path>>(
+        file: P,
+    ) -> Result<(Vec<StudentResult>, Vec<RowDiagnostic>), ParseResultError> {
+        let file =
+            File::open(file).map_err(|e| ParseResultError::WorkbookError(XlsxError::Io(e)))?;
+        Self::from_result_reader_lenient(BufReader::new(file))
+    }
+
+    /// Extract all the student from a result report (0A) workbook in a
+    /// reader, skipping invalid rows.
+    ///
+    /// See [`StudentResult::from_result_lenient`] for more information.
+    pub fn from_result_reader_lenient<R: Read + Seek>(
+        reader: R,
+    ) -> Result<(Vec<StudentResult>, Vec<RowDiagnostic>), ParseResultError> {
+        let options = ParseOptions {
+            lenient: true,
+            ..Default::default()
+        };
+        Self::parse_result(reader, &options)
+    }
+
+    /// Extract all the student from a result report (0A) workbook with the
+    /// [`ParseOptions`], e.g. a custom [`StatusPalette`] or layout.
+    ///
+    /// Invalid rows are skipped and reported if `lenient` is set in the
+    /// `options`, otherwise the first invalid row is returned as an error.
+    pub fn from_result_with_options<P: AsRef<Path>>(
+        file: P,
+        options: &ParseOptions,
+    ) -> Result<(Vec<StudentResult>, Vec<RowDiagnostic>), ParseResultError> {
+        let file =
+            File::open(file).map_err(|e| ParseResultError::WorkbookError(XlsxError::Io(e)))?;
+        Self::from_result_reader_with_options(BufReader::new(file), options)
+    }
+
+    /// Extract all the student from a result report (0A) workbook in a
+    /// reader with the [`ParseOptions`].
+    ///
+    /// See [`StudentResult::from_result_with_options`] for more information.
+    pub fn from_result_reader_with_options<R: Read + Seek>(
+        reader: R,
+        options: &ParseOptions,
+    ) -> Result<(Vec<StudentResult>, Vec<RowDiagnostic>), ParseResultError> {
+        Self::parse_result(reader, options)
+    }
+
+    /// Extract all the student from a result report (0A) workbook in a reader.
+    fn parse_result<R: Read + Seek>(
+        reader: R,
+        options: &ParseOptions,
+    ) -> Result<(Vec<StudentResult>, Vec<RowDiagnostic>), ParseResultError> {
+        let archive =
+            SpreadsheetArchive::from_reader(reader).map_err(ParseResultError::StyleError)?;
+        let mut excel = archive.excel().map_err(ParseResultError::WorkbookError)?;
 
         let mut data = vec![];
+        let mut diagnostics = vec![];
         for (name, sheet) in excel.worksheets() {
-            let mut sheet_data = Self::from_result_worksheet(
-                &name,
-                sheet,
-                &file,
-                &workbook,
-                &relationship,
-                &styles,
-            )?;
+            let (mut sheet_data, mut sheet_diagnostics) =
+                Self::parse_result_worksheet(&name, sheet, &archive, options)?;
             data.append(&mut sheet_data);
+            diagnostics.append(&mut sheet_diagnostics);
         }
 
-        Ok(data)
+        Ok((data, diagnostics))
     }
 }
 
@@ -374,38 +503,34 @@ impl FromStr for Mark {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let data: Vec<&str> = s.split("\r\n").filter(|s| !s.is_empty()).collect();
 
-        if data.len() == 3 {
-            let code = data[0].to_owned() + data[1];
-            let credit = 10;
-            let mark = f64::from_str(data[2]).map_err(|_| ParseResultRowError::InvalidModule)?;
-            Ok(Mark {
-                code,
-                credit,
-                mark,
-                ..Default::default()
-            })
+        let (credit, mark) = if data.len() == 3 {
+            (10, data[2])
         } else if data.len() == 4 {
-            let code = data[0].to_owned() + data[1];
             let credit = i64::from_str(data[2]).map_err(|_| ParseResultRowError::InvalidModule)?;
-            let mark = f64::from_str(data[3]).map_err(|_| ParseResultRowError::InvalidModule)?;
-            Ok(Mark {
-                code,
-                credit,
-                mark,
-                ..Default::default()
-            })
+            (credit, data[3])
         } else {
             return Err(ParseResultRowError::InvalidModule);
-        }
+        };
+        let mark = MarkValue::from_str(mark).map_err(|_| ParseResultRowError::InvalidModule)?;
+
+        // The result report only has the first sit of the module
+        Ok(Mark {
+            code: data[0].to_owned() + data[1],
+            credit,
+            attempts: vec![Attempt {
+                mark,
+                ..Default::default()
+            }],
+            ..Default::default()
+        })
     }
 }
 
 impl TryFrom<ColourValue> for ModuleStatus {
     type Error = ParseResultRowError;
 
-    /// Get the [`ModuleStatus`] base on the fill colour of the cell.
-    ///
-    /// The [`ModuleStatus`] colour code is as folows:
+    /// Get the [`ModuleStatus`] base on the fill colour of the cell with the
+    /// default [`StatusPalette`].
     fn try_from(value: ColourValue) -> Result<Self, Self::Error> {
         Self::try_from(&value)
     }
@@ -414,35 +539,11 @@ impl TryFrom<ColourValue> for ModuleStatus {
 impl TryFrom<&ColourValue> for ModuleStatus {
     type Error = ParseResultRowError;
 
-    /// Get the [`ModuleStatus`] base on the fill colour of the cell.
-    ///
-    /// The [`ModuleStatus`] colour code is as folows:
-    ///
-    /// Orange (255, 255, 235, 156) => Component Fail (CF)
-    ///
-    /// Green (255, 198, 235, 156) or (255, 198, 239, 206) => Soft Fail
-    /// (SF)
-    ///
-    /// Red (255, 255, 199, 206) => Hard Fail (HF)
+    /// Get the [`ModuleStatus`] base on the fill colour of the cell with the
+    /// default [`StatusPalette`].
     fn try_from(value: &ColourValue) -> Result<Self, Self::Error> {
-        if value.alpha != 255 {
-            return Err(ParseResultRowError::InvalidModule);
-        }
-
-        if value.red == 255 && value.green == 235 && value.blue == 156 {
-            // Orange Cell => Component Fail (CF)
-            Ok(Self::ComponentFail)
-        } else if value.red == 198
-            && (value.green == 235 || value.green == 239)
-            && (value.blue == 156 || value.blue == 206)
-        {
-            // Green Cell => Soft Fail (SF)
-            Ok(Self::SoftFail)
-        } else if value.red == 255 && value.green == 199 && value.blue == 206 {
-            // Red Cell => Hard Fail (HF)
-            Ok(Self::HardFail)
-        } else {
-            Err(ParseResultRowError::InvalidModule)
-        }
+        StatusPalette::default()
+            .status(value)
+            .ok_or(ParseResultRowError::InvalidModule)
     }
 }
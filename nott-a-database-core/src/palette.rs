@@ -0,0 +1,250 @@
+//! Mapping of the fill colour of a module cell to the [`ModuleStatus`].
+use std::{fmt::Display, fs, path::Path, str::FromStr};
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{errors::ParsePaletteError, ColourValue, ModuleStatus};
+
+/// The default maximum distance between a fill colour and a colour of the
+/// palette.
+const DEFAULT_TOLERANCE: f64 = 20.0;
+
+/// The fill colours used for each [`ModuleStatus`] in the raw data.
+///
+/// The status of a fill colour is the status of the nearest colour in the
+/// palette, as long as the distance between the colours (in the RGB colour
+/// space) is within the `tolerance`. The alpha channel is ignored.
+///
+/// The default palette is as folows:
+///
+/// Orange (255, 235, 156) => Component Fail (CF)
+///
+/// Green (198, 235, 156) or (198, 239, 206) => Soft Fail (SF)
+///
+/// Red (255, 199, 206) => Hard Fail (HF)
+///
+/// The resit reports use different colours, see [`StatusPalette::resit`].
+/// Colours which do not show a status, e.g. the highlight of the modules
+/// resat in a session, are listed in `highlight`. Fill colours which are
+/// neither a status nor a highlight make the module invalid.
+///
+/// The palette can be loaded from a TOML file where the colours are
+/// hexadecimal RGB or ARGB values, e.g.
+///
+/// ```toml
+/// tolerance = 20.0
+/// pass = ["FFFFFF"]
+/// soft-fail = ["C6EB9C", "C6EFCE"]
+/// hard-fail = ["FFC7CE"]
+/// component-fail = ["FFEB9C"]
+/// highlight = []
+/// ```
+///
+/// # Examples
+///
+/// ```rust
+/// use nott_a_database_core::{palette::StatusPalette, ColourValue, ModuleStatus};
+///
+/// let palette = StatusPalette::from_toml("hard-fail = [\"FF0000\"]").expect("Invalid palette");
+///
+/// // Slightly different shade of red
+/// let colour = ColourValue { alpha: 255, red: 250, green: 10, blue: 0 };
+/// assert_eq!(palette.status(&colour), Some(ModuleStatus::HardFail));
+///
+/// let colour = ColourValue { alpha: 255, red: 0, green: 0, blue: 255 };
+/// assert_eq!(palette.status(&colour), None);
+/// assert!(!palette.is_highlight(&colour));
+///
+/// // The August resit report (0D) highlights the resat modules in orange
+/// let colour = ColourValue { alpha: 255, red: 255, green: 235, blue: 156 };
+/// assert_eq!(StatusPalette::resit().status(&colour), None);
+/// assert!(StatusPalette::resit().is_highlight(&colour));
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct StatusPalette {
+    /// The maximum distance between a fill colour and a colour of the
+    /// palette.
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+    /// The fill colours of passed modules.
+    #[serde(default, with = "hex_colours")]
+    pub pass: Vec<ColourValue>,
+    /// The fill colours of soft-failed modules.
+    #[serde(default, with = "hex_colours")]
+    pub soft_fail: Vec<ColourValue>,
+    /// The fill colours of hard-failed modules.
+    #[serde(default, with = "hex_colours")]
+    pub hard_fail: Vec<ColourValue>,
+    /// The fill colours of component-failed modules.
+    #[serde(default, with = "hex_colours")]
+    pub component_fail: Vec<ColourValue>,
+    /// The fill colours which do not show a status, e.g. highlights.
+    #[serde(default, with = "hex_colours")]
+    pub highlight: Vec<ColourValue>,
+}
+
+fn default_tolerance() -> f64 {
+    DEFAULT_TOLERANCE
+}
+
+/// Gets an opaque colour from its RGB values.
+fn colour(red: u8, green: u8, blue: u8) -> ColourValue {
+    ColourValue {
+        alpha: 255,
+        red,
+        green,
+        blue,
+    }
+}
+
+impl Default for StatusPalette {
+    fn default() -> Self {
+        Self {
+            tolerance: DEFAULT_TOLERANCE,
+            pass: vec![],
+            soft_fail: vec![colour(198, 235, 156), colour(198, 239, 206)],
+            hard_fail: vec![colour(255, 199, 206)],
+            component_fail: vec![colour(255, 235, 156)],
+            highlight: vec![],
+        }
+    }
+}
+
+impl StatusPalette {
+    /// Parses a [`StatusPalette`] from a TOML string.
+    ///
+    /// Missing statuses have no colours and the default tolerance is used if
+    /// it is not given.
+    pub fn from_toml(s: &str) -> Result<Self, ParsePaletteError> {
+        toml::from_str(s).map_err(ParsePaletteError::InvalidToml)
+    }
+
+    /// Reads a [`StatusPalette`] from a TOML file.
+    pub fn open<P: AsRef<Path>>(file: P) -> Result<Self, ParsePaletteError> {
+        let content = fs::read_to_string(file).map_err(ParsePaletteError::Io)?;
+        Self::from_toml(&content)
+    }
+
+    /// Converts the [`StatusPalette`] into a TOML string.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("The palette should always be serializable")
+    }
+
+    /// The default palette of the resit reports (0C and 0D).
+    ///
+    /// The resit reports use the same soft-fail and hard-fail colours, but
+    /// the August resit report (0D) highlights the resat modules in orange
+    /// (255, 235, 156) instead of marking component-fails, and notes are
+    /// highlighted in light yellow (255, 255, 204).
+    pub fn resit() -> Self {
+        Self {
+            component_fail: vec![],
+            highlight: vec![colour(255, 235, 156), colour(255, 255, 204)],
+            ..Self::default()
+        }
+    }
+
+    /// Gets the nearest colour of the palette within the tolerance, with its
+    /// [`ModuleStatus`] or [`None`] for a highlight.
+    fn nearest(&self, colour: &ColourValue) -> Option<Option<ModuleStatus>> {
+        let entries = [
+            (Some(ModuleStatus::Pass), &self.pass),
+            (Some(ModuleStatus::SoftFail), &self.soft_fail),
+            (Some(ModuleStatus::HardFail), &self.hard_fail),
+            (Some(ModuleStatus::ComponentFail), &self.component_fail),
+            (None, &self.highlight),
+        ];
+
+        entries
+            .into_iter()
+            .flat_map(|(status, colours)| colours.iter().map(move |c| (status.clone(), c)))
+            .map(|(status, c)| (status, c.distance(colour)))
+            .filter(|(_, distance)| *distance <= self.tolerance)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(status, _)| status)
+    }
+
+    /// Gets the [`ModuleStatus`] of a fill colour.
+    ///
+    /// Returns [`None`] if there is no colour in the palette within the
+    /// tolerance, or the nearest colour is a highlight.
+    pub fn status(&self, colour: &ColourValue) -> Option<ModuleStatus> {
+        self.nearest(colour).flatten()
+    }
+
+    /// Checks if a fill colour is a highlight, which does not change the
+    /// status of a module.
+    pub fn is_highlight(&self, colour: &ColourValue) -> bool {
+        matches!(self.nearest(colour), Some(None))
+    }
+}
+
+impl ColourValue {
+    /// Gets the Euclidean distance between two colours in the RGB colour
+    /// space, the alpha channel is ignored.
+    pub fn distance(&self, other: &ColourValue) -> f64 {
+        let red = f64::from(self.red) - f64::from(other.red);
+        let green = f64::from(self.green) - f64::from(other.green);
+        let blue = f64::from(self.blue) - f64::from(other.blue);
+        (red * red + green * green + blue * blue).sqrt()
+    }
+}
+
+impl FromStr for ColourValue {
+    type Err = String;
+
+    /// Parses a hexadecimal RGB (`RRGGBB`) or ARGB (`AARRGGBB`) value, the
+    /// leading `#` is optional.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().trim_start_matches('#');
+        let value = u32::from_str_radix(s, 16).map_err(|_| format!("Invalid colour {s}"))?;
+
+        match s.len() {
+            6 => Ok(Self {
+                alpha: 255,
+                red: (value >> 16) as u8,
+                green: (value >> 8) as u8,
+                blue: value as u8,
+            }),
+            8 => Ok(Self {
+                alpha: (value >> 24) as u8,
+                red: (value >> 16) as u8,
+                green: (value >> 8) as u8,
+                blue: value as u8,
+            }),
+            _ => Err(format!("Invalid colour {s}, expected 6 or 8 hex digits")),
+        }
+    }
+}
+
+impl Display for ColourValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:02X}{:02X}{:02X}{:02X}",
+            self.alpha, self.red, self.green, self.blue
+        )
+    }
+}
+
+/// (De)serializes a list of [`ColourValue`] as hexadecimal strings.
+mod hex_colours {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        colours: &[ColourValue],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(colours.iter().map(|c| c.to_string()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<ColourValue>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|s| s.parse().map_err(D::Error::custom))
+            .collect()
+    }
+}
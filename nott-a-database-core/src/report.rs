@@ -0,0 +1,365 @@
+//! Detection of the type of report in a raw data workbook.
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{BufReader, Read, Seek},
+    path::Path,
+};
+
+use calamine::{open_workbook_from_rs, Data, Range, Reader, Xlsx, XlsxError};
+
+use crate::{
+    award::AwardHeader,
+    errors::{CellContext, DetectReportError, RowDiagnostic},
+    layout::ReportLayouts,
+    spreadsheet_ml::XlsxColumns,
+    DetectionConfidence, ReportDetection, ReportKind, ResitSession,
+};
+
+/// The header and sub-header rows of a worksheet.
+#[derive(Debug)]
+pub(crate) struct SheetHeaders {
+    /// The name of the worksheet.
+    pub(crate) name: String,
+    /// The first row of the worksheet.
+    pub(crate) headers: Vec<String>,
+    /// The second row of the worksheet.
+    pub(crate) sub_headers: Option<Vec<String>>,
+}
+
+impl SheetHeaders {
+    /// Extract the headers and sub-headers from a worksheet.
+    ///
+    /// Returns [`None`] if the worksheet is empty.
+    pub(crate) fn from_range(name: String, range: &Range<Data>) -> Option<Self> {
+        let headers = range.headers()?;
+        let sub_headers = range
+            .end()
+            .filter(|end| end.0 >= 1)
+            .and_then(|end| range.range((1, 0), end).headers());
+
+        Some(Self {
+            name,
+            headers,
+            sub_headers,
+        })
+    }
+
+    /// Checks if the headers has a column with the name `header`.
+    fn has_header(&self, header: &str) -> bool {
+        self.headers.iter().any(|h| h.trim() == header)
+    }
+
+    /// Gets the display name of the column `col`.
+    ///
+    /// Empty headers are treated as merged with the closest non-empty header
+    /// on the left and the sub-header is appended if there is one.
+    pub(crate) fn column_name(&self, col: usize) -> String {
+        let header = self
+            .headers
+            .iter()
+            .take(col + 1)
+            .rev()
+            .map(|h| h.replace("\r\n", " ").trim().to_owned())
+            .find(|h| !h.is_empty())
+            .unwrap_or_default();
+        let sub_header = self
+            .sub_headers
+            .as_ref()
+            .and_then(|s| s.get(col))
+            .map(|s| s.replace("\r\n", " ").trim().to_owned())
+            .unwrap_or_default();
+
+        match (header.is_empty(), sub_header.is_empty()) {
+            (_, true) => header,
+            (true, false) => sub_header,
+            (false, false) => format!("{header} {sub_header}"),
+        }
+    }
+
+    /// Creates the [`CellContext`] of the cell at the 1-based `row` and
+    /// 0-based `col` of the worksheet.
+    pub(crate) fn cell_context(
+        &self,
+        row: usize,
+        col: usize,
+        value: Option<&Data>,
+        expected: &'static str,
+    ) -> CellContext {
+        let column = XlsxColumns::new()
+            .nth(col)
+            .expect("There should be an infinite amount of XLSX columns.");
+
+        CellContext {
+            sheet: self.name.clone(),
+            cell: format!("{column}{row}"),
+            row,
+            header: self.column_name(col),
+            expected,
+            value: value.cloned().unwrap_or_default(),
+        }
+    }
+
+    /// Creates a [`RowDiagnostic`] for an invalid cell in the worksheet.
+    pub(crate) fn diagnostic<E: Display>(
+        &self,
+        row: usize,
+        col: usize,
+        value: Option<&Data>,
+        expected: &'static str,
+        error: E,
+    ) -> RowDiagnostic {
+        RowDiagnostic {
+            context: self.cell_context(row, col, value, expected),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// The evidence found in a workbook for a [`ReportKind`].
+#[derive(Debug)]
+struct Evidence {
+    /// The type of report.
+    kind: ReportKind,
+    /// All the headers are accepted by the parser of the report.
+    headers: bool,
+    /// The worksheets layout matches the report.
+    layout: bool,
+    /// Explanation for each of the evidence found.
+    notes: Vec<String>,
+}
+
+impl Evidence {
+    /// The confidence of the evidence found.
+    fn confidence(&self) -> Option<DetectionConfidence> {
+        match (self.headers, self.layout) {
+            (true, true) => Some(DetectionConfidence::High),
+            (true, false) => Some(DetectionConfidence::Medium),
+            (false, true) => Some(DetectionConfidence::Low),
+            (false, false) => None,
+        }
+    }
+}
+
+impl ReportKind {
+    /// Detects the type of report from the worksheet names and the header and
+    /// sub-header rows of a workbook.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use nott_a_database_core::{DetectionConfidence, ReportKind};
+    ///
+    /// let detection = ReportKind::detect("./sample_0A.xlsx").expect("Unable to open workbook");
+    /// assert_eq!(detection.kind, ReportKind::Result);
+    /// assert_eq!(detection.confidence, DetectionConfidence::High);
+    /// ```
+    pub fn detect<P: AsRef<Path>>(file: P) -> Result<ReportDetection, DetectReportError> {
+        let file =
+            File::open(file).map_err(|e| DetectReportError::WorkbookError(XlsxError::Io(e)))?;
+        Self::detect_reader(BufReader::new(file))
+    }
+
+    /// Detects the type of report of a workbook in a reader.
+    ///
+    /// See [`ReportKind::detect`] for more information.
+    pub fn detect_reader<R: Read + Seek>(reader: R) -> Result<ReportDetection, DetectReportError> {
+        Self::detect_reader_with_layouts(reader, &ReportLayouts::default())
+    }
+
+    /// Detects the type of report of a workbook with custom
+    /// [`ReportLayouts`].
+    ///
+    /// See [`ReportKind::detect`] for more information.
+    pub fn detect_with_layouts<P: AsRef<Path>>(
+        file: P,
+        layouts: &ReportLayouts,
+    ) -> Result<ReportDetection, DetectReportError> {
+        let file =
+            File::open(file).map_err(|e| DetectReportError::WorkbookError(XlsxError::Io(e)))?;
+        Self::detect_reader_with_layouts(BufReader::new(file), layouts)
+    }
+
+    /// Detects the type of report of a workbook in a reader with custom
+    /// [`ReportLayouts`].
+    ///
+    /// See [`ReportKind::detect`] for more information.
+    pub fn detect_reader_with_layouts<R: Read + Seek>(
+        reader: R,
+        layouts: &ReportLayouts,
+    ) -> Result<ReportDetection, DetectReportError> {
+        let mut excel: Xlsx<_> =
+            open_workbook_from_rs(reader).map_err(DetectReportError::WorkbookError)?;
+
+        let sheets: Vec<SheetHeaders> = excel
+            .worksheets()
+            .into_iter()
+            .filter_map(|(name, range)| SheetHeaders::from_range(name, &range))
+            .collect();
+
+        Self::detect_sheets(&sheets, layouts)
+    }
+
+    /// Detects the type of report from the headers of all the worksheets.
+    fn detect_sheets(
+        sheets: &[SheetHeaders],
+        layouts: &ReportLayouts,
+    ) -> Result<ReportDetection, DetectReportError> {
+        let first = sheets.first().ok_or(DetectReportError::UnknownReport)?;
+        let last = sheets.last().ok_or(DetectReportError::UnknownReport)?;
+
+        let evidences = [
+            Self::result_evidence(sheets, layouts),
+            Self::award_evidence(sheets, first, layouts),
+            Self::resit_may_evidence(sheets, first, layouts),
+            Self::resit_aug_evidence(last, layouts),
+        ];
+
+        let (evidence, confidence) = evidences
+            .into_iter()
+            .filter_map(|e| e.confidence().map(|c| (e, c)))
+            .max_by_key(|(_, c)| *c)
+            .ok_or(DetectReportError::UnknownReport)?;
+
+        Ok(ReportDetection {
+            kind: evidence.kind,
+            confidence,
+            explanation: evidence.notes.join(" "),
+        })
+    }
+
+    /// Gets the evidence of a result report (0A) from all the worksheets.
+    fn result_evidence(sheets: &[SheetHeaders], layouts: &ReportLayouts) -> Evidence {
+        let headers = sheets.iter().all(|sheet| match &sheet.sub_headers {
+            Some(sub_headers) => layouts
+                .result
+                .headers(&sheet.headers, Some(sub_headers))
+                .is_ok(),
+            None => false,
+        });
+        let layout = sheets.iter().any(|sheet| sheet.has_header("Modules"));
+
+        let mut notes = vec![];
+        if headers {
+            notes.push(String::from(
+                "All worksheets have result report (0A) headers.",
+            ));
+        }
+        if layout {
+            notes.push(String::from("Found a \"Modules\" column."));
+        }
+
+        Evidence {
+            kind: Self::Result,
+            headers,
+            layout,
+            notes,
+        }
+    }
+
+    /// Gets the evidence of an award report (0B) from all the worksheets.
+    fn award_evidence(
+        sheets: &[SheetHeaders],
+        first: &SheetHeaders,
+        layouts: &ReportLayouts,
+    ) -> Evidence {
+        let award = sheets.iter().find(|sheet| sheet.name == "Award Report");
+        let layout = award.is_some();
+
+        let sheet = award.unwrap_or(first);
+        let headers = layouts
+            .award
+            .headers(&sheet.headers, sheet.sub_headers.as_deref())
+            .is_ok_and(|h| h.contains(&AwardHeader::Id));
+
+        let mut notes = vec![];
+        if headers {
+            notes.push(format!(
+                "Worksheet \"{}\" has award report (0B) headers.",
+                sheet.name
+            ));
+        }
+        if layout {
+            notes.push(String::from("Found worksheet \"Award Report\"."));
+        }
+
+        Evidence {
+            kind: Self::Award,
+            headers,
+            layout,
+            notes,
+        }
+    }
+
+    /// Gets the evidence of a May resit report (0C) from all the worksheets.
+    fn resit_may_evidence(
+        sheets: &[SheetHeaders],
+        first: &SheetHeaders,
+        layouts: &ReportLayouts,
+    ) -> Evidence {
+        let sheet1 = sheets.iter().find(|sheet| sheet.name == "Sheet1");
+        let sheet = sheet1.unwrap_or(first);
+
+        let headers = match &sheet.sub_headers {
+            Some(sub_headers) => layouts
+                .resit(ResitSession::May)
+                .headers(&sheet.headers, Some(sub_headers))
+                .is_ok(),
+            None => false,
+        };
+        let layout = sheet1.is_some() && sheet.has_header("Course") && sheet.has_header("Summer");
+
+        let mut notes = vec![];
+        if headers {
+            notes.push(format!(
+                "Worksheet \"{}\" has May resit report (0C) headers.",
+                sheet.name
+            ));
+        }
+        if layout {
+            notes.push(String::from(
+                "Found worksheet \"Sheet1\" with \"Course\" and \"Summer\" columns.",
+            ));
+        }
+
+        Evidence {
+            kind: Self::Resit(ResitSession::May),
+            headers,
+            layout,
+            notes,
+        }
+    }
+
+    /// Gets the evidence of an August resit report (0D) from the last worksheet.
+    fn resit_aug_evidence(last: &SheetHeaders, layouts: &ReportLayouts) -> Evidence {
+        let headers = match &last.sub_headers {
+            Some(sub_headers) => layouts
+                .resit(ResitSession::August)
+                .headers(&last.headers, Some(sub_headers))
+                .is_ok(),
+            None => false,
+        };
+        let layout = last.has_header("Course") && !last.has_header("Summer");
+
+        let mut notes = vec![];
+        if headers {
+            notes.push(format!(
+                "Worksheet \"{}\" has August resit report (0D) headers.",
+                last.name
+            ));
+        }
+        if layout {
+            notes.push(format!(
+                "Found worksheet \"{}\" with a \"Course\" column but no \"Summer\" column.",
+                last.name
+            ));
+        }
+
+        Evidence {
+            kind: Self::Resit(ResitSession::August),
+            headers,
+            layout,
+            notes,
+        }
+    }
+}
@@ -0,0 +1,778 @@
+//! Parser for resit report (0C, 0D) raw data.
+//!
+//! The resit reports of every session share the same layout apart from a few
+//! columns, so they are parsed by the same parser with the [`ResitSession`]
+//! selecting the [`ReportLayout`](crate::layout::ReportLayout) and the error
+//! codes.
+
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek},
+    path::Path,
+};
+
+use calamine::{Data, DataType, Reader, SheetType, SheetVisible, XlsxError};
+
+use serde::Deserialize;
+
+use crate::{
+    errors::{ParseResitError, ParseResitErrorKind, ParseResitRowError, RowDiagnostic},
+    report::SheetHeaders,
+    spreadsheet_ml::{record_values, SpreadsheetArchive, XlsxColumns},
+    Attempt, AttemptSession, ColourValue, Mark, MarkValue, ModuleStatus, ParseOptions,
+    ResitSession, ResultAttempt, StudentResult,
+};
+
+/// Headers for resit report (0C, 0D) raw data.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ResitHeader {
+    /// The entry number in the sheet.
+    No,
+    /// The student ID of the student.
+    Id,
+    /// The last name of the student.
+    LastName,
+    /// The first name of the student.
+    FirstName,
+    /// The course plan the student is studying.
+    Plan,
+    /// The year of studies of the student.
+    YearOfProgram,
+    /// The amount of credits taken by the student in the Autumn Semester.
+    AutumnCredit,
+    /// The average/mean marks of the student in the Autumn Semester.
+    AutumnMean,
+    /// The amount of credits taken by the student in the Summer Semester.
+    SummerCredit,
+    /// The average/mean marks of the student in the Summer Semester.
+    SummerMean,
+    /// The amount of credits taken by the student in the entire year.
+    FullCredit,
+    /// The average/mean marks of the student in the entire year.
+    FullMean,
+    /// The amount of credits taken by the student in the Spring Semester.
+    SpringCredit,
+    /// The average/mean marks of the student in the Spring Semester.
+    SpringMean,
+    /// A column which is not used, e.g. the columns without a header in the
+    /// August resit report (0D).
+    Ignored,
+    /// The amount of credits taken by the student in the entire year.
+    YearCredit,
+    /// The average/mean marks of the student in the entire year.
+    YearProgAverage,
+    /// Credits (L3) <30
+    CreditsL3Lt30,
+    /// Credits (L3) 30-39
+    #[serde(rename = "credits_l3_30_39")]
+    CreditsL33039,
+    /// The progression status of the student, e.g. requires retake.
+    Progression,
+    /// All the marks of the modules taken by the student.
+    Course,
+    /// Remarks regardding the students result.
+    Remarks,
+}
+
+impl ResitHeader {
+    /// Gets the type of value expected in the column.
+    pub fn expected(&self) -> &'static str {
+        match self {
+            Self::Ignored => "anything",
+            Self::No | Self::Id => "an integer",
+            Self::LastName
+            | Self::FirstName
+            | Self::Plan
+            | Self::YearOfProgram
+            | Self::Progression => "text",
+            Self::AutumnCredit
+            | Self::AutumnMean
+            | Self::SummerCredit
+            | Self::SummerMean
+            | Self::FullCredit
+            | Self::FullMean
+            | Self::SpringCredit => "a number or an empty cell",
+            Self::SpringMean => "a number, resit labels or an empty cell",
+            Self::YearCredit
+            | Self::YearProgAverage
+            | Self::CreditsL3Lt30
+            | Self::CreditsL33039 => "numbers on separate lines or an empty cell",
+            Self::Course => "the module code, credits and marks (or codes) on separate lines",
+            Self::Remarks => "text or an empty cell",
+        }
+    }
+}
+
+/// Parses a number from a cell, an empty cell is parsed as [`None`].
+fn optional_number(
+    value: &Data,
+    error: ParseResitRowError,
+) -> Result<Option<f64>, ParseResitRowError> {
+    if value.is_empty() {
+        Ok(None)
+    } else {
+        value.as_f64().map(Some).ok_or(error)
+    }
+}
+
+/// Parses the number of every attempt from the numbers in every row of a
+/// record, each row can have numbers on separate lines.
+///
+/// The numbers are in the order of the attempts, the first number is from
+/// the first attempt before any resits.
+fn attempt_numbers(
+    values: &[Data],
+    error: ParseResitRowError,
+) -> Result<Vec<f64>, ParseResitRowError> {
+    let mut numbers = vec![];
+    for value in values {
+        for line in value
+            .as_string()
+            .ok_or(error)?
+            .split("\r\n")
+            .filter(|s| !s.is_empty())
+        {
+            numbers.push(line.trim().parse().map_err(|_| error)?);
+        }
+    }
+
+    Ok(numbers)
+}
+
+/// Parses the spring mean from a cell.
+///
+/// The column is shared with the labels of the resit rows of the record, e.g.
+/// `1st Resit`, so the mean is only taken from the first line if it is a
+/// number.
+fn spring_mean(value: &Data) -> Result<Option<f64>, ParseResitRowError> {
+    let Data::String(value) = value else {
+        return optional_number(value, ParseResitRowError::InvalidSpringMean);
+    };
+
+    match value.lines().map(str::trim).find(|s| !s.is_empty()) {
+        None => Ok(None),
+        Some(line) if line.to_lowercase().contains("resit") => Ok(None),
+        Some(line) => line
+            .parse()
+            .map(Some)
+            .map_err(|_| ParseResitRowError::InvalidSpringMean),
+    }
+}
+
+/// Parses the mark or the code of an attempt, [`None`] for an empty value.
+///
+/// Returns [`ParseResitRowError::InvalidCourse`] if the value is neither a
+/// number nor a code (see [`MarkValue`]), e.g. a note like
+/// `Opt for ext resit`.
+fn parse_attempt(value: &str) -> Result<Option<MarkValue>, ParseResitRowError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    value
+        .parse()
+        .map(Some)
+        .map_err(|_| ParseResitRowError::InvalidCourse)
+}
+
+/// Parses the first sit and the resits of a module in the `session` from
+/// the marks of each attempt, empty resits are ignored.
+///
+/// Invalid resits, e.g. notes like `Opt for ext resit` after a mark, are
+/// skipped without failing the module. Returns whether an invalid resit was
+/// skipped.
+fn parse_attempts<I>(
+    mark: &mut Mark,
+    attempts: I,
+    session: AttemptSession,
+) -> Result<bool, ParseResitRowError>
+where
+    I: IntoIterator<Item = Result<Option<MarkValue>, ParseResitRowError>>,
+{
+    let mut attempts = attempts.into_iter();
+
+    let first = attempts
+        .next()
+        .unwrap_or(Ok(None))?
+        .ok_or(ParseResitRowError::InvalidCourse)?;
+    mark.attempts.push(Attempt {
+        mark: first,
+        ..Default::default()
+    });
+    let mut skipped = false;
+    for resit in attempts {
+        let resit = match resit {
+            Ok(Some(resit)) => resit,
+            Ok(None) => continue,
+            Err(_) => {
+                skipped = true;
+                continue;
+            }
+        };
+        // Resits are capped at the pass mark
+        mark.attempts.push(Attempt {
+            session,
+            mark: resit,
+            capped: true,
+            status: None,
+        });
+    }
+
+    Ok(skipped)
+}
+
+/// Parses a [`Mark`] from the rows of a course cell of a record in resit
+/// report raw data.
+///
+/// A record spanning multiple rows has the module code and credits in the
+/// first row followed by a row for each attempt, otherwise everything is on
+/// separate lines of a single cell.
+///
+/// Returns whether an invalid resit was skipped, see [`parse_attempts`].
+fn parse_course(
+    values: &[Data],
+    session: AttemptSession,
+) -> Result<(Mark, bool), ParseResitRowError> {
+    let mut mark = Mark::default();
+    let (info, attempts) = values
+        .split_first()
+        .ok_or(ParseResitRowError::InvalidCourse)?;
+    let info = info.as_string().ok_or(ParseResitRowError::InvalidCourse)?;
+
+    if !attempts.is_empty() {
+        // Multi-row data
+        let mut module_info: Vec<&str> = info.split("\r\n").filter(|s| !s.is_empty()).collect();
+
+        // Extract module code and credits
+        if module_info.len() == 3 {
+            let credits = module_info.split_off(2)[0];
+            mark.code = module_info.join("").trim().to_owned();
+            mark.credit = credits
+                .trim()
+                .parse()
+                .map_err(|_| ParseResitRowError::InvalidCourse)?;
+        } else if module_info.len() == 2 {
+            mark.code = module_info.join("");
+            mark.credit = 10;
+        } else {
+            return Err(ParseResitRowError::InvalidCourse);
+        }
+
+        let skipped = parse_attempts(
+            &mut mark,
+            attempts.iter().map(|a| match a {
+                Data::Empty => Ok(None),
+                a => a
+                    .as_string()
+                    .ok_or(ParseResitRowError::InvalidCourse)
+                    .and_then(|a| parse_attempt(&a)),
+            }),
+            session,
+        )?;
+        Ok((mark, skipped))
+    } else {
+        // Single row data
+        let mut value: Vec<&str> = info.split("\r\n").collect();
+        if value.len() < 3 {
+            return Err(ParseResitRowError::InvalidCourse);
+        }
+        let mut rest = value.split_off(2);
+
+        // Extracting module code and credits
+        mark.code = value.join("").trim().to_owned();
+        mark.credit = if rest.len() == 1 {
+            10
+        } else {
+            let tmp = rest.split_off(2);
+            let credits = rest[1].trim();
+            rest = tmp;
+            if credits.is_empty() {
+                10
+            } else {
+                credits
+                    .parse()
+                    .map_err(|_| ParseResitRowError::InvalidCourse)?
+            }
+        };
+
+        rest.retain(|s| !s.is_empty());
+        let skipped = parse_attempts(&mut mark, rest.into_iter().map(parse_attempt), session)?;
+        Ok((mark, skipped))
+    }
+}
+
+/// The errors of the invalid cells of a record with their column.
+type CellErrors = Vec<(usize, ParseResitRowError)>;
+
+/// Gets the value of a cell of a record to show in errors, the rows of a
+/// record spanning multiple rows are shown on separate lines.
+fn record_value(values: &[Data]) -> Data {
+    match values {
+        [] => Data::Empty,
+        [value] => value.clone(),
+        values => Data::String(
+            values
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\r\n"),
+        ),
+    }
+}
+
+impl StudentResult {
+    /// Parse [`StudentResult`] from a row of resit report raw data,
+    /// collecting the errors of every invalid cell with its column.
+    ///
+    /// Invalid resits are skipped without failing the row, the errors of the
+    /// cells with skipped resits are returned with the row.
+    fn from_resit_row(
+        headers: &[ResitHeader],
+        data: &[Vec<Data>],
+        fills: &[Option<ColourValue>],
+        session: ResitSession,
+        options: &ParseOptions,
+    ) -> Result<(StudentResult, CellErrors), CellErrors> {
+        let mut output = StudentResult {
+            session: Some(session),
+            ..Self::new()
+        };
+
+        let mut errors = vec![];
+        let mut skipped = vec![];
+        for (col, (header, value)) in headers.iter().zip(data).enumerate() {
+            match output.set_resit_cell(
+                header,
+                value,
+                fills.get(col).and_then(Option::as_ref),
+                options,
+            ) {
+                Ok(true) => skipped.push((col, ParseResitRowError::InvalidCourse)),
+                Ok(false) => {}
+                Err(e) => errors.push((col, e)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok((output, skipped))
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Sets the field of `header` from the rows of a cell of a record in
+    /// resit report raw data.
+    ///
+    /// Returns whether an invalid resit was skipped, see [`parse_attempts`].
+    fn set_resit_cell(
+        &mut self,
+        header: &ResitHeader,
+        values: &[Data],
+        fill: Option<&ColourValue>,
+        options: &ParseOptions,
+    ) -> Result<bool, ParseResitRowError> {
+        // Filtering out weird character "_x000D_"
+        let values: Vec<Data> = values
+            .iter()
+            .map(|value| match value {
+                Data::String(s) => Data::String(
+                    s.split("\r\n")
+                        .filter(|s| *s != "_x000D_")
+                        .collect::<Vec<&str>>()
+                        .join("\r\n"),
+                ),
+                value => value.clone(),
+            })
+            .collect();
+        // Fields with a single value are in the first row of the record
+        let value = values.first().unwrap_or(&Data::Empty);
+
+        match header {
+            ResitHeader::No => self.no = Some(value.as_i64().ok_or(ParseResitRowError::InvalidID)?),
+            ResitHeader::Id => {
+                self.student_info.id = value.as_i64().ok_or(ParseResitRowError::InvalidID)?
+            }
+            ResitHeader::LastName => {
+                self.student_info.last_name = value
+                    .as_string()
+                    .ok_or(ParseResitRowError::InvalidLastName)?
+            }
+            ResitHeader::FirstName => {
+                self.student_info.first_name = value
+                    .as_string()
+                    .ok_or(ParseResitRowError::InvalidFirstName)?
+            }
+            ResitHeader::Plan => {
+                self.student_info.plan = value.as_string().ok_or(ParseResitRowError::InvalidPlan)?
+            }
+            ResitHeader::YearOfProgram => {
+                self.year_of_program = value
+                    .as_string()
+                    .ok_or(ParseResitRowError::InvalidYearOfProgram)?
+            }
+            ResitHeader::AutumnCredit => {
+                self.autumn_credit =
+                    optional_number(value, ParseResitRowError::InvalidAutumnCredit)?
+            }
+            ResitHeader::AutumnMean => {
+                self.autumn_mean = optional_number(value, ParseResitRowError::InvalidAutumnMean)?
+            }
+            ResitHeader::SummerCredit => {
+                self.summer_credit =
+                    optional_number(value, ParseResitRowError::InvalidSummerCredit)?
+            }
+            ResitHeader::SummerMean => {
+                self.summer_mean = optional_number(value, ParseResitRowError::InvalidSummerMean)?
+            }
+            ResitHeader::FullCredit => {
+                self.full_credit = optional_number(value, ParseResitRowError::InvalidFullCredit)?
+            }
+            ResitHeader::FullMean => {
+                self.full_mean = optional_number(value, ParseResitRowError::InvalidFullMean)?
+            }
+            ResitHeader::SpringCredit => {
+                self.spring_credit =
+                    optional_number(value, ParseResitRowError::InvalidSpringCredit)?
+            }
+            ResitHeader::SpringMean => self.spring_mean = spring_mean(value)?,
+            ResitHeader::Ignored => {}
+            ResitHeader::YearCredit => {
+                self.year_credit =
+                    self.set_attempts(&values, ParseResitRowError::InvalidYearCredit, |a| {
+                        &mut a.year_credit
+                    })?
+            }
+            ResitHeader::YearProgAverage => {
+                self.year_prog_average =
+                    self.set_attempts(&values, ParseResitRowError::InvalidYearProgAverage, |a| {
+                        &mut a.year_prog_average
+                    })?
+            }
+            ResitHeader::CreditsL3Lt30 => {
+                self.credits_l3_lt30 =
+                    self.set_attempts(&values, ParseResitRowError::InvalidCreditsL3Lt30, |a| {
+                        &mut a.credits_l3_lt30
+                    })?
+            }
+            ResitHeader::CreditsL33039 => {
+                self.credits_l3_30_39 =
+                    self.set_attempts(&values, ParseResitRowError::InvalidCreditsL33039, |a| {
+                        &mut a.credits_l3_30_39
+                    })?
+            }
+            ResitHeader::Progression => {
+                self.progression = value
+                    .as_string()
+                    .ok_or(ParseResitRowError::InvalidProgression)?;
+            }
+            ResitHeader::Course => {
+                // Skipping Empty course
+                if values.is_empty() {
+                    return Ok(false);
+                }
+
+                let session = self
+                    .session
+                    .map_or(AttemptSession::FirstSit, |s| s.attempt_session());
+                let (mut mark, skipped) = parse_course(&values, session)?;
+
+                // Extracting module status from the fill colour, highlights
+                // are kept without changing the status
+                let mut status = ModuleStatus::default();
+                if let Some(colour) = fill {
+                    if !options.resit_palette.is_highlight(colour) {
+                        status = options
+                            .resit_palette
+                            .status(colour)
+                            .ok_or(ParseResitRowError::InvalidCourse)?;
+                    }
+                    mark.fill = Some(colour.clone());
+                }
+                mark.set_status(status);
+                self.modules.push(mark);
+                return Ok(skipped);
+            }
+            ResitHeader::Remarks => {
+                self.remarks = if value.is_empty() {
+                    None
+                } else {
+                    Some(
+                        value
+                            .as_string()
+                            .ok_or(ParseResitRowError::InvalidRemarks)?,
+                    )
+                }
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Sets the `field` of every attempt from the rows of a cell of a
+    /// record, returning the number of the newest attempt.
+    fn set_attempts(
+        &mut self,
+        values: &[Data],
+        error: ParseResitRowError,
+        field: fn(&mut ResultAttempt) -> &mut Option<f64>,
+    ) -> Result<Option<f64>, ParseResitRowError> {
+        let numbers = attempt_numbers(values, error)?;
+        for (attempt, number) in numbers.iter().enumerate() {
+            *field(self.attempt_mut(attempt)) = Some(*number);
+        }
+
+        Ok(numbers.last().copied())
+    }
+
+    /// Parse [`StudentResult`] from a resit report raw data of the
+    /// [`ResitSession`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use nott_a_database_core::{ResitSession, StudentResult};
+    ///
+    /// let may = StudentResult::from_resit("./sample_0C.xlsx", ResitSession::May)
+    ///     .expect("Invalid May resit report");
+    /// assert_eq!(may.len(), 9);
+    ///
+    /// let august = StudentResult::from_resit("./sample_0D.xlsx", ResitSession::August)
+    ///     .expect("Invalid August resit report");
+    /// assert_eq!(august.len(), 25);
+    ///
+    /// // Two resits of the August resit report are notes instead of marks,
+    /// // they are skipped and reported without failing their row
+    /// let (_, diagnostics) = StudentResult::from_resit_with_options(
+    ///     "./sample_0D.xlsx",
+    ///     ResitSession::August,
+    ///     &Default::default(),
+    /// )
+    /// .expect("Invalid August resit report");
+    /// assert_eq!(diagnostics.len(), 2);
+    /// ```
+    pub fn from_resit<P: AsRef<Path>>(
+        data: P,
+        session: ResitSession,
+    ) -> Result<Vec<StudentResult>, ParseResitError> {
+        let data = File::open(data).map_err(|e| {
+            ParseResitError::new(
+                session,
+                ParseResitErrorKind::WorkbookError(XlsxError::Io(e)),
+            )
+        })?;
+        Self::from_resit_reader(BufReader::new(data), session)
+    }
+
+    /// Parse [`StudentResult`] from a resit report raw data of the
+    /// [`ResitSession`] in a reader.
+    pub fn from_resit_reader<R: Read + Seek>(
+        data: R,
+        session: ResitSession,
+    ) -> Result<Vec<StudentResult>, ParseResitError> {
+        Self::parse_resit(data, session, &ParseOptions::default()).map(|(output, _)| output)
+    }
+
+    /// Parse [`StudentResult`] from a resit report raw data of the
+    /// [`ResitSession`], skipping invalid rows.
+    ///
+    /// A [`RowDiagnostic`] is returned for every invalid cell in the skipped
+    /// rows instead of stopping at the first invalid row.
+    pub fn from_resit_lenient<P: AsRef<Path>>(
+        data: P,
+        session: ResitSession,
+    ) -> Result<(Vec<StudentResult>, Vec<RowDiagnostic>), ParseResitError> {
+        let data = File::open(data).map_err(|e| {
+            ParseResitError::new(
+                session,
+                ParseResitErrorKind::WorkbookError(XlsxError::Io(e)),
+            )
+        })?;
+        Self::from_resit_reader_lenient(BufReader::new(data), session)
+    }
+
+    /// Parse [`StudentResult`] from a resit report raw data of the
+    /// [`ResitSession`] in a reader, skipping invalid rows.
+    ///
+    /// See [`StudentResult::from_resit_lenient`] for more information.
+    pub fn from_resit_reader_lenient<R: Read + Seek>(
+        data: R,
+        session: ResitSession,
+    ) -> Result<(Vec<StudentResult>, Vec<RowDiagnostic>), ParseResitError> {
+        let options = ParseOptions {
+            lenient: true,
+            ..Default::default()
+        };
+        Self::parse_resit(data, session, &options)
+    }
+
+    /// Parse [`StudentResult`] from a resit report raw data of the
+    /// [`ResitSession`] with the [`ParseOptions`], e.g. a custom
+    /// [`StatusPalette`](crate::palette::StatusPalette) or layout.
+    ///
+    /// Invalid rows are skipped and reported if `lenient` is set in the
+    /// `options`, otherwise the first invalid row is returned as an error.
+    /// Resits which are neither a number nor a code (see [`MarkValue`]) are
+    /// always skipped without skipping their row, and a [`RowDiagnostic`] is
+    /// returned for their cell.
+    pub fn from_resit_with_options<P: AsRef<Path>>(
+        data: P,
+        session: ResitSession,
+        options: &ParseOptions,
+    ) -> Result<(Vec<StudentResult>, Vec<RowDiagnostic>), ParseResitError> {
+        let data = File::open(data).map_err(|e| {
+            ParseResitError::new(
+                session,
+                ParseResitErrorKind::WorkbookError(XlsxError::Io(e)),
+            )
+        })?;
+        Self::from_resit_reader_with_options(BufReader::new(data), session, options)
+    }
+
+    /// Parse [`StudentResult`] from a resit report raw data of the
+    /// [`ResitSession`] in a reader with the [`ParseOptions`].
+    ///
+    /// See [`StudentResult::from_resit_with_options`] for more information.
+    pub fn from_resit_reader_with_options<R: Read + Seek>(
+        data: R,
+        session: ResitSession,
+        options: &ParseOptions,
+    ) -> Result<(Vec<StudentResult>, Vec<RowDiagnostic>), ParseResitError> {
+        Self::parse_resit(data, session, options)
+    }
+
+    /// Parse [`StudentResult`] from a May resit report (0C) raw data.
+    #[deprecated(note = "use `StudentResult::from_resit` with `ResitSession::May`")]
+    pub fn from_resit_may<P: AsRef<Path>>(data: P) -> Result<Vec<StudentResult>, ParseResitError> {
+        Self::from_resit(data, ResitSession::May)
+    }
+
+    /// Parse [`StudentResult`] from an August resit report (0D) raw data.
+    #[deprecated(note = "use `StudentResult::from_resit` with `ResitSession::August`")]
+    pub fn from_resit_aug<P: AsRef<Path>>(data: P) -> Result<Vec<StudentResult>, ParseResitError> {
+        Self::from_resit(data, ResitSession::August)
+    }
+
+    /// Parse [`StudentResult`] from a resit report raw data of the
+    /// [`ResitSession`] in a reader.
+    ///
+    /// The first visible worksheet of the workbook is parsed. Invalid rows
+    /// are skipped and reported if `lenient` is set, otherwise the first
+    /// invalid row is returned as an error.
+    fn parse_resit<R: Read + Seek>(
+        data: R,
+        session: ResitSession,
+        options: &ParseOptions,
+    ) -> Result<(Vec<StudentResult>, Vec<RowDiagnostic>), ParseResitError> {
+        let error = |kind| ParseResitError::new(session, kind);
+        let mut output = vec![];
+        let mut diagnostics = vec![];
+
+        // Checking workbook
+        let archive = SpreadsheetArchive::from_reader(data)
+            .map_err(|e| error(ParseResitErrorKind::StyleError(e)))?;
+        let mut excel = archive
+            .excel()
+            .map_err(|e| error(ParseResitErrorKind::WorkbookError(e)))?;
+        let name = excel
+            .sheets_metadata()
+            .iter()
+            .find(|sheet| {
+                sheet.typ == SheetType::WorkSheet && sheet.visible == SheetVisible::Visible
+            })
+            .map(|sheet| sheet.name.clone())
+            .ok_or_else(|| error(ParseResitErrorKind::InvalidWorksheet))?;
+        let range = excel
+            .worksheet_range(&name)
+            .map_err(|e| error(ParseResitErrorKind::WorkbookError(e)))?;
+
+        // Getting Headers
+        let sheet_headers = SheetHeaders::from_range(name, &range)
+            .ok_or_else(|| error(ParseResitErrorKind::NoHeaders))?;
+        let layout = options.layouts.resit(session);
+        let sub_headers = sheet_headers.sub_headers.as_deref();
+        if layout.header_rows() > 1 && sub_headers.is_none() {
+            return Err(error(ParseResitErrorKind::NoSubheader));
+        }
+        let headers = layout
+            .headers(&sheet_headers.headers, sub_headers)
+            .map_err(|e| error(ParseResitErrorKind::InvalidHeaders(e)))?;
+        let id_col = headers
+            .iter()
+            .position(|h| *h == ResitHeader::Id)
+            .ok_or_else(|| error(ParseResitErrorKind::InvalidHeaders(String::from("ID"))))?;
+
+        // Grouping the rows of each record by the merged student ID cells
+        let styles = archive
+            .styles()
+            .map_err(|e| error(ParseResitErrorKind::StyleError(e)))?;
+        let sheet = archive
+            .worksheet(&sheet_headers.name)
+            .map_err(|e| error(ParseResitErrorKind::StyleError(e)))?;
+        let last_row = range.end().map_or(0, |(row, _)| row as usize + 1);
+        let records: Vec<_> = sheet
+            .records(id_col, layout.header_rows() + 1..=last_row)
+            .into_iter()
+            .map(|rows| {
+                let values = record_values(&range, rows.clone());
+                (rows, values)
+            })
+            // Skipping empty rows
+            .filter(|(_, values)| values.iter().any(|v| !v.is_empty()))
+            .collect();
+
+        // Extracting the fill of the modules, including merged multi-row cells
+        let fills: Vec<Vec<_>> = records
+            .iter()
+            .map(|(rows, _)| {
+                headers
+                    .iter()
+                    .zip(XlsxColumns::new())
+                    .map(|(header, column)| match header {
+                        ResitHeader::Course => sheet.record_fill(styles, &column, rows.clone()),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Parsing data
+        for ((rows, data), fills) in records.iter().zip(&fills) {
+            let sheet_row = *rows.start();
+            // The skipped resits of a valid row are reported with the errors
+            // of the skipped rows
+            let errors = match Self::from_resit_row(&headers, data, fills, session, options) {
+                Ok((row_data, skipped)) => {
+                    output.push(row_data);
+                    skipped
+                }
+                Err(mut errors) if !options.lenient => {
+                    let (col, err) = errors.swap_remove(0);
+                    let context = sheet_headers.cell_context(
+                        sheet_row,
+                        col,
+                        data.get(col).map(|v| record_value(v)).as_ref(),
+                        headers[col].expected(),
+                    );
+                    return Err(error(ParseResitErrorKind::InvalidDataRow(
+                        Box::new(context),
+                        err,
+                    )));
+                }
+                Err(errors) => errors,
+            };
+            diagnostics.extend(errors.into_iter().map(|(col, err)| {
+                sheet_headers.diagnostic(
+                    sheet_row,
+                    col,
+                    data.get(col).map(|v| record_value(v)).as_ref(),
+                    headers[col].expected(),
+                    err,
+                )
+            }));
+        }
+
+        Ok((output, diagnostics))
+    }
+}
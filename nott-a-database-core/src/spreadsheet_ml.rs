@@ -2,14 +2,28 @@
 //!
 //! This module only implement a small part of the Office Open XML document for
 //! SpreadsheetML. Only a small part of the [`Styles Part`](Styles),
-//! [`Archive Relationships`](Relationships), [`Worksheet Part`](Sheets), and
-//! [`Workbook Part`](Workbook). See the
+//! [`Archive Relationships`](Relationships), [`Worksheet Part`](Sheets),
+//! [`Theme Part`](Theme) and [`Workbook Part`](Workbook). See the
 //! [spec](https://www.iso.org/standard/71691.html) for more information.
-use std::{fmt::Debug, fs::File, io::Read, iter::Cloned, path::Path, slice::Iter};
+use std::{
+    cell::{OnceCell, RefCell, RefMut},
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    fs::File,
+    io::{Cursor, Read, Seek},
+    iter::Cloned,
+    ops::RangeInclusive,
+    path::Path,
+    rc::Rc,
+    slice::Iter,
+    sync::Arc,
+};
 
+use calamine::{open_workbook_from_rs, Data, DataType, Range, Xlsx, XlsxError};
 use quick_xml::de::from_str;
 use serde::{de::Visitor, Deserialize};
-use zip::ZipArchive;
+use zip::{result::ZipError, ZipArchive};
 
 use crate::{errors::ParseStyleError, ColourValue};
 
@@ -21,6 +35,16 @@ pub struct Styles {
     /// The styles of each cell.
     #[serde(rename = "cellXfs")]
     pub cell_xfs: CellXf,
+    /// The custom colours of the workbook.
+    pub colors: Option<Colors>,
+    /// The differential formatting used by conditional formatting rules.
+    pub dxfs: Option<Dxfs>,
+    /// The theme of the workbook used to resolve theme colours.
+    ///
+    /// This is not part of the `Styles Part`, it is only set when the styles
+    /// are read with [`SpreadsheetArchive::styles`].
+    #[serde(skip)]
+    pub theme: Option<Theme>,
 }
 
 /// The styles for the fill of a cell.
@@ -61,6 +85,10 @@ pub enum PatternType {
 }
 
 /// The foreground colour for a given pattern fill.
+///
+/// The colour is given either as an ARGB value, a colour of the
+/// [`Theme`] or a colour of the legacy indexed palette. Use
+/// [`FgColor::colour`] to get the actual colour.
 #[derive(Debug, Deserialize)]
 pub struct FgColor {
     /// The ARGB value of the pattern fill.
@@ -69,10 +97,338 @@ pub struct FgColor {
     /// intensity, and the blue intensity.
     ///
     /// The value is a 8 digit hexadecimal number encoded as a string.
+    #[serde(
+        rename = "@rgb",
+        default,
+        deserialize_with = "deserialize_optional_colour_value"
+    )]
+    pub rgb: Option<ColourValue>,
+    /// The index of the colour in the colour scheme of the [`Theme`].
+    #[serde(rename = "@theme")]
+    pub theme: Option<usize>,
+    /// The tint applied to the colour, from -1.0 (100% darken) to 1.0 (100%
+    /// lighten).
+    #[serde(rename = "@tint")]
+    pub tint: Option<f64>,
+    /// The index of the colour in the legacy indexed palette.
+    #[serde(rename = "@indexed")]
+    pub indexed: Option<usize>,
+}
+
+impl FgColor {
+    /// Gets the actual colour of the fill.
+    ///
+    /// Theme colours are resolved with the colour scheme of the `theme` and
+    /// indexed colours with the custom `colors` of the workbook or the
+    /// default palette. The tint is applied afterwards.
+    ///
+    /// Returns [`None`] if the colour can't be resolved, e.g. there is no
+    /// theme.
+    pub fn colour(&self, theme: Option<&Theme>, colors: Option<&Colors>) -> Option<ColourValue> {
+        let colour = if let Some(rgb) = &self.rgb {
+            rgb.clone()
+        } else if let Some(index) = self.theme {
+            theme?.colour(index)?
+        } else if let Some(index) = self.indexed {
+            colors
+                .and_then(|c| c.indexed_colour(index))
+                .or_else(|| indexed_colour(index))?
+        } else {
+            return None;
+        };
+
+        Some(match self.tint {
+            Some(tint) if tint != 0.0 => colour.with_tint(tint),
+            _ => colour,
+        })
+    }
+}
+
+/// The custom colours of the workbook.
+#[derive(Debug, Deserialize)]
+pub struct Colors {
+    /// The custom legacy indexed palette.
+    #[serde(rename = "indexedColors")]
+    pub indexed_colors: Option<IndexedColors>,
+}
+
+impl Colors {
+    /// Gets the colour at `index` of the custom legacy indexed palette.
+    pub fn indexed_colour(&self, index: usize) -> Option<ColourValue> {
+        self.indexed_colors
+            .as_ref()?
+            .rgb_color
+            .get(index)
+            .map(|c| c.rgb.clone())
+    }
+}
+
+/// A custom legacy indexed palette.
+#[derive(Debug, Deserialize)]
+pub struct IndexedColors {
+    /// All the colours in the palette.
+    #[serde(rename = "rgbColor", default)]
+    pub rgb_color: Vec<RgbColor>,
+}
+
+/// A colour in a custom legacy indexed palette.
+#[derive(Debug, Deserialize)]
+pub struct RgbColor {
+    /// The ARGB value of the colour.
     #[serde(rename = "@rgb", deserialize_with = "deserialize_colour_value")]
     pub rgb: ColourValue,
 }
 
+/// The default legacy indexed palette as RGB values.
+///
+/// The index 64 and 65 are the system foreground and background colours.
+const INDEXED_COLOURS: [u32; 66] = [
+    0x000000, 0xFFFFFF, 0xFF0000, 0x00FF00, 0x0000FF, 0xFFFF00, 0xFF00FF, 0x00FFFF, // 0-7
+    0x000000, 0xFFFFFF, 0xFF0000, 0x00FF00, 0x0000FF, 0xFFFF00, 0xFF00FF, 0x00FFFF, // 8-15
+    0x800000, 0x008000, 0x000080, 0x808000, 0x800080, 0x008080, 0xC0C0C0, 0x808080, // 16-23
+    0x9999FF, 0x993366, 0xFFFFCC, 0xCCFFFF, 0x660066, 0xFF8080, 0x0066CC, 0xCCCCFF, // 24-31
+    0x000080, 0xFF00FF, 0xFFFF00, 0x00FFFF, 0x800080, 0x800000, 0x008080, 0x0000FF, // 32-39
+    0x00CCFF, 0xCCFFFF, 0xCCFFCC, 0xFFFF99, 0x99CCFF, 0xFF99CC, 0xCC99FF, 0xFFCC99, // 40-47
+    0x3366FF, 0x33CCCC, 0x99CC00, 0xFFCC00, 0xFF9900, 0xFF6600, 0x666699, 0x969696, // 48-55
+    0x003366, 0x339966, 0x003300, 0x333300, 0x993300, 0x993366, 0x333399, 0x333333, // 56-63
+    0x000000, 0xFFFFFF, // 64-65
+];
+
+/// Gets the colour at `index` of the default legacy indexed palette.
+fn indexed_colour(index: usize) -> Option<ColourValue> {
+    let rgb = INDEXED_COLOURS.get(index)?;
+    Some(ColourValue {
+        alpha: 255,
+        red: (rgb >> 16) as u8,
+        green: (rgb >> 8) as u8,
+        blue: *rgb as u8,
+    })
+}
+
+impl ColourValue {
+    /// Applies a `tint` to the colour.
+    ///
+    /// The tint is a value from -1.0 to 1.0, the colour is darkened if it is
+    /// negative and lightened if it is positive. The luminance is adjusted in
+    /// the HLS colour space as defined in the spec.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use nott_a_database_core::ColourValue;
+    ///
+    /// let colour = ColourValue { alpha: 255, red: 255, green: 0, blue: 0 };
+    /// let lighter = colour.with_tint(0.5);
+    /// assert_eq!((lighter.red, lighter.green, lighter.blue), (255, 128, 128));
+    /// ```
+    pub fn with_tint(&self, tint: f64) -> ColourValue {
+        let (hue, luminance, saturation) = self.to_hls();
+        let luminance = if tint < 0.0 {
+            luminance * (1.0 + tint)
+        } else {
+            luminance * (1.0 - tint) + tint
+        };
+
+        let (red, green, blue) = hls_to_rgb(hue, luminance.clamp(0.0, 1.0), saturation);
+        ColourValue {
+            alpha: self.alpha,
+            red,
+            green,
+            blue,
+        }
+    }
+
+    /// Converts the colour into the hue, luminance and saturation values
+    /// from 0.0 to 1.0.
+    fn to_hls(&self) -> (f64, f64, f64) {
+        let red = f64::from(self.red) / 255.0;
+        let green = f64::from(self.green) / 255.0;
+        let blue = f64::from(self.blue) / 255.0;
+        let max = red.max(green).max(blue);
+        let min = red.min(green).min(blue);
+        let luminance = (max + min) / 2.0;
+
+        if max == min {
+            return (0.0, luminance, 0.0);
+        }
+
+        let delta = max - min;
+        let saturation = if luminance > 0.5 {
+            delta / (2.0 - max - min)
+        } else {
+            delta / (max + min)
+        };
+        let hue = if max == red {
+            (green - blue) / delta + if green < blue { 6.0 } else { 0.0 }
+        } else if max == green {
+            (blue - red) / delta + 2.0
+        } else {
+            (red - green) / delta + 4.0
+        };
+
+        (hue / 6.0, luminance, saturation)
+    }
+}
+
+/// Converts the hue, luminance and saturation values from 0.0 to 1.0 into the
+/// red, green and blue values.
+fn hls_to_rgb(hue: f64, luminance: f64, saturation: f64) -> (u8, u8, u8) {
+    let to_u8 = |value: f64| (value * 255.0).round() as u8;
+    if saturation == 0.0 {
+        let value = to_u8(luminance);
+        return (value, value, value);
+    }
+
+    let q = if luminance < 0.5 {
+        luminance * (1.0 + saturation)
+    } else {
+        luminance + saturation - luminance * saturation
+    };
+    let p = 2.0 * luminance - q;
+    let hue_to_rgb = |t: f64| {
+        let t = t.rem_euclid(1.0);
+        if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 1.0 / 2.0 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        }
+    };
+
+    (
+        to_u8(hue_to_rgb(hue + 1.0 / 3.0)),
+        to_u8(hue_to_rgb(hue)),
+        to_u8(hue_to_rgb(hue - 1.0 / 3.0)),
+    )
+}
+
+/// The `Theme Part` in the workbook.
+#[derive(Debug, Deserialize)]
+pub struct Theme {
+    /// The elements of the theme.
+    #[serde(rename = "themeElements")]
+    pub theme_elements: ThemeElements,
+}
+
+impl Theme {
+    /// Gets the colour at `index` of the colour scheme.
+    ///
+    /// See [`ColourScheme::get`] for the order of the colours.
+    pub fn colour(&self, index: usize) -> Option<ColourValue> {
+        self.theme_elements.clr_scheme.get(index)?.colour()
+    }
+}
+
+/// The elements of a theme.
+#[derive(Debug, Deserialize)]
+pub struct ThemeElements {
+    /// The colour scheme of the theme.
+    #[serde(rename = "clrScheme")]
+    pub clr_scheme: ColourScheme,
+}
+
+/// The colour scheme of a theme.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColourScheme {
+    /// The dark 1 colour, usually the text colour.
+    pub dk1: ThemeColour,
+    /// The light 1 colour, usually the background colour.
+    pub lt1: ThemeColour,
+    /// The dark 2 colour.
+    pub dk2: ThemeColour,
+    /// The light 2 colour.
+    pub lt2: ThemeColour,
+    /// The accent 1 colour.
+    pub accent1: ThemeColour,
+    /// The accent 2 colour.
+    pub accent2: ThemeColour,
+    /// The accent 3 colour.
+    pub accent3: ThemeColour,
+    /// The accent 4 colour.
+    pub accent4: ThemeColour,
+    /// The accent 5 colour.
+    pub accent5: ThemeColour,
+    /// The accent 6 colour.
+    pub accent6: ThemeColour,
+    /// The hyperlink colour.
+    pub hlink: ThemeColour,
+    /// The followed hyperlink colour.
+    pub fol_hlink: ThemeColour,
+}
+
+impl ColourScheme {
+    /// Gets the colour at the theme colour `index` of a cell.
+    ///
+    /// The light and dark colours are swapped in the index, i.e. the order is
+    /// `lt1`, `dk1`, `lt2`, `dk2`, `accent1` to `accent6`, `hlink` and
+    /// `folHlink`.
+    pub fn get(&self, index: usize) -> Option<&ThemeColour> {
+        Some(match index {
+            0 => &self.lt1,
+            1 => &self.dk1,
+            2 => &self.lt2,
+            3 => &self.dk2,
+            4 => &self.accent1,
+            5 => &self.accent2,
+            6 => &self.accent3,
+            7 => &self.accent4,
+            8 => &self.accent5,
+            9 => &self.accent6,
+            10 => &self.hlink,
+            11 => &self.fol_hlink,
+            _ => return None,
+        })
+    }
+}
+
+/// A colour in the colour scheme of a theme.
+#[derive(Debug, Deserialize)]
+pub struct ThemeColour {
+    /// The colour as an RGB value.
+    #[serde(rename = "srgbClr")]
+    pub srgb_clr: Option<SrgbColour>,
+    /// The colour as a system colour.
+    #[serde(rename = "sysClr")]
+    pub sys_clr: Option<SystemColour>,
+}
+
+impl ThemeColour {
+    /// Gets the actual colour of the theme colour.
+    ///
+    /// The last computed value is used for system colours.
+    pub fn colour(&self) -> Option<ColourValue> {
+        if let Some(srgb) = &self.srgb_clr {
+            Some(srgb.val.clone())
+        } else {
+            self.sys_clr.as_ref()?.last_clr.clone()
+        }
+    }
+}
+
+/// An RGB colour in a theme.
+#[derive(Debug, Deserialize)]
+pub struct SrgbColour {
+    /// The RGB value of the colour.
+    #[serde(rename = "@val", deserialize_with = "deserialize_colour_value")]
+    pub val: ColourValue,
+}
+
+/// A system colour in a theme.
+#[derive(Debug, Deserialize)]
+pub struct SystemColour {
+    /// The last computed RGB value of the system colour.
+    #[serde(
+        rename = "@lastClr",
+        default,
+        deserialize_with = "deserialize_optional_colour_value"
+    )]
+    pub last_clr: Option<ColourValue>,
+}
+
 fn deserialize_colour_value<'de, D>(deserializer: D) -> Result<ColourValue, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -80,26 +436,40 @@ where
     deserializer.deserialize_str(HexVisitor)
 }
 
-/// Custom visitor for parsing [`ColourValue`] from an 8 digit hexadecimal string value.
+fn deserialize_optional_colour_value<'de, D>(
+    deserializer: D,
+) -> Result<Option<ColourValue>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    deserializer.deserialize_str(HexVisitor).map(Some)
+}
+
+/// Custom visitor for parsing [`ColourValue`] from an 8 digit ARGB or a 6
+/// digit RGB hexadecimal string value.
 struct HexVisitor;
 
 impl Visitor<'_> for HexVisitor {
     type Value = ColourValue;
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        formatter.write_str("a 8 digit hexadecimal number")
+        formatter.write_str("a 6 or 8 digit hexadecimal number")
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
         E: serde::de::Error,
     {
-        if v.len() != 8 {
-            return Err(E::custom(format!(
-                "The &str is {} characters long and not 8.",
-                v.len()
-            )));
-        }
+        // RGB values are fully opaque
+        let v = match v.len() {
+            8 => v.to_owned(),
+            6 => format!("FF{v}"),
+            len => {
+                return Err(E::custom(format!(
+                    "The &str is {len} characters long and not 6 or 8."
+                )))
+            }
+        };
         Ok(Self::Value {
             alpha: u8::from_str_radix(&v[0..=1], 16)
                 .map_err(|_| E::custom(format!("Invalide hex number {}", &v[0..=1])))?,
@@ -113,6 +483,78 @@ impl Visitor<'_> for HexVisitor {
     }
 }
 
+impl Styles {
+    /// Gets the foreground colour of the fill used by the cell style
+    /// `style_id`.
+    ///
+    /// Theme and indexed colours are resolved into the actual colour, see
+    /// [`FgColor::colour`].
+    ///
+    /// Returns [`None`] if the style is not found or has no fill colour.
+    pub fn fill(&self, style_id: usize) -> Option<ColourValue> {
+        let fill_id = self.cell_xfs.xf.get(style_id)?.fill_id;
+        let fill = self.fills.fill.get(fill_id)?;
+        fill.pattern_fill
+            .fg_color
+            .as_ref()?
+            .colour(self.theme.as_ref(), self.colors.as_ref())
+    }
+
+    /// Gets the fill colour of the differential formatting `dxf_id`.
+    ///
+    /// The background colour is used if it is set, otherwise the foreground
+    /// colour is used.
+    ///
+    /// Returns [`None`] if the formatting is not found or has no fill colour.
+    pub fn dxf_fill(&self, dxf_id: usize) -> Option<ColourValue> {
+        let dxf = self.dxfs.as_ref()?.dxf.get(dxf_id)?;
+        let pattern_fill = dxf.fill.as_ref()?.pattern_fill.as_ref()?;
+        pattern_fill
+            .bg_color
+            .as_ref()
+            .or(pattern_fill.fg_color.as_ref())?
+            .colour(self.theme.as_ref(), self.colors.as_ref())
+    }
+}
+
+/// The differential formatting used by conditional formatting rules.
+#[derive(Debug, Deserialize)]
+pub struct Dxfs {
+    /// All the differential formatting in the workbook.
+    #[serde(default)]
+    pub dxf: Vec<Dxf>,
+}
+
+/// A differential formatting, i.e. the formatting applied on top of the cell
+/// style when a conditional formatting rule matches.
+#[derive(Debug, Deserialize)]
+pub struct Dxf {
+    /// The fill applied to the cell.
+    pub fill: Option<DxfFill>,
+}
+
+/// The fill of a differential formatting.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DxfFill {
+    /// The fill information of the fill entry.
+    pub pattern_fill: Option<DxfPatternFill>,
+}
+
+/// A pattern fill of a differential formatting.
+///
+/// Unlike [`PatternFill`], the colour of a solid fill is usually stored in
+/// the background colour.
+#[derive(Debug, Deserialize)]
+pub struct DxfPatternFill {
+    /// The foreground colour of the fill.
+    #[serde(rename = "fgColor")]
+    pub fg_color: Option<FgColor>,
+    /// The background colour of the fill.
+    #[serde(rename = "bgColor")]
+    pub bg_color: Option<FgColor>,
+}
+
 /// The formatting of all cell styles.
 #[derive(Debug, Deserialize)]
 pub struct CellXf {
@@ -181,20 +623,156 @@ pub struct Worksheet {
     /// The data in the worksheet.
     #[serde(rename = "sheetData")]
     pub sheet_data: SheetData,
+    /// The conditional formatting of the worksheet.
+    #[serde(rename = "conditionalFormatting", default)]
+    pub conditional_formatting: Vec<ConditionalFormatting>,
+    /// The merged cells of the worksheet.
+    #[serde(rename = "mergeCells")]
+    pub merge_cells: Option<MergeCells>,
+}
+
+impl Worksheet {
+    /// Gets the row with the row number `row` (1-based).
+    ///
+    /// Returns [`None`] if the row is not stored, e.g. an empty row.
+    pub fn row(&self, row: usize) -> Option<&SheetRow> {
+        let mut previous = 0;
+        self.sheet_data.row.iter().find(|data| {
+            previous = data.row.unwrap_or(previous + 1);
+            previous == row
+        })
+    }
+
+    /// Gets the cell at the location `cell`, e.g. `A1`.
+    pub fn cell(&self, cell: &str) -> Option<&SheetCell> {
+        self.sheet_data
+            .row
+            .iter()
+            .flat_map(|row| &row.cells)
+            .find(|c| c.cell == cell)
+    }
+
+    /// Gets the fill colour of the cell at the location `cell`.
+    ///
+    /// Returns [`None`] if the cell is not found or has no fill.
+    pub fn fill(&self, styles: &Styles, cell: &str) -> Option<ColourValue> {
+        let style_id: usize = self.cell(cell)?.style.parse().ok()?;
+        styles.fill(style_id)
+    }
+
+    /// Gets the fill colour of a record spanning the `rows` in the `column`,
+    /// e.g. the merged cells of a module in a resit report.
+    ///
+    /// The fill of the last cell with a value (the newest attempt) is used.
+    /// The fill of the first cell is used if the last cell has no fill as the
+    /// style of merged cells is stored in the top-left cell.
+    pub fn record_fill(
+        &self,
+        styles: &Styles,
+        column: &str,
+        rows: RangeInclusive<usize>,
+    ) -> Option<ColourValue> {
+        let first = format!("{column}{}", rows.start());
+        let last = rows
+            .rev()
+            .map(|row| format!("{column}{row}"))
+            .find(|cell| self.cell(cell).is_some_and(|c| c.value.is_some()));
+
+        last.and_then(|cell| self.fill(styles, &cell))
+            .or_else(|| self.fill(styles, &first))
+    }
+
+    /// Gets the fill colour applied by the conditional formatting of the
+    /// worksheet to the cell at the location `cell`.
+    ///
+    /// See [`conditional_fill`] for how the rules are evaluated.
+    pub fn conditional_fill<F>(&self, styles: &Styles, cell: &str, value: F) -> Option<ColourValue>
+    where
+        F: Fn(&str) -> Option<Data>,
+    {
+        conditional_fill(&self.conditional_formatting, styles, cell, value)
+    }
+
+    /// Gets the rows spanned by the merged cells containing the cell in the
+    /// `column` (0-based) and `row` (1-based).
+    ///
+    /// Returns [`None`] if the cell is not merged.
+    pub fn merged_rows(&self, column: usize, row: usize) -> Option<RangeInclusive<usize>> {
+        self.merge_cells
+            .iter()
+            .flat_map(|cells| &cells.merge_cell)
+            .filter_map(MergeCell::range)
+            .find(|(start, end)| {
+                (start.column..=end.column).contains(&column)
+                    && (start.row..=end.row).contains(&row)
+            })
+            .map(|(start, end)| start.row..=end.row)
+    }
+
+    /// Groups the `rows` (1-based) into records by the merged cells in the
+    /// `column` (0-based), e.g. the student ID merged across a row for each
+    /// attempt of a student in a resit report.
+    ///
+    /// A row which is not merged in the `column` is a record of its own.
+    pub fn records(
+        &self,
+        column: usize,
+        rows: RangeInclusive<usize>,
+    ) -> Vec<RangeInclusive<usize>> {
+        let mut records = vec![];
+        let mut row = *rows.start();
+
+        while row <= *rows.end() {
+            let end = self
+                .merged_rows(column, row)
+                .map_or(row, |merged| *merged.end())
+                .clamp(row, *rows.end());
+            records.push(row..=end);
+            row = end + 1;
+        }
+
+        records
+    }
+}
+
+/// Gets the values of every column of a record spanning the `rows` (1-based)
+/// of a worksheet, e.g. the module code followed by the mark of each attempt.
+///
+/// Only the rows with a value are kept, a merged cell only has a value in its
+/// first row.
+pub fn record_values(range: &Range<Data>, rows: RangeInclusive<usize>) -> Vec<Vec<Data>> {
+    let Some((_, last_column)) = range.end() else {
+        return vec![];
+    };
+
+    (0..=last_column)
+        .map(|column| {
+            rows.clone()
+                .filter_map(|row| range.get_value(((row - 1) as u32, column)))
+                .filter(|value| !value.is_empty())
+                .cloned()
+                .collect()
+        })
+        .collect()
 }
 
 /// The data in the worksheet.
 #[derive(Debug, Deserialize)]
 pub struct SheetData {
     /// All the rows of data in the worksheet.
+    #[serde(default)]
     pub row: Vec<SheetRow>,
 }
 
 /// A row of data in the worksheet.
 #[derive(Debug, Deserialize)]
 pub struct SheetRow {
+    /// The row number (1-based) of the row, the row after the previous row
+    /// if not given.
+    #[serde(rename = "@r")]
+    pub row: Option<usize>,
     /// All the cell in the row of data.
-    #[serde(rename = "c")]
+    #[serde(rename = "c", default)]
     pub cells: Vec<SheetCell>,
 }
 
@@ -207,6 +785,487 @@ pub struct SheetCell {
     /// The ID of the style used.
     #[serde(rename = "@s")]
     pub style: String,
+    /// The raw value of the cell, e.g. the index of the shared string.
+    #[serde(rename = "v")]
+    pub value: Option<String>,
+}
+
+/// The merged cells of a worksheet.
+#[derive(Debug, Deserialize)]
+pub struct MergeCells {
+    /// All the ranges of merged cells.
+    #[serde(rename = "mergeCell", default)]
+    pub merge_cell: Vec<MergeCell>,
+}
+
+/// A range of merged cells.
+#[derive(Debug, Deserialize)]
+pub struct MergeCell {
+    /// The range of the merged cells, e.g. `B3:B6`.
+    #[serde(rename = "@ref")]
+    pub reference: String,
+}
+
+impl MergeCell {
+    /// Gets the top-left and bottom-right cells of the range.
+    fn range(&self) -> Option<(CellPosition, CellPosition)> {
+        let (start, end) = self.reference.split_once(':')?;
+        Some((CellPosition::parse(start)?, CellPosition::parse(end)?))
+    }
+}
+
+/// The conditional formatting rules applied to a range of cells.
+#[derive(Debug, Deserialize)]
+pub struct ConditionalFormatting {
+    /// The space-separated ranges the rules are applied to, e.g.
+    /// `C3:Z50 AB3`.
+    #[serde(rename = "@sqref")]
+    pub sqref: String,
+    /// The rules of the conditional formatting.
+    #[serde(rename = "cfRule", default)]
+    pub cf_rule: Vec<CfRule>,
+}
+
+impl ConditionalFormatting {
+    /// Gets all the ranges of cells as the top-left and bottom-right cells.
+    fn ranges(&self) -> impl Iterator<Item = (CellPosition, CellPosition)> + '_ {
+        self.sqref.split_whitespace().filter_map(|range| {
+            let (start, end) = range.split_once(':').unwrap_or((range, range));
+            Some((CellPosition::parse(start)?, CellPosition::parse(end)?))
+        })
+    }
+
+    /// Checks if the cell at `position` is in the ranges of cells.
+    fn contains(&self, position: CellPosition) -> bool {
+        self.ranges().any(|(start, end)| {
+            (start.column..=end.column).contains(&position.column)
+                && (start.row..=end.row).contains(&position.row)
+        })
+    }
+}
+
+/// A conditional formatting rule.
+#[derive(Debug, Deserialize)]
+pub struct CfRule {
+    /// The type of the rule.
+    #[serde(rename = "@type")]
+    pub rule_type: CfRuleType,
+    /// The ID of the differential formatting applied when the rule matches.
+    #[serde(rename = "@dxfId")]
+    pub dxf_id: Option<usize>,
+    /// The priority of the rule, rules with a lower value are evaluated
+    /// first.
+    #[serde(rename = "@priority")]
+    pub priority: usize,
+    /// The comparison operator of a `cellIs` rule.
+    #[serde(rename = "@operator")]
+    pub operator: Option<CfOperator>,
+    /// Whether the rules with a lower priority are skipped when the rule
+    /// matches.
+    #[serde(rename = "@stopIfTrue", default)]
+    pub stop_if_true: bool,
+    /// The formulas of the rule.
+    #[serde(default)]
+    pub formula: Vec<String>,
+}
+
+/// The different type of conditional formatting rules.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CfRuleType {
+    /// Compares the value of the cell with the formulas.
+    CellIs,
+    /// Evaluates a formula.
+    Expression,
+    /// Other rule types which are not supported.
+    #[serde(other)]
+    Other,
+}
+
+/// The comparison operators of a `cellIs` rule.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CfOperator {
+    /// The value is less than the formula.
+    LessThan,
+    /// The value is less than or equal to the formula.
+    LessThanOrEqual,
+    /// The value is equal to the formula.
+    Equal,
+    /// The value is not equal to the formula.
+    NotEqual,
+    /// The value is greater than or equal to the formula.
+    GreaterThanOrEqual,
+    /// The value is greater than the formula.
+    GreaterThan,
+    /// The value is between the two formulas (inclusive).
+    Between,
+    /// The value is not between the two formulas.
+    NotBetween,
+    /// Other operators which are not supported, e.g. `containsText`.
+    #[serde(other)]
+    Other,
+}
+
+impl CfRule {
+    /// Checks if the rule matches the cell at `position`.
+    ///
+    /// `offset` is the offset of the cell from the top-left cell of the
+    /// conditional formatting, used to shift the relative references of the
+    /// formulas.
+    fn matches<F>(&self, position: CellPosition, offset: (isize, isize), value: &F) -> bool
+    where
+        F: Fn(&str) -> Option<Data>,
+    {
+        let operand = |index: usize| {
+            self.formula
+                .get(index)
+                .and_then(|formula| Operand::parse(formula))
+                .and_then(|operand| operand.value(offset, value))
+        };
+
+        match self.rule_type {
+            CfRuleType::CellIs => {
+                let cell = value(&position.to_string()).unwrap_or(Data::Empty);
+                let Some(first) = operand(0) else {
+                    return false;
+                };
+                let Some(operator) = &self.operator else {
+                    return false;
+                };
+
+                match operator {
+                    CfOperator::Between | CfOperator::NotBetween => {
+                        let Some(second) = operand(1) else {
+                            return false;
+                        };
+                        let (Some(a), Some(b)) = (compare(&cell, &first), compare(&cell, &second))
+                        else {
+                            return false;
+                        };
+                        let between = a != b || a == Ordering::Equal;
+                        between == (*operator == CfOperator::Between)
+                    }
+                    CfOperator::Other => false,
+                    operator => {
+                        compare(&cell, &first).is_some_and(|ordering| operator.accepts(ordering))
+                    }
+                }
+            }
+            CfRuleType::Expression => self
+                .formula
+                .first()
+                .and_then(|formula| {
+                    let (left, operator, right) = split_comparison(formula)?;
+                    let left = Operand::parse(left)?.value(offset, value)?;
+                    let right = Operand::parse(right)?.value(offset, value)?;
+                    compare(&left, &right).map(|ordering| operator.accepts(ordering))
+                })
+                .unwrap_or(false),
+            CfRuleType::Other => false,
+        }
+    }
+}
+
+impl CfOperator {
+    /// Checks if the result of comparing a value with the formula satisfies
+    /// the operator.
+    fn accepts(&self, ordering: Ordering) -> bool {
+        match self {
+            CfOperator::LessThan => ordering.is_lt(),
+            CfOperator::LessThanOrEqual => ordering.is_le(),
+            CfOperator::Equal => ordering.is_eq(),
+            CfOperator::NotEqual => ordering.is_ne(),
+            CfOperator::GreaterThanOrEqual => ordering.is_ge(),
+            CfOperator::GreaterThan => ordering.is_gt(),
+            CfOperator::Between | CfOperator::NotBetween | CfOperator::Other => false,
+        }
+    }
+}
+
+/// Gets the fill colour applied to the cell at the location `cell` by the
+/// conditional `formats`.
+///
+/// The rules are evaluated in order of priority, the fill of the first
+/// matching rule with a fill is used. `value` gets the value of a cell
+/// referenced by the rules, e.g. `C3`, [`None`] is treated as an empty cell.
+///
+/// Only `cellIs` rules and `expression` rules with a single comparison (e.g.
+/// `$C3<40`) are supported, other rules never match.
+///
+/// Returns [`None`] if no rule with a fill matches.
+///
+/// # Examples
+///
+/// ```rust
+/// use calamine::Data;
+/// use nott_a_database_core::spreadsheet_ml::{
+///     conditional_fill, ConditionalFormatting, Styles,
+/// };
+///
+/// let styles: Styles = quick_xml::de::from_str(
+///     r#"<styleSheet>
+///         <fills><fill><patternFill patternType="none"/></fill></fills>
+///         <cellXfs><xf fillId="0"/></cellXfs>
+///         <dxfs><dxf><fill><patternFill><bgColor rgb="FFFF0000"/></patternFill></fill></dxf></dxfs>
+///     </styleSheet>"#,
+/// )
+/// .expect("Invalid styles");
+/// let red = styles.dxf_fill(0);
+/// assert!(red.is_some());
+/// let format = |rule: &str, formulas: &str| -> Vec<ConditionalFormatting> {
+///     let xml = format!(
+///         r#"<conditionalFormatting sqref="C3:E10">
+///             <cfRule {rule} dxfId="0" priority="1">{formulas}</cfRule>
+///         </conditionalFormatting>"#
+///     );
+///     vec![quick_xml::de::from_str(&xml).expect("Invalid conditional formatting")]
+/// };
+/// let marks = |cell: &str| match cell {
+///     "C3" => Some(Data::Float(35.0)),
+///     "C4" => Some(Data::Float(62.0)),
+///     "C5" => Some(Data::String(String::new())),
+///     "C6" => Some(Data::Float(0.0)),
+///     "C7" => Some(Data::Float(40.0)),
+///     _ => None,
+/// };
+///
+/// // The column of `$C3` is absolute, so E3 is filled by the mark in C3
+/// let formats = format(r#"type="expression""#, "<formula>$C3&lt;40</formula>");
+/// assert_eq!(conditional_fill(&formats, &styles, "C3", marks), red);
+/// assert_eq!(conditional_fill(&formats, &styles, "E3", marks), red);
+/// assert_eq!(conditional_fill(&formats, &styles, "C4", marks), None);
+///
+/// // The bounds of `between` can be in any order and are inclusive
+/// let formats = format(
+///     r#"type="cellIs" operator="between""#,
+///     "<formula>40</formula><formula>30</formula>",
+/// );
+/// assert_eq!(conditional_fill(&formats, &styles, "C3", marks), red);
+/// assert_eq!(conditional_fill(&formats, &styles, "C7", marks), red);
+/// assert_eq!(conditional_fill(&formats, &styles, "C4", marks), None);
+///
+/// // An empty cell (C8) is equal to both `0` and `""`, unlike an empty text
+/// // (C5) which is only equal to `""` and a zero (C6) which is only equal to `0`
+/// let formats = format(r#"type="cellIs" operator="equal""#, "<formula>0</formula>");
+/// assert_eq!(conditional_fill(&formats, &styles, "C8", marks), red);
+/// assert_eq!(conditional_fill(&formats, &styles, "C5", marks), None);
+/// assert_eq!(conditional_fill(&formats, &styles, "C6", marks), red);
+/// let formats = format(r#"type="cellIs" operator="equal""#, r#"<formula>""</formula>"#);
+/// assert_eq!(conditional_fill(&formats, &styles, "C8", marks), red);
+/// assert_eq!(conditional_fill(&formats, &styles, "C5", marks), red);
+/// assert_eq!(conditional_fill(&formats, &styles, "C6", marks), None);
+/// ```
+pub fn conditional_fill<F>(
+    formats: &[ConditionalFormatting],
+    styles: &Styles,
+    cell: &str,
+    value: F,
+) -> Option<ColourValue>
+where
+    F: Fn(&str) -> Option<Data>,
+{
+    let position = CellPosition::parse(cell)?;
+    let mut rules: Vec<_> = formats
+        .iter()
+        .filter(|format| format.contains(position))
+        .flat_map(|format| format.cf_rule.iter().map(move |rule| (format, rule)))
+        .collect();
+    rules.sort_by_key(|(_, rule)| rule.priority);
+
+    for (format, rule) in rules {
+        let Some((origin, _)) = format.ranges().next() else {
+            continue;
+        };
+        let offset = (
+            position.column as isize - origin.column as isize,
+            position.row as isize - origin.row as isize,
+        );
+        if !rule.matches(position, offset, &value) {
+            continue;
+        }
+
+        if let Some(fill) = rule.dxf_id.and_then(|id| styles.dxf_fill(id)) {
+            return Some(fill);
+        }
+        if rule.stop_if_true {
+            return None;
+        }
+    }
+
+    None
+}
+
+/// The location of a cell in a worksheet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CellPosition {
+    /// The index of the column (0-based).
+    column: usize,
+    /// The row number (1-based).
+    row: usize,
+}
+
+impl CellPosition {
+    /// Parses the location of a cell, e.g. `C3` or `$C$3`.
+    fn parse(cell: &str) -> Option<Self> {
+        let cell = cell.replace('$', "");
+        let split = cell.find(|c: char| c.is_ascii_digit())?;
+        let (column, row) = cell.split_at(split);
+        if column.is_empty() || !column.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+
+        let column = column
+            .to_ascii_uppercase()
+            .bytes()
+            .fold(0, |acc, c| acc * 26 + usize::from(c - b'A') + 1);
+        Some(Self {
+            column: column - 1,
+            row: row.parse().ok()?,
+        })
+    }
+}
+
+impl std::fmt::Display for CellPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let column = XlsxColumns::new()
+            .nth(self.column)
+            .expect("There should be an infinite amount of XLSX columns.");
+        write!(f, "{column}{}", self.row)
+    }
+}
+
+/// An operand of a formula of a conditional formatting rule.
+#[derive(Debug)]
+enum Operand {
+    /// A constant value.
+    Value(Data),
+    /// A reference to a cell, the column and row are shifted with the cell
+    /// evaluated unless they are absolute (`$`).
+    Reference {
+        position: CellPosition,
+        absolute_column: bool,
+        absolute_row: bool,
+    },
+}
+
+impl Operand {
+    /// Parses a number, a string, a boolean or a cell reference.
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if let Some(text) = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+            return Some(Self::Value(Data::String(text.replace("\"\"", "\""))));
+        }
+        if let Ok(number) = s.parse::<f64>() {
+            return Some(Self::Value(Data::Float(number)));
+        }
+        if s.eq_ignore_ascii_case("TRUE") {
+            return Some(Self::Value(Data::Bool(true)));
+        }
+        if s.eq_ignore_ascii_case("FALSE") {
+            return Some(Self::Value(Data::Bool(false)));
+        }
+
+        let absolute_column = s.starts_with('$');
+        let absolute_row = s.trim_start_matches('$').contains('$');
+        Some(Self::Reference {
+            position: CellPosition::parse(s)?,
+            absolute_column,
+            absolute_row,
+        })
+    }
+
+    /// Gets the value of the operand for a cell at `offset` from the top-left
+    /// cell of the conditional formatting.
+    fn value<F>(&self, offset: (isize, isize), value: &F) -> Option<Data>
+    where
+        F: Fn(&str) -> Option<Data>,
+    {
+        match self {
+            Operand::Value(data) => Some(data.clone()),
+            Operand::Reference {
+                position,
+                absolute_column,
+                absolute_row,
+            } => {
+                let column = if *absolute_column {
+                    position.column
+                } else {
+                    position.column.checked_add_signed(offset.0)?
+                };
+                let row = if *absolute_row {
+                    position.row
+                } else {
+                    position.row.checked_add_signed(offset.1)?
+                };
+                let cell = CellPosition { column, row };
+                Some(value(&cell.to_string()).unwrap_or(Data::Empty))
+            }
+        }
+    }
+}
+
+/// Splits a formula with a single comparison, e.g. `$C3<40`, into the left
+/// operand, the operator and the right operand.
+fn split_comparison(formula: &str) -> Option<(&str, CfOperator, &str)> {
+    let formula = formula.trim().trim_start_matches('=');
+    let mut in_string = false;
+    for (index, c) in formula.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '<' | '>' | '=' if !in_string => {
+                let rest = &formula[index + 1..];
+                let (operator, len) = match (c, rest.chars().next()) {
+                    ('<', Some('=')) => (CfOperator::LessThanOrEqual, 2),
+                    ('<', Some('>')) => (CfOperator::NotEqual, 2),
+                    ('>', Some('=')) => (CfOperator::GreaterThanOrEqual, 2),
+                    ('<', _) => (CfOperator::LessThan, 1),
+                    ('>', _) => (CfOperator::GreaterThan, 1),
+                    _ => (CfOperator::Equal, 1),
+                };
+                return Some((&formula[..index], operator, &formula[index + len..]));
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Compares two values the same way as Excel.
+///
+/// Numbers are less than text which are less than booleans, text is compared
+/// case-insensitively and empty cells are equal to `0`, `""` or `FALSE`.
+///
+/// Returns [`None`] if any of the values is an error.
+fn compare(a: &Data, b: &Data) -> Option<Ordering> {
+    /// The comparable value of a cell.
+    #[derive(PartialEq, PartialOrd)]
+    enum Value {
+        Number(f64),
+        Text(String),
+        Bool(bool),
+    }
+
+    fn value(data: &Data, other: &Data) -> Option<Value> {
+        match data {
+            Data::Int(i) => Some(Value::Number(*i as f64)),
+            Data::Float(f) => Some(Value::Number(*f)),
+            Data::DateTime(d) => Some(Value::Number(d.as_f64())),
+            Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => {
+                Some(Value::Text(s.to_lowercase()))
+            }
+            Data::Bool(b) => Some(Value::Bool(*b)),
+            Data::Empty => match other {
+                Data::String(_) => Some(Value::Text(String::new())),
+                Data::Bool(_) => Some(Value::Bool(false)),
+                _ => Some(Value::Number(0.0)),
+            },
+            Data::Error(_) => None,
+        }
+    }
+
+    value(a, b)?.partial_cmp(&value(b, a)?)
 }
 
 /// An iterator for all columns in an Excel worksheet.
@@ -357,6 +1416,232 @@ impl Iterator for ChainPermutation<'_> {
     }
 }
 
+/// A reader of the raw content of a [`SpreadsheetArchive`].
+pub type ArchiveReader = Cursor<Arc<[u8]>>;
+
+/// An XLSX archive that is only read once.
+///
+/// The raw content of the archive is kept in memory and the zip archive is
+/// opened once for the parsed [`Styles`], [`Workbook`], [`Relationships`] and
+/// [`Worksheet`] parts, which are cached. The workbook is opened once with
+/// [`calamine`] from the same content by [`SpreadsheetArchive::excel`], so
+/// the cell values and the styles of a worksheet are read from the same
+/// archive.
+///
+/// # Examples
+///
+/// ```rust
+/// use calamine::Reader;
+/// use nott_a_database_core::spreadsheet_ml::SpreadsheetArchive;
+///
+/// let archive = SpreadsheetArchive::open("./sample_0A.xlsx").expect("Unable to open file");
+/// let range = archive
+///     .excel()
+///     .expect("Invalid workbook")
+///     .worksheet_range("Year 1")
+///     .expect("Unable to read worksheet");
+///
+/// let styles = archive.styles().expect("Unable to parse styles");
+/// let worksheet = archive.worksheet("Year 1").expect("Unable to parse worksheet");
+/// println!("{:?} {:?} {:?}", range, styles, worksheet);
+/// ```
+pub struct SpreadsheetArchive {
+    /// The raw content of the XLSX archive.
+    data: Arc<[u8]>,
+    /// The opened zip archive.
+    archive: RefCell<ZipArchive<Cursor<Arc<[u8]>>>>,
+    /// The cached `Styles Part` of the workbook.
+    styles: OnceCell<Styles>,
+    /// The cached `Workbook Part` of the workbook.
+    workbook: OnceCell<Workbook>,
+    /// The cached relationship file of the workbook.
+    relationships: OnceCell<Relationships>,
+    /// The cached `Worksheet Part` of each worksheet by the worksheet name.
+    worksheets: RefCell<HashMap<String, Rc<Worksheet>>>,
+    /// The workbook opened with [`calamine`].
+    excel: OnceCell<RefCell<Xlsx<ArchiveReader>>>,
+}
+
+impl Debug for SpreadsheetArchive {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpreadsheetArchive")
+            .field("styles", &self.styles)
+            .field("workbook", &self.workbook)
+            .field("relationships", &self.relationships)
+            .field("worksheets", &self.worksheets)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SpreadsheetArchive {
+    /// Opens the XLSX archive at the path `file`.
+    pub fn open<P: AsRef<Path>>(file: P) -> Result<Self, ParseStyleError> {
+        let file = File::open(file).map_err(ParseStyleError::WorkbookError)?;
+        Self::from_reader(file)
+    }
+
+    /// Opens the XLSX archive from a `reader`.
+    ///
+    /// The whole content of the `reader` is read into memory.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, ParseStyleError> {
+        let mut data = vec![];
+        reader
+            .read_to_end(&mut data)
+            .map_err(ParseStyleError::WorkbookError)?;
+        Self::from_bytes(data)
+    }
+
+    /// Opens the XLSX archive from its raw content.
+    pub fn from_bytes<D: Into<Arc<[u8]>>>(data: D) -> Result<Self, ParseStyleError> {
+        let data = data.into();
+        let archive =
+            ZipArchive::new(Cursor::new(data.clone())).map_err(ParseStyleError::ArchiveError)?;
+
+        Ok(Self {
+            data,
+            archive: RefCell::new(archive),
+            styles: OnceCell::new(),
+            workbook: OnceCell::new(),
+            relationships: OnceCell::new(),
+            worksheets: RefCell::new(HashMap::new()),
+            excel: OnceCell::new(),
+        })
+    }
+
+    /// Gets a reader of the raw content of the XLSX archive.
+    ///
+    /// The reader does not copy the content of the archive.
+    pub fn reader(&self) -> ArchiveReader {
+        Cursor::new(self.data.clone())
+    }
+
+    /// Gets the workbook opened with [`calamine`].
+    ///
+    /// The workbook is opened on the first call and shared by the following
+    /// calls, so the worksheets of the archive are read from a single opened
+    /// workbook.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the workbook is already borrowed.
+    pub fn excel(&self) -> Result<RefMut<'_, Xlsx<ArchiveReader>>, XlsxError> {
+        if let Some(excel) = self.excel.get() {
+            return Ok(excel.borrow_mut());
+        }
+
+        let excel = open_workbook_from_rs(self.reader())?;
+        Ok(self.excel.get_or_init(|| RefCell::new(excel)).borrow_mut())
+    }
+
+    /// Gets data from a file in the XLSX archive.
+    ///
+    /// This function is the same as [`get_data`] but it does not reopen the
+    /// archive. The data is not cached.
+    pub fn get_data<T: for<'a> Deserialize<'a> + Debug>(
+        &self,
+        archive_file: &str,
+    ) -> Result<T, ParseStyleError> {
+        let mut archive = self.archive.borrow_mut();
+        let mut archive_file = archive
+            .by_name(archive_file)
+            .map_err(ParseStyleError::ArchiveError)?;
+        let mut file_content = String::new();
+        archive_file
+            .read_to_string(&mut file_content)
+            .map_err(ParseStyleError::ReadArchiveError)?;
+
+        let output: T = from_str(&file_content).map_err(ParseStyleError::DeserialiseError)?;
+        Ok(output)
+    }
+
+    /// Gets the cached value in `cell` or initialize it from `archive_file`.
+    fn get_cached<'a, T: for<'b> Deserialize<'b> + Debug>(
+        &self,
+        cell: &'a OnceCell<T>,
+        archive_file: &str,
+    ) -> Result<&'a T, ParseStyleError> {
+        if let Some(value) = cell.get() {
+            return Ok(value);
+        }
+
+        let value = self.get_data(archive_file)?;
+        Ok(cell.get_or_init(|| value))
+    }
+
+    /// Gets the `Styles Part` of the workbook.
+    ///
+    /// The [`Theme`] of the workbook is included to resolve theme colours.
+    pub fn styles(&self) -> Result<&Styles, ParseStyleError> {
+        if let Some(styles) = self.styles.get() {
+            return Ok(styles);
+        }
+
+        let mut styles: Styles = self.get_data("xl/styles.xml")?;
+        styles.theme = self.theme()?;
+        Ok(self.styles.get_or_init(|| styles))
+    }
+
+    /// Gets the `Theme Part` of the workbook.
+    ///
+    /// Returns [`None`] if the workbook has no theme.
+    pub fn theme(&self) -> Result<Option<Theme>, ParseStyleError> {
+        match self.get_data("xl/theme/theme1.xml") {
+            Ok(theme) => Ok(Some(theme)),
+            Err(ParseStyleError::ArchiveError(ZipError::FileNotFound)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Gets the `Workbook Part` of the workbook.
+    pub fn workbook(&self) -> Result<&Workbook, ParseStyleError> {
+        self.get_cached(&self.workbook, "xl/workbook.xml")
+    }
+
+    /// Gets the relationship file of the workbook.
+    pub fn relationships(&self) -> Result<&Relationships, ParseStyleError> {
+        self.get_cached(&self.relationships, "xl/_rels/workbook.xml.rels")
+    }
+
+    /// Gets the path of the worksheet with the display `name` in the archive.
+    pub fn worksheet_path(&self, name: &str) -> Result<String, ParseStyleError> {
+        let worksheet = self
+            .workbook()?
+            .sheets
+            .sheet
+            .iter()
+            .find(|x| x.name == name)
+            .ok_or(ParseStyleError::ArchiveError(ZipError::FileNotFound))?;
+        let sheet_file = &self
+            .relationships()?
+            .relationship
+            .iter()
+            .find(|x| x.id == worksheet.rid)
+            .ok_or(ParseStyleError::ArchiveError(ZipError::FileNotFound))?
+            .path;
+
+        Ok(if let Some(path) = sheet_file.strip_prefix("../") {
+            path.to_owned()
+        } else if let Some(path) = sheet_file.strip_prefix("/") {
+            path.to_owned()
+        } else {
+            format!("xl/{sheet_file}")
+        })
+    }
+
+    /// Gets the `Worksheet Part` of the worksheet with the display `name`.
+    pub fn worksheet(&self, name: &str) -> Result<Rc<Worksheet>, ParseStyleError> {
+        if let Some(worksheet) = self.worksheets.borrow().get(name) {
+            return Ok(worksheet.clone());
+        }
+
+        let worksheet: Rc<Worksheet> = Rc::new(self.get_data(&self.worksheet_path(name)?)?);
+        self.worksheets
+            .borrow_mut()
+            .insert(name.to_owned(), worksheet.clone());
+        Ok(worksheet)
+    }
+}
+
 /// Gets data from an XLSX file.
 ///
 /// This function will extract the XML data from a file in the given XLSX
@@ -378,7 +1663,34 @@ pub fn get_data<T: for<'a> Deserialize<'a> + Debug, P: AsRef<Path>>(
     archive_file: &str,
 ) -> Result<T, ParseStyleError> {
     let file = File::open(file).map_err(ParseStyleError::WorkbookError)?;
-    let mut archive = ZipArchive::new(file).map_err(ParseStyleError::ArchiveError)?;
+    get_data_reader(file, archive_file)
+}
+
+/// Gets data from an XLSX archive in a reader.
+///
+/// This function is the same as [`get_data`] but the XLSX archive is read from
+/// any `reader` that implements [`Read`] and [`Seek`], e.g. an in-memory
+/// buffer.
+///
+/// # Examples
+///
+/// This example extracts the styles in the XLSX archive from an in-memory
+/// buffer.
+///
+/// ```rust
+/// use std::io::Cursor;
+///
+/// use nott_a_database_core::spreadsheet_ml::{get_data_reader, Styles};
+///
+/// let data = std::fs::read("./sample_0A.xlsx").expect("Unable to read file");
+/// let styles: Styles = get_data_reader(Cursor::new(data), "xl/styles.xml").expect("Unable to find file");
+/// println!("{:?}", styles);
+/// ```
+pub fn get_data_reader<T: for<'a> Deserialize<'a> + Debug, R: Read + Seek>(
+    reader: R,
+    archive_file: &str,
+) -> Result<T, ParseStyleError> {
+    let mut archive = ZipArchive::new(reader).map_err(ParseStyleError::ArchiveError)?;
 
     let mut archive_file = archive
         .by_name(archive_file)
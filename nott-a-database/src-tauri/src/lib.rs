@@ -2,106 +2,337 @@
 /// TODO: Limit the amount of student per fetch.
 /// TODO: Use React Suspense to prevent blocking.
 /// TODO: Handle errors when calling invokes.
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    fs,
+    io::{Cursor, Seek},
+    path::PathBuf,
+    str::FromStr,
+};
 
-use serde::Deserialize;
+use serde::Serialize;
 use sqlx::SqlitePool;
-use tauri::{Manager, State};
+use tauri::{AppHandle, Manager, State};
 use tokio::sync::Mutex;
 
 use nott_a_database_core::{
-    database::{insert_student_info_async, insert_student_result_async},
-    AcademicYear, StudentInfo, StudentResult,
+    database::{insert_student_info_transaction_async, insert_student_result_transaction_async},
+    errors::{CreditConflict, DuplicateImport, FieldChange, IntakeConflict, RowDiagnostic},
+    palette::StatusPalette,
+    AcademicYear, ConflictPolicy, CreditPolicy, Error, ImportBatch, ParseOptions, ReportKind,
+    StudentInfo, StudentResult,
 };
 
-/// Enumerations of all the different possible data types.
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "kebab-case")]
-enum DataType {
-    /// The `Result (0A)` data type.
-    Result,
-    /// The `Award (0B)` data type.
-    Award,
-    /// The `May Resit (0C)` data type.
-    ResitMay,
-    /// The `August Resit (0D)` data type.
-    ResitAug,
-}
-
 macro_rules! wrap_error {
     ($result:expr, $db:ident, $db_pool:ident) => {
         match $result {
             Ok(v) => v,
             Err(e) => {
                 *$db = Some($db_pool);
-                return Err(e.to_string());
+                return Err(e.into());
             }
         }
     };
 }
 
+/// Summary of the data inserted from a report.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InsertSummary {
+    /// The ID of the import, used to roll back the import, or `None` if the
+    /// report was skipped as a duplicate.
+    batch: Option<i64>,
+    /// The type of report inserted.
+    kind: ReportKind,
+    /// The number of rows inserted.
+    rows: usize,
+    /// The invalid rows skipped when parsing leniently.
+    diagnostics: Vec<RowDiagnostic>,
+    /// The modules found with different credits from the stored credits.
+    credit_conflicts: Vec<CreditConflict>,
+    /// The fields of stored students changed by the report.
+    changes: Vec<FieldChange>,
+    /// The stored students with a different intake year from the intake year
+    /// inferred from the report.
+    intake_conflicts: Vec<IntakeConflict>,
+    /// The new students inserted with an unknown intake year.
+    unknown_intakes: Vec<i64>,
+    /// The previous import of a file with the same content.
+    duplicate: Option<DuplicateImport>,
+}
+
 // Inserts new data into the database.
+//
+// The import is recorded as an import batch, which can be rolled back. The
+// type of report is detected from the file if `data_type` is not given.
+// Invalid rows are skipped and reported if `lenient` is set. Modules with
+// different credits from the stored credits are resolved with `credit_policy`,
+// the stored credits are kept by default. The stored details of students are
+// updated with `conflict_policy`, overwriting them by default. A file with the
+// same content as a previous import is reported as a duplicate, and skipped
+// if `skip_duplicates` is set.
 #[tauri::command]
 async fn insert_data(
-    data_type: DataType,
+    data_type: Option<ReportKind>,
     academic_year: AcademicYear,
     path: PathBuf,
+    lenient: Option<bool>,
+    credit_policy: Option<CreditPolicy>,
+    conflict_policy: Option<ConflictPolicy>,
+    skip_duplicates: Option<bool>,
+    app: AppHandle,
+    db_pool: State<'_, Mutex<Option<SqlitePool>>>,
+    palette: State<'_, Mutex<StatusPalette>>,
+) -> Result<InsertSummary, Error> {
+    log::debug!(
+        "Rust Data\nType: {data_type:?}\nYear: {academic_year}\nPath: {path:?}\nLenient: {lenient:?}\nCredit Policy: {credit_policy:?}\nConflict Policy: {conflict_policy:?}\nSkip Duplicates: {skip_duplicates:?}"
+    );
+
+    let content = fs::read(&path)?;
+    let file_name = path
+        .file_name()
+        .map_or_else(|| path.to_string_lossy(), |name| name.to_string_lossy());
+    insert_report(
+        data_type,
+        academic_year,
+        file_name.into_owned(),
+        content,
+        parse_options(lenient.unwrap_or(false), &app, &palette).await?,
+        credit_policy.unwrap_or_default(),
+        conflict_policy.unwrap_or_default(),
+        skip_duplicates.unwrap_or(false),
+        &db_pool,
+    )
+    .await
+}
+
+// Inserts new data into the database from the raw content of a workbook, e.g.
+// an uploaded file.
+//
+// The import is recorded as an import batch of `file_name`, which can be
+// rolled back. The type of report is detected from the data if `data_type` is
+// not given.
+// Invalid rows are skipped and reported if `lenient` is set. Modules with
+// different credits from the stored credits are resolved with `credit_policy`,
+// the stored credits are kept by default. The stored details of students are
+// updated with `conflict_policy`, overwriting them by default. Data with the
+// same content as a previous import is reported as a duplicate, and skipped
+// if `skip_duplicates` is set.
+#[tauri::command]
+async fn insert_data_bytes(
+    data_type: Option<ReportKind>,
+    academic_year: AcademicYear,
+    data: Vec<u8>,
+    file_name: Option<String>,
+    lenient: Option<bool>,
+    credit_policy: Option<CreditPolicy>,
+    conflict_policy: Option<ConflictPolicy>,
+    skip_duplicates: Option<bool>,
+    app: AppHandle,
     db_pool: State<'_, Mutex<Option<SqlitePool>>>,
-) -> Result<(), String> {
-    log::debug!("Rust Data\nType: {data_type:?}\nYear: {academic_year}\nPath: {path:?}");
+    palette: State<'_, Mutex<StatusPalette>>,
+) -> Result<InsertSummary, Error> {
+    log::debug!(
+        "Rust Data\nType: {data_type:?}\nYear: {academic_year}\nFile: {file_name:?}\nSize: {}\nLenient: {lenient:?}\nCredit Policy: {credit_policy:?}\nConflict Policy: {conflict_policy:?}\nSkip Duplicates: {skip_duplicates:?}",
+        data.len()
+    );
+
+    insert_report(
+        data_type,
+        academic_year,
+        file_name.unwrap_or_else(|| String::from("upload")),
+        data,
+        parse_options(lenient.unwrap_or(false), &app, &palette).await?,
+        credit_policy.unwrap_or_default(),
+        conflict_policy.unwrap_or_default(),
+        skip_duplicates.unwrap_or(false),
+        &db_pool,
+    )
+    .await
+}
+
+/// Gets the options used to parse reports with the status palette, the
+/// default resit palette and the user report layouts.
+async fn parse_options(
+    lenient: bool,
+    app: &AppHandle,
+    palette: &Mutex<StatusPalette>,
+) -> Result<ParseOptions, Error> {
+    Ok(ParseOptions {
+        lenient,
+        palette: palette.lock().await.clone(),
+        resit_palette: StatusPalette::resit(),
+        layouts: settings::load_layouts(app)?,
+    })
+}
+
+/// Parses a report from the content of `file_name` and inserts it into the
+/// database as a new import batch.
+async fn insert_report(
+    data_type: Option<ReportKind>,
+    academic_year: AcademicYear,
+    file_name: String,
+    content: Vec<u8>,
+    options: ParseOptions,
+    credit_policy: CreditPolicy,
+    conflict_policy: ConflictPolicy,
+    skip_duplicates: bool,
+    db_pool: &Mutex<Option<SqlitePool>>,
+) -> Result<InsertSummary, Error> {
+    let mut reader = Cursor::new(content.as_slice());
+    let data_type = match data_type {
+        Some(data_type) => data_type,
+        None => {
+            let detection = ReportKind::detect_reader_with_layouts(&mut reader, &options.layouts)?;
+            reader.rewind()?;
+            log::info!(
+                "Detected {} ({} confidence): {}",
+                detection.kind,
+                detection.confidence,
+                detection.explanation
+            );
+            detection.kind
+        }
+    };
+    let mut batch = ImportBatch::new(file_name, &content, data_type, academic_year.clone());
 
     let mut db = db_pool.lock().await;
     let mut db_pool = db.take().expect("There should be an unlocked database");
 
+    // Checking Duplicate Imports
+    let duplicate = wrap_error!(batch.find_duplicate_async(&mut db_pool).await, db, db_pool);
+    if let Some(duplicate) = &duplicate {
+        log::warn!("{}: {duplicate}", batch.file_name);
+        if skip_duplicates {
+            *db = Some(db_pool);
+            return Ok(InsertSummary {
+                batch: None,
+                kind: data_type,
+                rows: 0,
+                diagnostics: vec![],
+                credit_conflicts: vec![],
+                changes: vec![],
+                intake_conflicts: vec![],
+                unknown_intakes: vec![],
+                duplicate: Some(duplicate.clone()),
+            });
+        }
+    }
+
     // Inserting Academic Year
     wrap_error!(
         academic_year.insert_db_async(&mut db_pool).await,
         db,
         db_pool
     );
+    let mut trans = wrap_error!(db_pool.begin().await, db, db_pool);
 
     // Inserting Data
-    match data_type {
-        DataType::Result => {
-            let data = wrap_error!(StudentResult::from_result(path), db, db_pool);
-            wrap_error!(
-                insert_student_result_async(&mut db_pool, &data, &academic_year).await,
+    let (batch_id, diagnostics, report) = match data_type {
+        ReportKind::Result => {
+            let (data, diagnostics) = wrap_error!(
+                StudentResult::from_result_reader_with_options(reader, &options),
                 db,
                 db_pool
             );
-        }
-        DataType::Award => {
-            let data = wrap_error!(StudentInfo::from_award(path), db, db_pool);
-            wrap_error!(
-                insert_student_info_async(&mut db_pool, &data, &academic_year, true).await,
+            batch.rows = data.len();
+            batch.skipped = diagnostics.len();
+            let batch_id = wrap_error!(
+                batch.insert_db_transaction_async(&mut trans).await,
+                db,
+                db_pool
+            );
+            let report = wrap_error!(
+                insert_student_result_transaction_async(
+                    &mut trans,
+                    &data,
+                    &academic_year,
+                    credit_policy,
+                    conflict_policy,
+                    batch_id
+                )
+                .await,
                 db,
                 db_pool
             );
+            (batch_id, diagnostics, report)
         }
-        DataType::ResitMay => {
-            let data = wrap_error!(StudentResult::from_resit_may(path), db, db_pool);
-            wrap_error!(
-                insert_student_result_async(&mut db_pool, &data, &academic_year).await,
+        ReportKind::Award => {
+            let (data, diagnostics) = wrap_error!(
+                StudentInfo::from_award_reader_with_options(reader, &options),
+                db,
+                db_pool
+            );
+            batch.rows = data.len();
+            batch.skipped = diagnostics.len();
+            let batch_id = wrap_error!(
+                batch.insert_db_transaction_async(&mut trans).await,
+                db,
+                db_pool
+            );
+            let report = wrap_error!(
+                insert_student_info_transaction_async(
+                    &mut trans,
+                    &data,
+                    &academic_year,
+                    true,
+                    conflict_policy,
+                    batch_id
+                )
+                .await,
                 db,
                 db_pool
             );
+            (batch_id, diagnostics, report)
         }
-        DataType::ResitAug => {
-            let data = wrap_error!(StudentResult::from_resit_aug(path), db, db_pool);
-            wrap_error!(
-                insert_student_result_async(&mut db_pool, &data, &academic_year).await,
+        ReportKind::Resit(session) => {
+            let (data, diagnostics) = wrap_error!(
+                StudentResult::from_resit_reader_with_options(reader, session, &options),
                 db,
                 db_pool
             );
+            batch.rows = data.len();
+            batch.skipped = diagnostics.len();
+            let batch_id = wrap_error!(
+                batch.insert_db_transaction_async(&mut trans).await,
+                db,
+                db_pool
+            );
+            let report = wrap_error!(
+                insert_student_result_transaction_async(
+                    &mut trans,
+                    &data,
+                    &academic_year,
+                    credit_policy,
+                    conflict_policy,
+                    batch_id
+                )
+                .await,
+                db,
+                db_pool
+            );
+            (batch_id, diagnostics, report)
         }
     };
 
+    wrap_error!(trans.commit().await, db, db_pool);
+
     *db = Some(db_pool);
-    Ok(())
+    Ok(InsertSummary {
+        batch: Some(batch_id),
+        kind: data_type,
+        rows: batch.rows,
+        diagnostics,
+        credit_conflicts: report.credit_conflicts,
+        changes: report.changes,
+        intake_conflicts: report.intake_conflicts,
+        unknown_intakes: report.unknown_intakes,
+        duplicate,
+    })
 }
 
 /// Commands, types and utilities for interacting with module data.
 mod modules {
+    use nott_a_database_core::Error;
     use serde::{Deserialize, Serialize};
     use sqlx::{prelude::FromRow, SqlitePool};
     use tauri::State;
@@ -124,7 +355,7 @@ mod modules {
     pub async fn update_module(
         module: Module,
         db_pool: State<'_, Mutex<Option<SqlitePool>>>,
-    ) -> Result<Module, String> {
+    ) -> Result<Module, Error> {
         let mut db = db_pool.lock().await;
         let db_pool = db.take().expect("There should be an unlocked database");
 
@@ -134,7 +365,7 @@ mod modules {
             .bind(&module.name)
             .execute(&db_pool)
             .await
-            .map_err(|e| e.to_string());
+            .map_err(Error::from);
 
         *db = Some(db_pool);
         match data {
@@ -150,14 +381,14 @@ mod modules {
     #[tauri::command]
     pub async fn get_modules(
         db_pool: State<'_, Mutex<Option<SqlitePool>>>,
-    ) -> Result<Vec<Module>, String> {
+    ) -> Result<Vec<Module>, Error> {
         let mut db = db_pool.lock().await;
         let db_pool = db.take().expect("There should be an unlocked database");
 
         let data = sqlx::query_as("SELECT * from Module")
             .fetch_all(&db_pool)
             .await
-            .map_err(|e| e.to_string());
+            .map_err(Error::from);
 
         *db = Some(db_pool);
         match data {
@@ -170,7 +401,112 @@ mod modules {
     }
 }
 
+/// Commands and types for listing and rolling back import batches.
+mod imports {
+    use nott_a_database_core::{
+        database::{repair_intake_years_async, rollback_import_async},
+        errors::IntakeConflict,
+        Error,
+    };
+    use serde::Serialize;
+    use sqlx::{prelude::FromRow, SqlitePool};
+    use tauri::State;
+    use tokio::sync::Mutex;
+
+    /// Wrapper type containing all the columns of the `ImportBatch` table.
+    #[derive(Debug, Serialize, FromRow)]
+    #[serde(rename_all = "camelCase")]
+    #[sqlx(rename_all = "PascalCase")]
+    pub struct ImportBatch {
+        /// The ID of the import batch.
+        #[sqlx(rename = "ID")]
+        id: i64,
+        /// The name of the imported file.
+        file_name: String,
+        /// The SHA-256 hash of the imported file.
+        sha256: String,
+        /// The type of report imported.
+        kind: String,
+        /// The session of the imported resit report.
+        session: Option<String>,
+        /// The academic year of the imported report.
+        academic_year: String,
+        /// The time of the import.
+        imported_at: String,
+        /// The number of rows imported.
+        rows: i64,
+        /// The number of invalid rows skipped.
+        skipped: i64,
+        /// The time the import was rolled back, if it was rolled back.
+        rolled_back_at: Option<String>,
+    }
+
+    /// Fetches all the import batches, the latest first.
+    #[tauri::command]
+    pub async fn get_import_batches(
+        db_pool: State<'_, Mutex<Option<SqlitePool>>>,
+    ) -> Result<Vec<ImportBatch>, Error> {
+        let mut db = db_pool.lock().await;
+        let db_pool = db.take().expect("There should be an unlocked database");
+
+        let data = sqlx::query_as("SELECT * FROM ImportBatch ORDER BY ID DESC")
+            .fetch_all(&db_pool)
+            .await
+            .map_err(Error::from);
+
+        *db = Some(db_pool);
+        match data {
+            Ok(data) => Ok(data),
+            Err(e) => {
+                log::error!("Error fetching import batches: {e}");
+                Err(e)
+            }
+        }
+    }
+
+    /// Rolls back the import batch `id`, restoring the rows updated by the
+    /// import and deleting the rows inserted by the import.
+    #[tauri::command]
+    pub async fn rollback_import(
+        id: i64,
+        db_pool: State<'_, Mutex<Option<SqlitePool>>>,
+    ) -> Result<(), Error> {
+        let mut db = db_pool.lock().await;
+        let mut db_pool = db.take().expect("There should be an unlocked database");
+
+        let result = rollback_import_async(&mut db_pool, id).await;
+
+        *db = Some(db_pool);
+        if let Err(e) = &result {
+            log::error!("Error rolling back import {id}: {e}");
+        }
+        result
+    }
+
+    /// Recomputes the intake years of the students from the intake in the
+    /// award report (0B) or the year of study of their results.
+    ///
+    /// Returns the students whose intake year was updated.
+    #[tauri::command]
+    pub async fn repair_intake_years(
+        db_pool: State<'_, Mutex<Option<SqlitePool>>>,
+    ) -> Result<Vec<IntakeConflict>, Error> {
+        let mut db = db_pool.lock().await;
+        let mut db_pool = db.take().expect("There should be an unlocked database");
+
+        let result = repair_intake_years_async(&mut db_pool).await;
+
+        *db = Some(db_pool);
+        match &result {
+            Ok(repaired) => log::info!("Repaired {} intake years", repaired.len()),
+            Err(e) => log::error!("Error repairing intake years: {e}"),
+        }
+        result
+    }
+}
+
 mod students {
+    use nott_a_database_core::Error;
     use serde::Serialize;
     use sqlx::{prelude::FromRow, SqlitePool};
     use tauri::State;
@@ -204,7 +540,7 @@ mod students {
         selected: Option<u64>,
         exception_data: Option<String>,
         recommendation: Option<String>,
-        intake_year: String,
+        intake_year: Option<String>,
         graduation_year: Option<String>,
     }
 
@@ -212,14 +548,14 @@ mod students {
     #[tauri::command]
     pub async fn get_student_info(
         db_pool: State<'_, Mutex<Option<SqlitePool>>>,
-    ) -> Result<Vec<StudentInfo>, String> {
+    ) -> Result<Vec<StudentInfo>, Error> {
         let mut db = db_pool.lock().await;
         let db_pool = db.take().expect("There should be an unlocked database");
 
         let data = sqlx::query_as("SELECT * from StudentInfo")
             .fetch_all(&db_pool)
             .await
-            .map_err(|e| e.to_string());
+            .map_err(Error::from);
 
         *db = Some(db_pool);
 
@@ -237,7 +573,7 @@ mod students {
     pub async fn get_student(
         id: i64,
         db_pool: State<'_, Mutex<Option<SqlitePool>>>,
-    ) -> Result<StudentInfo, String> {
+    ) -> Result<StudentInfo, Error> {
         let mut db = db_pool.lock().await;
         let db_pool = db.take().expect("There should be an unlocked database");
 
@@ -245,7 +581,7 @@ mod students {
             .bind(id)
             .fetch_one(&db_pool)
             .await
-            .map_err(|e| e.to_string());
+            .map_err(Error::from);
 
         *db = Some(db_pool);
 
@@ -269,6 +605,8 @@ mod students {
         year_of_study: u64,
         autumn_credits: Option<u64>,
         autumn_mean: Option<f64>,
+        summer_credits: Option<u64>,
+        summer_mean: Option<f64>,
         spring_credits: Option<u64>,
         spring_mean: Option<f64>,
         year_credits: Option<u64>,
@@ -282,7 +620,7 @@ mod students {
     pub async fn get_results(
         id: i64,
         db_pool: State<'_, Mutex<Option<SqlitePool>>>,
-    ) -> Result<Vec<StudentResult>, String> {
+    ) -> Result<Vec<StudentResult>, Error> {
         let mut db = db_pool.lock().await;
         let db_pool = db.take().expect("There should be an unlocked database");
 
@@ -290,7 +628,7 @@ mod students {
             .bind(id)
             .fetch_all(&db_pool)
             .await
-            .map_err(|e| e.to_string());
+            .map_err(Error::from);
 
         *db = Some(db_pool);
 
@@ -303,6 +641,54 @@ mod students {
         }
     }
 
+    /// Wrapper type for a row of data in the ResultAttempt table.
+    #[derive(Debug, Serialize, FromRow)]
+    #[sqlx(rename_all = "PascalCase")]
+    #[serde(rename_all = "camelCase")]
+    pub struct ResultAttempt {
+        academic_year: String,
+        #[sqlx(rename = "ID")]
+        id: u64,
+        session: String,
+        attempt: u64,
+        year_credits: Option<u64>,
+        year_mean: Option<f64>,
+        #[sqlx(rename = "CreditsL3Lt30")]
+        credits_l3_lt30: Option<f64>,
+        #[sqlx(rename = "CreditsL33039")]
+        credits_l3_30_39: Option<f64>,
+    }
+
+    /// Fetches the student's year figures after every attempt of each
+    /// session, e.g. before and after the resits.
+    #[tauri::command]
+    pub async fn get_result_attempts(
+        id: i64,
+        db_pool: State<'_, Mutex<Option<SqlitePool>>>,
+    ) -> Result<Vec<ResultAttempt>, Error> {
+        let mut db = db_pool.lock().await;
+        let db_pool = db.take().expect("There should be an unlocked database");
+
+        let data = sqlx::query_as(
+            "SELECT * from ResultAttempt WHERE ID=?1
+             ORDER BY AcademicYear, Session, Attempt",
+        )
+        .bind(id)
+        .fetch_all(&db_pool)
+        .await
+        .map_err(Error::from);
+
+        *db = Some(db_pool);
+
+        match data {
+            Ok(data) => Ok(data),
+            Err(e) => {
+                log::error!("Error fecthing result attempts for {id}: {e}");
+                Err(e)
+            }
+        }
+    }
+
     /// Wrapper type for a row of data in the Mark table.
     #[derive(Debug, Serialize, FromRow)]
     #[sqlx(rename_all = "PascalCase")]
@@ -310,32 +696,125 @@ mod students {
     pub struct Mark {
         #[sqlx(rename = "ID")]
         id: u64,
-        mark: f64,
+        academic_year: String,
+        mark: Option<f64>,
+        mark_code: Option<String>,
         fill: Option<u64>,
-        retake1: Option<f64>,
-        retake2: Option<f64>,
         extra: Option<String>,
         module: String,
         status: String,
+        #[sqlx(skip)]
+        attempts: Vec<MarkAttempt>,
+        #[sqlx(skip)]
+        failed_components: Vec<FailedComponent>,
+    }
+
+    /// Wrapper type for a row of data in the MarkAttempt table.
+    #[derive(Debug, Serialize, FromRow)]
+    #[sqlx(rename_all = "PascalCase")]
+    #[serde(rename_all = "camelCase")]
+    pub struct MarkAttempt {
+        #[sqlx(rename = "ID")]
+        id: u64,
+        academic_year: String,
+        module: String,
+        attempt: u64,
+        session: String,
+        mark: Option<f64>,
+        mark_code: Option<String>,
+        capped: bool,
+        status: Option<String>,
+    }
+
+    /// Wrapper type for a row of data in the FailedComponent view.
+    #[derive(Debug, Serialize, FromRow)]
+    #[sqlx(rename_all = "PascalCase")]
+    #[serde(rename_all = "camelCase")]
+    pub struct FailedComponent {
+        #[sqlx(rename = "ID")]
+        id: u64,
+        academic_year: String,
+        module: String,
+        attempt: u64,
+        component: String,
+        weight: f64,
+        mark: Option<f64>,
+        mark_code: Option<String>,
     }
 
-    /// Fetches all the student's module marks in the database.
+    /// The student's module marks of an academic year.
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct YearMarks {
+        academic_year: String,
+        marks: Vec<Mark>,
+    }
+
+    /// Fetches all the student's module marks in the database grouped by the
+    /// academic year, with the failed components explaining a component fail
+    /// (CF).
     #[tauri::command]
     pub async fn get_marks(
         id: i64,
         db_pool: State<'_, Mutex<Option<SqlitePool>>>,
-    ) -> Result<Vec<Mark>, String> {
+    ) -> Result<Vec<YearMarks>, Error> {
         let mut db = db_pool.lock().await;
         let db_pool = db.take().expect("There should be an unlocked database");
 
-        let data = sqlx::query_as("SELECT * from Mark WHERE ID=?1")
+        let data = sqlx::query_as("SELECT * from Mark WHERE ID=?1 ORDER BY AcademicYear, Module")
             .bind(id)
             .fetch_all(&db_pool)
             .await
-            .map_err(|e| e.to_string());
+            .map_err(Error::from);
+        let attempts = sqlx::query_as::<_, MarkAttempt>(
+            "SELECT * from MarkAttempt WHERE ID=?1 ORDER BY AcademicYear, Module, Attempt",
+        )
+        .bind(id)
+        .fetch_all(&db_pool)
+        .await
+        .map_err(Error::from);
+        let components = sqlx::query_as::<_, FailedComponent>(
+            "SELECT * from FailedComponent WHERE ID=?1
+             ORDER BY AcademicYear, Module, Attempt, Component",
+        )
+        .bind(id)
+        .fetch_all(&db_pool)
+        .await
+        .map_err(Error::from);
 
         *db = Some(db_pool);
 
+        // Grouping the attempts and the failed components by the module, and
+        // the modules by the academic year
+        let data = data.and_then(|mut marks: Vec<Mark>| {
+            for attempt in attempts? {
+                if let Some(mark) = marks.iter_mut().find(|m| {
+                    m.academic_year == attempt.academic_year && m.module == attempt.module
+                }) {
+                    mark.attempts.push(attempt);
+                }
+            }
+            for component in components? {
+                if let Some(mark) = marks.iter_mut().find(|m| {
+                    m.academic_year == component.academic_year && m.module == component.module
+                }) {
+                    mark.failed_components.push(component);
+                }
+            }
+
+            let mut years: Vec<YearMarks> = vec![];
+            for mark in marks {
+                match years.last_mut() {
+                    Some(year) if year.academic_year == mark.academic_year => year.marks.push(mark),
+                    _ => years.push(YearMarks {
+                        academic_year: mark.academic_year.clone(),
+                        marks: vec![mark],
+                    }),
+                }
+            }
+            Ok(years)
+        });
+
         match data {
             Ok(data) => Ok(data),
             Err(e) => {
@@ -347,12 +826,117 @@ mod students {
 }
 
 mod settings {
-    use std::borrow::Cow;
+    use std::{borrow::Cow, path::PathBuf};
 
+    use nott_a_database_core::{layout::ReportLayouts, palette::StatusPalette, Error};
     use sqlx::{migrate, sqlite::SqliteConnectOptions, SqlitePool};
     use tauri::{AppHandle, Manager, State};
     use tokio::sync::Mutex;
 
+    /// Gets the path of the status palette file in the app data directory.
+    fn palette_path(app: &AppHandle) -> PathBuf {
+        let mut path = app.path().app_data_dir().expect("Unsupported OS detected.");
+        path.push("palette.toml");
+        path
+    }
+
+    /// Loads the saved status palette, the default palette is used if there
+    /// is no saved palette.
+    pub fn load_palette(app: &AppHandle) -> StatusPalette {
+        let path = palette_path(app);
+        if !path.exists() {
+            return StatusPalette::default();
+        }
+
+        StatusPalette::open(&path).unwrap_or_else(|e| {
+            log::error!("Error loading status palette {path:?}: {e}");
+            StatusPalette::default()
+        })
+    }
+
+    /// Gets the directory of the user report layouts in the app data
+    /// directory.
+    fn layouts_dir(app: &AppHandle) -> PathBuf {
+        let mut path = app.path().app_data_dir().expect("Unsupported OS detected.");
+        path.push("layouts");
+        path
+    }
+
+    /// Loads the report layouts, every TOML file in the layouts directory
+    /// replaces the built-in layout of the same type of report.
+    pub fn load_layouts(app: &AppHandle) -> Result<ReportLayouts, Error> {
+        let mut layouts = ReportLayouts::default();
+        let dir = layouts_dir(app);
+        if !dir.exists() {
+            return Ok(layouts);
+        }
+
+        let mut files: Vec<_> = std::fs::read_dir(&dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<_, _>>()?;
+        files.sort();
+        for file in files
+            .iter()
+            .filter(|f| f.extension().is_some_and(|e| e == "toml"))
+        {
+            let kind = layouts.load(file)?;
+            log::info!("Using layout {file:?} for {kind}");
+        }
+
+        Ok(layouts)
+    }
+
+    /// Fetches the directory of the user report layouts.
+    #[tauri::command]
+    pub fn get_layouts_dir(app: AppHandle) -> String {
+        layouts_dir(&app).to_string_lossy().into_owned()
+    }
+
+    /// Fetches the status palette used to get the module status as TOML.
+    #[tauri::command]
+    pub async fn get_status_palette(
+        palette: State<'_, Mutex<StatusPalette>>,
+    ) -> Result<String, ()> {
+        Ok(palette.lock().await.to_toml())
+    }
+
+    /// Updates and saves the status palette from a TOML string.
+    #[tauri::command]
+    pub async fn set_status_palette(
+        toml: String,
+        app: AppHandle,
+        palette: State<'_, Mutex<StatusPalette>>,
+    ) -> Result<String, Error> {
+        let new_palette = StatusPalette::from_toml(&toml)?;
+
+        let path = palette_path(&app);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, new_palette.to_toml())?;
+
+        let output = new_palette.to_toml();
+        *palette.lock().await = new_palette;
+        Ok(output)
+    }
+
+    /// Resets the status palette to the default palette.
+    #[tauri::command]
+    pub async fn reset_status_palette(
+        app: AppHandle,
+        palette: State<'_, Mutex<StatusPalette>>,
+    ) -> Result<String, Error> {
+        let path = palette_path(&app);
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+
+        let new_palette = StatusPalette::default();
+        let output = new_palette.to_toml();
+        *palette.lock().await = new_palette;
+        Ok(output)
+    }
+
     #[tauri::command]
     pub async fn change_password(
         password: String,
@@ -447,21 +1031,31 @@ pub fn run() {
         .setup(|app| {
             run_async_command(async move {
                 app.manage(Mutex::<Option<SqlitePool>>::new(None));
+                app.manage(Mutex::new(settings::load_palette(app.handle())));
 
                 Ok(())
             })
         })
         .invoke_handler(tauri::generate_handler![
             insert_data,
+            insert_data_bytes,
+            imports::get_import_batches,
+            imports::rollback_import,
+            imports::repair_intake_years,
             modules::get_modules,
             modules::update_module,
             students::get_student_info,
             students::get_student,
             students::get_results,
+            students::get_result_attempts,
             students::get_marks,
             settings::change_password,
             settings::decrypt_db,
             settings::check_decryption,
+            settings::get_status_palette,
+            settings::set_status_palette,
+            settings::reset_status_palette,
+            settings::get_layouts_dir,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -23,11 +23,18 @@ Input.displayName = "Input";
 
 type FileInputProps = React.ComponentProps<"button"> & {
   accept?: string;
-  clickFn: (fileName: string | null) => void;
+  multiple?: boolean;
+  clickFn: (fileNames: string[]) => void;
 };
 
-function FileInput({ className, accept, clickFn, ...props }: FileInputProps) {
-  const [file, setFile] = React.useState<string | null>(null);
+function FileInput({
+  className,
+  accept,
+  multiple,
+  clickFn,
+  ...props
+}: FileInputProps) {
+  const [files, setFiles] = React.useState<string[]>([]);
 
   const filters: DialogFilter[] = [];
   if (accept !== undefined) {
@@ -51,18 +58,26 @@ function FileInput({ className, accept, clickFn, ...props }: FileInputProps) {
         className,
       )}
       onClick={async () => {
-        const file = await open({
-          multiple: false,
+        const selected = await open({
+          multiple: multiple ?? false,
           directory: false,
           filters: filters,
         });
-        setFile(file);
-        clickFn(file);
+        let files: string[] = [];
+        if (Array.isArray(selected)) {
+          files = selected;
+        } else if (selected !== null) {
+          files = [selected];
+        }
+        setFiles(files);
+        clickFn(files);
       }}
       {...props}
     >
-      <span>Choose File</span>
-      <span className="truncate">{file ?? "no file selected"}</span>
+      <span>{multiple ? "Choose Files" : "Choose File"}</span>
+      <span className="truncate">
+        {files.length > 0 ? files.join(", ") : "no file selected"}
+      </span>
     </Button>
   );
 }
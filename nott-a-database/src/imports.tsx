@@ -0,0 +1,242 @@
+import { Suspense, useEffect, useState } from "react";
+
+import { Undo2, Wrench } from "lucide-react";
+
+import { invoke } from "@tauri-apps/api/core";
+import * as log from "@tauri-apps/plugin-log";
+
+import { Button } from "@/components/ui/button";
+import {
+  Dialog,
+  DialogFooter,
+  DialogHeader,
+  DialogContent,
+  DialogDescription,
+  DialogTitle,
+  DialogTrigger,
+  DialogClose,
+} from "@/components/ui/dialog";
+import {
+  Table,
+  TableBody,
+  TableCell,
+  TableHead,
+  TableHeader,
+  TableRow,
+} from "@/components/ui/table";
+import { useToast } from "./hooks/use-toast";
+import { errorMessage } from "@/lib/utils";
+
+type ImportBatch = {
+  id: number;
+  fileName: string;
+  sha256: string;
+  kind: string;
+  session?: string;
+  academicYear: string;
+  importedAt: string;
+  rows: number;
+  skipped: number;
+  rolledBackAt?: string;
+};
+
+function kindName(batch: ImportBatch) {
+  return batch.session ? `${batch.kind} (${batch.session})` : batch.kind;
+}
+
+function RollbackImport({
+  batch,
+  update,
+}: {
+  batch: ImportBatch;
+  update: () => void;
+}) {
+  const { toast } = useToast();
+
+  async function rollbackImport() {
+    log.info(`Rolling back import ${batch.id}`);
+
+    try {
+      await invoke("rollback_import", { id: batch.id });
+      log.info(`Successfully rolled back import ${batch.id}`);
+      toast({
+        title: "Success",
+        description: `Successfully rolled back the import of ${batch.fileName}`,
+      });
+      update();
+    } catch (e) {
+      toast({
+        title: "Error",
+        description: errorMessage(e),
+        variant: "destructive",
+      });
+      log.error(`Error rolling back import ${batch.id}: ${errorMessage(e)}`);
+    }
+  }
+
+  return (
+    <Dialog>
+      <DialogTrigger asChild>
+        <Button size="icon" variant="ghost" disabled={!!batch.rolledBackAt}>
+          <Undo2 />
+        </Button>
+      </DialogTrigger>
+      <DialogContent>
+        <DialogHeader>
+          <DialogTitle>{"Rolling Back Import"}</DialogTitle>
+          <DialogDescription>
+            {`Undoing the import of ${batch.fileName} (${kindName(batch)}, ${batch.academicYear}). The rows inserted by the import are deleted and the rows updated by the import are restored.`}
+          </DialogDescription>
+        </DialogHeader>
+        <DialogFooter>
+          <DialogClose asChild>
+            <Button variant="destructive" onClick={rollbackImport}>
+              Roll Back
+            </Button>
+          </DialogClose>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
+
+function RepairIntakeYears() {
+  const { toast } = useToast();
+
+  async function repairIntakeYears() {
+    log.info("Repairing intake years");
+
+    try {
+      const repaired = (await invoke("repair_intake_years")) as unknown[];
+      log.info(`Successfully repaired ${repaired.length} intake years`);
+      log.debug(`Repaired Intake Years: ${JSON.stringify(repaired)}`);
+      toast({
+        title: "Success",
+        description: `Updated the intake year of ${repaired.length} students`,
+      });
+    } catch (e) {
+      toast({
+        title: "Error",
+        description: errorMessage(e),
+        variant: "destructive",
+      });
+      log.error(`Error repairing intake years: ${errorMessage(e)}`);
+    }
+  }
+
+  return (
+    <Dialog>
+      <DialogTrigger asChild>
+        <Button variant="outline">
+          <Wrench />
+          Repair Intake Years
+        </Button>
+      </DialogTrigger>
+      <DialogContent>
+        <DialogHeader>
+          <DialogTitle>{"Repairing Intake Years"}</DialogTitle>
+          <DialogDescription>
+            {
+              "Recomputing the intake year of every student from the intake in the award reports (0B), or from the year of study of their results."
+            }
+          </DialogDescription>
+        </DialogHeader>
+        <DialogFooter>
+          <DialogClose asChild>
+            <Button onClick={repairIntakeYears}>Repair</Button>
+          </DialogClose>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
+
+function ImportRows() {
+  const [batches, setBatches] = useState<ImportBatch[]>([]);
+
+  async function fetchBatches() {
+    log.info("Fetching import batches");
+    try {
+      const batches = (await invoke("get_import_batches")) as ImportBatch[];
+      log.info("Done fetching import batches");
+      log.debug(`Import Batches: ${JSON.stringify(batches)}`);
+      setBatches(batches);
+    } catch (e) {
+      log.error(`Error fetching import batches: ${errorMessage(e)}`);
+    }
+  }
+
+  useEffect(() => {
+    fetchBatches();
+  }, []);
+
+  if (batches.length === 0) {
+    return (
+      <TableRow>
+        <TableCell colSpan={8} className="text-muted-foreground">
+          {"No imports found. Please upload data in the Upload page."}
+        </TableCell>
+      </TableRow>
+    );
+  }
+
+  return batches.map((batch) => (
+    <TableRow key={batch.id}>
+      <TableCell>{batch.id}</TableCell>
+      <TableCell>{batch.fileName}</TableCell>
+      <TableCell>{kindName(batch)}</TableCell>
+      <TableCell>{batch.academicYear}</TableCell>
+      <TableCell>{batch.importedAt}</TableCell>
+      <TableCell>{`${batch.rows} (${batch.skipped} skipped)`}</TableCell>
+      <TableCell>{batch.rolledBackAt ?? ""}</TableCell>
+      <TableCell className="max-w-1">
+        <RollbackImport batch={batch} update={fetchBatches} />
+      </TableCell>
+    </TableRow>
+  ));
+}
+
+export default function ImportsPage() {
+  return (
+    <>
+      <div className="mb-4 flex justify-end">
+        <RepairIntakeYears />
+      </div>
+      <div className="rounded-md border">
+        <Table className="w-full">
+          <TableHeader>
+            <TableRow>
+              <TableHead className="sticky top-0 bg-[hsl(var(--background))]">
+                ID
+              </TableHead>
+              <TableHead className="sticky top-0 bg-[hsl(var(--background))]">
+                File
+              </TableHead>
+              <TableHead className="sticky top-0 bg-[hsl(var(--background))]">
+                Report
+              </TableHead>
+              <TableHead className="sticky top-0 bg-[hsl(var(--background))]">
+                Academic Year
+              </TableHead>
+              <TableHead className="sticky top-0 bg-[hsl(var(--background))]">
+                Imported At
+              </TableHead>
+              <TableHead className="sticky top-0 bg-[hsl(var(--background))]">
+                Rows
+              </TableHead>
+              <TableHead className="sticky top-0 bg-[hsl(var(--background))]">
+                Rolled Back At
+              </TableHead>
+              <TableHead className="sticky top-0 max-w-1 bg-[hsl(var(--background))]"></TableHead>
+            </TableRow>
+          </TableHeader>
+          <TableBody>
+            <Suspense fallback={<div>Loading</div>}>
+              <ImportRows />
+            </Suspense>
+          </TableBody>
+        </Table>
+      </div>
+    </>
+  );
+}
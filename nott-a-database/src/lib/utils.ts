@@ -4,3 +4,20 @@ import { twMerge } from "tailwind-merge";
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs));
 }
+
+/** An error returned by a Tauri command. */
+export interface CommandError {
+  code: string;
+  category: string;
+  message: string;
+  cell: object | null;
+}
+
+/** Formats an error thrown by `invoke` as a message with its error code. */
+export function errorMessage(error: unknown): string {
+  if (typeof error === "object" && error !== null && "code" in error) {
+    const { code, message } = error as CommandError;
+    return `[${code}] ${message}`;
+  }
+  return `${error}`;
+}
@@ -9,6 +9,7 @@ import Home from "./home";
 import UploadPage from "./upload";
 import StudentsPage from "./students";
 import ModulesPage from "./modules";
+import ImportsPage from "./imports";
 import StudentInfo from "./student_info";
 import Settings from "./settings";
 import { Toaster } from "@/components/ui/toaster";
@@ -58,6 +59,11 @@ function Main() {
                 Modules
               </Link>
             </li>
+            <li>
+              <Link to="/imports" className="hover:text-gray-300">
+                Imports
+              </Link>
+            </li>
             <li>
               <Link to="/settings" className="hover:text-gray-300">
                 Settings
@@ -74,6 +80,7 @@ function Main() {
           <Route path="students" element={<StudentsPage />} />
           <Route path="student" element={<StudentInfo />} />
           <Route path="modules" element={<ModulesPage />} />
+          <Route path="imports" element={<ImportsPage />} />
           <Route path="settings" element={<Settings />} />
         </Routes>
       </main>
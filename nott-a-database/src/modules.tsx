@@ -27,6 +27,7 @@ import {
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import { useToast } from "./hooks/use-toast";
+import { errorMessage } from "@/lib/utils";
 
 type Module = {
   code: string;
@@ -80,10 +81,10 @@ function EditModule({
     } catch (e) {
       toast({
         title: "Error",
-        description: `${e}`,
+        description: errorMessage(e),
         variant: "destructive",
       });
-      log.error(`Error updating module ${data.code}: ${e}`);
+      log.error(`Error updating module ${data.code}: ${errorMessage(e)}`);
     }
   }
 
@@ -146,7 +147,7 @@ function ModuleRows() {
         log.debug(`Module Data: ${JSON.stringify(modules)}`);
         setModules(modules);
       } catch (e) {
-        log.error(`Error fetching module data: ${e}`);
+        log.error(`Error fetching module data: ${errorMessage(e)}`);
       }
     }
 
@@ -13,6 +13,7 @@ import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 
 import { useToast } from "@/hooks/use-toast";
+import { errorMessage } from "@/lib/utils";
 
 import * as log from "@tauri-apps/plugin-log";
 import { invoke } from "@tauri-apps/api/core";
@@ -113,6 +114,131 @@ function ChangePassword() {
   );
 }
 
+function StatusPalette() {
+  const [palette, setPalette] = useState<string>("");
+  const { toast } = useToast();
+
+  useEffect(() => {
+    invoke<string>("get_status_palette").then(setPalette);
+  }, []);
+
+  async function handleSave(event: FormEvent<HTMLFormElement>) {
+    event.preventDefault();
+    log.info("Updating status palette");
+    log.debug(`New status palette: ${palette}`);
+    try {
+      const saved: string = await invoke("set_status_palette", {
+        toml: palette,
+      });
+      setPalette(saved);
+      toast({
+        title: "Success",
+        description: "Successfully updated the status palette.",
+        variant: "default",
+      });
+      log.info("Done updating status palette");
+    } catch (e) {
+      toast({
+        title: "Error",
+        description: `Error updating status palette: ${errorMessage(e)}`,
+        variant: "destructive",
+      });
+      log.error(`Error updating status palette: ${errorMessage(e)}`);
+    }
+  }
+
+  async function handleReset() {
+    log.info("Resetting status palette");
+    try {
+      const saved: string = await invoke("reset_status_palette");
+      setPalette(saved);
+      toast({
+        title: "Success",
+        description: "Successfully reset the status palette.",
+        variant: "default",
+      });
+    } catch (e) {
+      toast({
+        title: "Error",
+        description: `Error resetting status palette: ${errorMessage(e)}`,
+        variant: "destructive",
+      });
+      log.error(`Error resetting status palette: ${errorMessage(e)}`);
+    }
+  }
+
+  return (
+    <article>
+      <Card>
+        <CardHeader>
+          <CardTitle>Status Palette</CardTitle>
+          <CardDescription>
+            The fill colours (hexadecimal RGB) used for each module status in
+            the raw data, fill colours within the tolerance of a colour are
+            given its status.
+          </CardDescription>
+        </CardHeader>
+        <CardContent>
+          <form id="status-palette" onSubmit={handleSave}>
+            <Label htmlFor="palette">Palette (TOML)</Label>
+            <textarea
+              id="palette"
+              name="palette"
+              rows={8}
+              spellCheck={false}
+              className="flex w-full rounded-md border border-input bg-transparent px-3 py-1 font-mono text-sm shadow-sm focus-visible:outline-none focus-visible:ring-1 focus-visible:ring-ring"
+              value={palette}
+              onChange={(e) => {
+                setPalette(e.target.value);
+              }}
+            />
+          </form>
+        </CardContent>
+        <CardFooter className="gap-2">
+          <Button type="submit" form="status-palette" className="w-full">
+            Save Palette
+          </Button>
+          <Button variant="outline" className="w-full" onClick={handleReset}>
+            Reset to Default
+          </Button>
+        </CardFooter>
+      </Card>
+    </article>
+  );
+}
+
+function ReportLayouts() {
+  const [layoutsDir, setLayoutsDir] = useState<string>("");
+
+  useEffect(() => {
+    invoke<string>("get_layouts_dir").then(setLayoutsDir);
+  }, []);
+
+  return (
+    <article>
+      <Card>
+        <CardHeader>
+          <CardTitle>Report Layouts</CardTitle>
+          <CardDescription>
+            The column names of each type of report are read from layout files
+            (TOML), a layout file in the layouts directory replaces the
+            built-in layout of the same type of report.
+          </CardDescription>
+        </CardHeader>
+        <CardContent>
+          <Label htmlFor="layouts-dir">Layouts Directory</Label>
+          <Input
+            id="layouts-dir"
+            className="font-mono"
+            value={layoutsDir}
+            readOnly
+          />
+        </CardContent>
+      </Card>
+    </article>
+  );
+}
+
 export default function Settings() {
   return (
     <article>
@@ -121,6 +247,8 @@ export default function Settings() {
         Manage database settings and preferences.
       </p>
       <ChangePassword />
+      <StatusPalette />
+      <ReportLayouts />
     </article>
   );
 }
@@ -27,18 +27,49 @@ import {
   TableRow,
 } from "@/components/ui/table";
 import { Button } from "@/components/ui/button";
+import { errorMessage } from "@/lib/utils";
 
 import { StudentInfo as Student } from "./students";
 
+export type MarkAttempt = {
+  id: number;
+  academicYear: string;
+  module: string;
+  attempt: number;
+  session: string;
+  mark?: number;
+  markCode?: string;
+  capped: boolean;
+  status?: string;
+};
+
+export type FailedComponent = {
+  id: number;
+  academicYear: string;
+  module: string;
+  attempt: number;
+  component: string;
+  weight: number;
+  mark?: number;
+  markCode?: string;
+};
+
 export type Mark = {
   id: number;
-  mark: number;
+  academicYear: string;
+  mark?: number;
+  markCode?: string;
   fill?: number;
-  retake1?: number;
-  retake2?: number;
   extra?: string;
   module: string;
   status: string;
+  attempts: MarkAttempt[];
+  failedComponents: FailedComponent[];
+};
+
+export type YearMarks = {
+  academicYear: string;
+  marks: Mark[];
 };
 
 export type StudentResult = {
@@ -47,6 +78,8 @@ export type StudentResult = {
   yearOfStudy: number;
   autumnCredits?: number;
   autumnMean?: number;
+  summerCredits?: number;
+  summerMean?: number;
   springCredits?: number;
   springMean?: number;
   yearCredits?: number;
@@ -63,20 +96,20 @@ export async function fetchStudent(id: number): Promise<Student> {
     log.info("Done fetching info");
     return student;
   } catch (e) {
-    log.error(`Error fetching info for ${id}: ${e}`);
+    log.error(`Error fetching info for ${id}: ${errorMessage(e)}`);
     throw e;
   }
 }
 
-export async function fetchMarks(id: number): Promise<Mark[]> {
+export async function fetchMarks(id: number): Promise<YearMarks[]> {
   log.info(`Fetching marks for ${id}`);
   try {
-    const marks = (await invoke("get_marks", { id })) as Mark[];
+    const marks = (await invoke("get_marks", { id })) as YearMarks[];
     log.debug(`Marks for ${id}: ${JSON.stringify(marks)}`);
     log.info("Done fetching marks");
     return marks;
   } catch (e) {
-    log.error(`Error fetching marks for ${id}: ${e}`);
+    log.error(`Error fetching marks for ${id}: ${errorMessage(e)}`);
     throw e;
   }
 }
@@ -89,7 +122,7 @@ export async function fetchResults(id: number): Promise<StudentResult[]> {
     log.info("Done fetching results");
     return results;
   } catch (e) {
-    log.error(`Error fetching results for ${id}: ${e}`);
+    log.error(`Error fetching results for ${id}: ${errorMessage(e)}`);
     throw e;
   }
 }
@@ -177,7 +210,7 @@ function GeneralInfo({ info }: { info: Promise<Student> }) {
         </TableRow>
         <TableRow>
           <TableHead>Intake Year</TableHead>
-          <TableCell>{infoData.intakeYear}</TableCell>
+          <TableCell>{infoData.intakeYear ?? "Unknown"}</TableCell>
         </TableRow>
         <TableRow>
           <TableHead>Graduation Year</TableHead>
@@ -199,7 +232,11 @@ export const marksColumns: ColumnDef<Mark>[] = [
   {
     accessorKey: "mark",
     header: "Initial Mark",
-    cell: ({ row }) => <div className="capitalize">{row.getValue("mark")}</div>,
+    cell: ({ row }) => (
+      <div className="capitalize">
+        {row.getValue("mark") ?? row.original.markCode}
+      </div>
+    ),
   },
   {
     accessorKey: "status",
@@ -209,23 +246,56 @@ export const marksColumns: ColumnDef<Mark>[] = [
     ),
   },
   {
-    accessorKey: "retake1",
-    header: "First Retake",
+    id: "resits",
+    header: "Resits",
     cell: ({ row }) => (
-      <div className="capitalize">{row.getValue("retake1")}</div>
+      <div className="capitalize">
+        {row.original.attempts
+          .slice(1)
+          .map(
+            (attempt) =>
+              `${attempt.mark ?? attempt.markCode} (${attempt.session}${attempt.capped ? ", capped" : ""})`,
+          )
+          .join(", ")}
+      </div>
     ),
   },
   {
-    accessorKey: "retake2",
-    header: "Second Retake",
+    id: "failedComponents",
+    header: "Failed Components",
     cell: ({ row }) => (
-      <div className="capitalize">{row.getValue("retake2")}</div>
+      <div className="capitalize">
+        {row.original.failedComponents
+          .map(
+            (component) =>
+              `${component.component} ${component.mark ?? component.markCode} (${component.weight}%)`,
+          )
+          .join(", ")}
+      </div>
     ),
   },
 ];
 
-function Marks({ marks }: { marks: Promise<Mark[]> }) {
-  const data = use(marks);
+function Marks({ marks }: { marks: Promise<YearMarks[]> }) {
+  const years = use(marks);
+
+  if (!years.length) {
+    return <MarksTable data={[]} />;
+  }
+
+  return (
+    <>
+      {years.map((year) => (
+        <div key={year.academicYear}>
+          <h3 className="font-semibold">{year.academicYear}</h3>
+          <MarksTable data={year.marks} />
+        </div>
+      ))}
+    </>
+  );
+}
+
+function MarksTable({ data }: { data: Mark[] }) {
   const [sorting, setSorting] = useState<SortingState>([]);
   const [columnFilters, setColumnFilters] = useState<ColumnFiltersState>([]);
   const [columnVisibility, setColumnVisibility] = useState<VisibilityState>({});
@@ -362,6 +432,13 @@ export const resultsColumns: ColumnDef<StudentResult>[] = [
       <div className="capitalize">{row.getValue("springMean")}</div>
     ),
   },
+  {
+    accessorKey: "summerMean",
+    header: "Mean Marks (Summer)",
+    cell: ({ row }) => (
+      <div className="capitalize">{row.getValue("summerMean")}</div>
+    ),
+  },
   {
     accessorKey: "yearMean",
     header: "Mean Marks (Year)",
@@ -383,6 +460,13 @@ export const resultsColumns: ColumnDef<StudentResult>[] = [
       <div className="capitalize">{row.getValue("springCredits")}</div>
     ),
   },
+  {
+    accessorKey: "summerCredits",
+    header: "Credits Marks (Summer)",
+    cell: ({ row }) => (
+      <div className="capitalize">{row.getValue("summerCredits")}</div>
+    ),
+  },
   {
     accessorKey: "yearCredits",
     header: "Credits Marks (Year)",
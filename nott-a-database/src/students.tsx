@@ -29,6 +29,7 @@ import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import * as log from "@tauri-apps/plugin-log";
 import { useNavigate } from "react-router";
+import { errorMessage } from "@/lib/utils";
 
 export type StudentInfo = {
   id: number;
@@ -51,7 +52,7 @@ export type StudentInfo = {
   selected?: number;
   exceptionData?: string;
   recommendation?: string;
-  intakeYear: string;
+  intakeYear?: string;
   graduationYear?: string;
 };
 
@@ -108,7 +109,9 @@ export const columns: ColumnDef<StudentInfo>[] = [
         </Button>
       );
     },
-    cell: ({ row }) => <div>{row.getValue("intakeYear")}</div>,
+    cell: ({ row }) => (
+      <div>{(row.getValue("intakeYear") as string | null) ?? "Unknown"}</div>
+    ),
   },
 ];
 
@@ -122,7 +125,7 @@ export async function fetchStudents(): Promise<StudentInfo[]> {
     log.debug(`Students: ${JSON.stringify(students)}`);
     return students;
   } catch (e) {
-    log.error(`Error fetching module data: ${e}`);
+    log.error(`Error fetching module data: ${errorMessage(e)}`);
     throw e;
   }
 }
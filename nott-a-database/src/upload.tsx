@@ -22,21 +22,275 @@ import {
   CardHeader,
   CardTitle,
 } from "@/components/ui/card";
+import {
+  Table,
+  TableBody,
+  TableCell,
+  TableHead,
+  TableHeader,
+  TableRow,
+} from "@/components/ui/table";
 import { useToast } from "@/hooks/use-toast";
+import { errorMessage } from "@/lib/utils";
 import * as log from "@tauri-apps/plugin-log";
 import { invoke } from "@tauri-apps/api/core";
 
+interface RawValue {
+  type: string;
+  value?: string | number | boolean;
+}
+
+interface RowDiagnostic {
+  sheet: string;
+  cell: string;
+  row: number;
+  header: string;
+  expected: string;
+  value: RawValue;
+  message: string;
+}
+
+interface CreditConflict {
+  module: string;
+  academicYear: string;
+  stored: number;
+  incoming: number;
+}
+
+interface FieldChange {
+  id: number;
+  field: string;
+  old: string | null;
+  new: string | null;
+}
+
+interface IntakeConflict {
+  id: number;
+  stored: string | null;
+  inferred: string;
+}
+
+interface DuplicateImport {
+  batch: number;
+  fileName: string;
+  importedAt: string;
+  academicYear: string;
+}
+
+interface InsertSummary {
+  batch: number | null;
+  kind: string;
+  rows: number;
+  diagnostics: RowDiagnostic[];
+  creditConflicts: CreditConflict[];
+  changes: FieldChange[];
+  intakeConflicts: IntakeConflict[];
+  unknownIntakes: number[];
+  duplicate: DuplicateImport | null;
+}
+
+interface Problem extends RowDiagnostic {
+  file: string;
+}
+
+interface Conflict extends CreditConflict {
+  file: string;
+}
+
+interface Change extends FieldChange {
+  file: string;
+}
+
+interface Intake extends IntakeConflict {
+  file: string;
+}
+
+function formatValue(value: RawValue): string {
+  if (value.type === "empty") {
+    return "(empty)";
+  }
+  return `${value.value} (${value.type})`;
+}
+
+function ProblemsTable({ problems }: { problems: Problem[] }) {
+  return (
+    <Card className="mx-auto mt-4 w-full">
+      <CardHeader>
+        <CardTitle>Skipped Rows</CardTitle>
+        <CardDescription>
+          {`Found ${problems.length} invalid cells, the rows containing them were not uploaded.`}
+        </CardDescription>
+      </CardHeader>
+      <CardContent>
+        <div className="rounded-md border">
+          <Table className="w-full">
+            <TableHeader>
+              <TableRow>
+                <TableHead>File</TableHead>
+                <TableHead>Cell</TableHead>
+                <TableHead>Header</TableHead>
+                <TableHead>Expected</TableHead>
+                <TableHead>Found</TableHead>
+                <TableHead>Problem</TableHead>
+              </TableRow>
+            </TableHeader>
+            <TableBody>
+              {problems.map((problem, idx) => (
+                <TableRow key={idx}>
+                  <TableCell>{problem.file}</TableCell>
+                  <TableCell>{`${problem.sheet}!${problem.cell}`}</TableCell>
+                  <TableCell>{problem.header}</TableCell>
+                  <TableCell>{problem.expected}</TableCell>
+                  <TableCell className="whitespace-pre-wrap">
+                    {formatValue(problem.value)}
+                  </TableCell>
+                  <TableCell>{problem.message}</TableCell>
+                </TableRow>
+              ))}
+            </TableBody>
+          </Table>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
+
+function ConflictsTable({
+  conflicts,
+  policy,
+}: {
+  conflicts: Conflict[];
+  policy: string;
+}) {
+  return (
+    <Card className="mx-auto mt-4 w-full">
+      <CardHeader>
+        <CardTitle>Module Credit Conflicts</CardTitle>
+        <CardDescription>
+          {`Found ${conflicts.length} modules with different credits from the saved credits, the saved credits were ${policy === "overwrite" ? "overwritten" : "kept"}.`}
+        </CardDescription>
+      </CardHeader>
+      <CardContent>
+        <div className="rounded-md border">
+          <Table className="w-full">
+            <TableHeader>
+              <TableRow>
+                <TableHead>File</TableHead>
+                <TableHead>Module</TableHead>
+                <TableHead>Saved Credits</TableHead>
+                <TableHead>Found Credits</TableHead>
+              </TableRow>
+            </TableHeader>
+            <TableBody>
+              {conflicts.map((conflict, idx) => (
+                <TableRow key={idx}>
+                  <TableCell>{conflict.file}</TableCell>
+                  <TableCell>{conflict.module}</TableCell>
+                  <TableCell>{conflict.stored}</TableCell>
+                  <TableCell>{conflict.incoming}</TableCell>
+                </TableRow>
+              ))}
+            </TableBody>
+          </Table>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
+
+function ChangesTable({ changes }: { changes: Change[] }) {
+  return (
+    <Card className="mx-auto mt-4 w-full">
+      <CardHeader>
+        <CardTitle>Updated Students</CardTitle>
+        <CardDescription>
+          {`Changed ${changes.length} fields of saved students.`}
+        </CardDescription>
+      </CardHeader>
+      <CardContent>
+        <div className="rounded-md border">
+          <Table className="w-full">
+            <TableHeader>
+              <TableRow>
+                <TableHead>File</TableHead>
+                <TableHead>Student ID</TableHead>
+                <TableHead>Field</TableHead>
+                <TableHead>Saved Value</TableHead>
+                <TableHead>New Value</TableHead>
+              </TableRow>
+            </TableHeader>
+            <TableBody>
+              {changes.map((change, idx) => (
+                <TableRow key={idx}>
+                  <TableCell>{change.file}</TableCell>
+                  <TableCell>{change.id}</TableCell>
+                  <TableCell>{change.field}</TableCell>
+                  <TableCell>{change.old ?? "(empty)"}</TableCell>
+                  <TableCell>{change.new ?? "(empty)"}</TableCell>
+                </TableRow>
+              ))}
+            </TableBody>
+          </Table>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
+
+function IntakesTable({ intakes }: { intakes: Intake[] }) {
+  return (
+    <Card className="mx-auto mt-4 w-full">
+      <CardHeader>
+        <CardTitle>Intake Year Conflicts</CardTitle>
+        <CardDescription>
+          {`Found ${intakes.length} saved students with a different intake year, the saved intake years were kept. Use Repair Intake Years in the Imports page to update them.`}
+        </CardDescription>
+      </CardHeader>
+      <CardContent>
+        <div className="rounded-md border">
+          <Table className="w-full">
+            <TableHeader>
+              <TableRow>
+                <TableHead>File</TableHead>
+                <TableHead>Student ID</TableHead>
+                <TableHead>Saved Intake Year</TableHead>
+                <TableHead>Found Intake Year</TableHead>
+              </TableRow>
+            </TableHeader>
+            <TableBody>
+              {intakes.map((intake, idx) => (
+                <TableRow key={idx}>
+                  <TableCell>{intake.file}</TableCell>
+                  <TableCell>{intake.id}</TableCell>
+                  <TableCell>{intake.stored ?? "(unknown)"}</TableCell>
+                  <TableCell>{intake.inferred}</TableCell>
+                </TableRow>
+              ))}
+            </TableBody>
+          </Table>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
+
 export default function UploadPage() {
-  const [type, setType] = useState<string | null>(null);
+  const [type, setType] = useState<string>("auto");
   const [year, setYear] = useState<string | null>(null);
-  const [file, setFile] = useState<string | null>(null);
+  const [files, setFiles] = useState<string[]>([]);
+  const [lenient, setLenient] = useState<boolean>(false);
+  const [creditPolicy, setCreditPolicy] = useState<string>("keep");
+  const [conflictPolicy, setConflictPolicy] = useState<string>("overwrite");
+  const [skipDuplicates, setSkipDuplicates] = useState<boolean>(false);
+  const [problems, setProblems] = useState<Problem[]>([]);
+  const [conflicts, setConflicts] = useState<Conflict[]>([]);
+  const [changes, setChanges] = useState<Change[]>([]);
+  const [intakes, setIntakes] = useState<Intake[]>([]);
   const { toast } = useToast();
 
   function handleTypeChange(type: string) {
     setType(type);
-    if (type !== null) {
-      log.info(`Type: ${type} selected for data`);
-    }
+    log.info(`Type: ${type} selected for data`);
   }
 
   function handleYearChange(event: ChangeEvent) {
@@ -56,10 +310,10 @@ export default function UploadPage() {
     }
   }
 
-  function handleFileChange(fileName: string | null) {
-    setFile(fileName);
+  function handleFileChange(fileNames: string[]) {
+    setFiles(fileNames);
 
-    if (fileName !== null) {
+    for (const fileName of fileNames) {
       log.info(`File: ${fileName} selected for upload`);
     }
   }
@@ -67,16 +321,6 @@ export default function UploadPage() {
   async function handleSubmit(e: React.FormEvent<HTMLFormElement>) {
     e.preventDefault();
 
-    if (!type) {
-      log.error("Tried to upload without data type");
-      toast({
-        title: "Error",
-        description: "Please select the type of data.",
-        variant: "destructive",
-      });
-      return;
-    }
-
     if (!year) {
       log.error("Tried to upload without academic year");
       toast({
@@ -122,7 +366,7 @@ export default function UploadPage() {
       }
     }
 
-    if (!file) {
+    if (files.length === 0) {
       log.error("Tried to upload without a file");
       toast({
         title: "Error",
@@ -132,80 +376,227 @@ export default function UploadPage() {
       return;
     }
 
-    try {
-      log.info("Uploading Data...");
-      log.debug(
-        `Form Data\nType: ${type}\nAcademic Year: ${year}\nFile: ${file}`,
-      );
-      await invoke("insert_data", {
-        dataType: type,
-        academicYear: year,
-        path: file,
-      });
-      log.info("Successfully Uploaded Data");
-      toast({
-        title: "Success",
-        description: "Successfully uploaded data",
-      });
-    } catch (error) {
-      log.error(`${error}`);
-      toast({
-        title: "Error",
-        description: `An error has occured: ${error}`,
-        variant: "destructive",
-      });
+    const newProblems: Problem[] = [];
+    const newConflicts: Conflict[] = [];
+    const newChanges: Change[] = [];
+    const newIntakes: Intake[] = [];
+    for (const file of files) {
+      try {
+        log.info(`Uploading Data from ${file}...`);
+        log.debug(
+          `Form Data\nType: ${type}\nAcademic Year: ${year}\nFile: ${file}\nLenient: ${lenient}\nCredit Policy: ${creditPolicy}\nConflict Policy: ${conflictPolicy}\nSkip Duplicates: ${skipDuplicates}`,
+        );
+        const summary: InsertSummary = await invoke("insert_data", {
+          dataType: type === "auto" ? null : type,
+          academicYear: year,
+          path: file,
+          lenient: lenient,
+          creditPolicy: creditPolicy,
+          conflictPolicy: conflictPolicy,
+          skipDuplicates: skipDuplicates,
+        });
+        if (summary.duplicate !== null) {
+          const duplicate = summary.duplicate;
+          const description = `${file} was already imported on ${duplicate.importedAt} for ${duplicate.academicYear} (${duplicate.fileName})`;
+          log.warn(description);
+          toast({
+            title: summary.batch === null ? "Skipped" : "Warning",
+            description: description,
+          });
+          if (summary.batch === null) {
+            continue;
+          }
+        }
+        log.info(
+          `Successfully Uploaded ${summary.rows} rows from ${file} as ${summary.kind}`,
+        );
+        for (const diagnostic of summary.diagnostics) {
+          log.warn(
+            `Skipped ${diagnostic.sheet}!${diagnostic.cell} in ${file}: ${diagnostic.message}`,
+          );
+          newProblems.push({ file: file, ...diagnostic });
+        }
+        for (const conflict of summary.creditConflicts) {
+          log.warn(
+            `Credit conflict of ${conflict.module} in ${file}: ${conflict.incoming} credits found, ${conflict.stored} credits saved`,
+          );
+          newConflicts.push({ file: file, ...conflict });
+        }
+        for (const change of summary.changes) {
+          log.info(
+            `${change.field} of student ${change.id} changed by ${file}: ${change.old ?? "(empty)"} to ${change.new ?? "(empty)"}`,
+          );
+          newChanges.push({ file: file, ...change });
+        }
+        for (const intake of summary.intakeConflicts) {
+          log.warn(
+            `Intake year conflict of student ${intake.id} in ${file}: ${intake.inferred} found, ${intake.stored ?? "(unknown)"} saved`,
+          );
+          newIntakes.push({ file: file, ...intake });
+        }
+        if (summary.unknownIntakes.length > 0) {
+          log.warn(
+            `Unable to infer the intake year of ${summary.unknownIntakes.length} new students in ${file}: ${summary.unknownIntakes.join(", ")}`,
+          );
+        }
+        toast({
+          title: "Success",
+          description:
+            summary.diagnostics.length === 0
+              ? `Successfully uploaded ${file} (${summary.kind})`
+              : `Uploaded ${file} (${summary.kind}) with ${summary.diagnostics.length} invalid cells skipped`,
+        });
+      } catch (error) {
+        log.error(errorMessage(error));
+        toast({
+          title: "Error",
+          description: `An error has occured in ${file}: ${errorMessage(error)}`,
+          variant: "destructive",
+        });
+      }
     }
+    setProblems(newProblems);
+    setConflicts(newConflicts);
+    setChanges(newChanges);
+    setIntakes(newIntakes);
   }
 
   return (
-    <Card className="mx-auto w-full max-w-md">
-      <CardHeader>
-        <CardTitle>Upload Exam Results</CardTitle>
-        <CardDescription>
-          Upload a CSV file containing exam results
-        </CardDescription>
-      </CardHeader>
-      <form onSubmit={handleSubmit}>
-        <CardContent>
-          <div className="grid w-full items-center gap-4">
-            <div className="flex max-w-full flex-col space-y-1.5 overflow-auto">
-              <Label htmlFor="type">Data Type</Label>
-              <Select onValueChange={handleTypeChange} name="type">
-                <SelectTrigger>
-                  <SelectValue placeholder="Select a Data Type" />
-                </SelectTrigger>
-                <SelectContent>
-                  <SelectGroup>
-                    <SelectLabel>Data Type</SelectLabel>
-                    <SelectItem value="result">Result (0A)</SelectItem>
-                    <SelectItem value="award">Award (0B)</SelectItem>
-                    <SelectItem value="resit-may">May Resit (0C)</SelectItem>
-                    <SelectItem value="resit-aug">August Resit (0D)</SelectItem>
-                  </SelectGroup>
-                </SelectContent>
-              </Select>
+    <>
+      <Card className="mx-auto w-full max-w-md">
+        <CardHeader>
+          <CardTitle>Upload Exam Results</CardTitle>
+          <CardDescription>
+            Upload a CSV file containing exam results
+          </CardDescription>
+        </CardHeader>
+        <form onSubmit={handleSubmit}>
+          <CardContent>
+            <div className="grid w-full items-center gap-4">
+              <div className="flex max-w-full flex-col space-y-1.5 overflow-auto">
+                <Label htmlFor="type">Data Type</Label>
+                <Select
+                  onValueChange={handleTypeChange}
+                  defaultValue="auto"
+                  name="type"
+                >
+                  <SelectTrigger>
+                    <SelectValue placeholder="Select a Data Type" />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectGroup>
+                      <SelectLabel>Data Type</SelectLabel>
+                      <SelectItem value="auto">Auto Detect</SelectItem>
+                      <SelectItem value="result">Result (0A)</SelectItem>
+                      <SelectItem value="award">Award (0B)</SelectItem>
+                      <SelectItem value="resit-may">May Resit (0C)</SelectItem>
+                      <SelectItem value="resit-aug">August Resit (0D)</SelectItem>
+                    </SelectGroup>
+                  </SelectContent>
+                </Select>
+              </div>
+              <div className="flex max-w-full flex-col space-y-1.5 overflow-auto">
+                <Label htmlFor="year">Academic Year</Label>
+                <Input id="year" name="year" onChange={handleYearChange} />
+              </div>
+              <div className="flex max-w-full flex-col space-y-1.5 overflow-auto">
+                <Label htmlFor="file">XLSX Files</Label>
+                <FileInput
+                  name="file"
+                  id="file"
+                  accept=".xlsx"
+                  multiple
+                  clickFn={handleFileChange}
+                />
+              </div>
+              <div className="flex items-center space-x-2">
+                <input
+                  type="checkbox"
+                  id="lenient"
+                  name="lenient"
+                  checked={lenient}
+                  onChange={(e) => setLenient(e.target.checked)}
+                />
+                <Label htmlFor="lenient">Skip invalid rows</Label>
+              </div>
+              <div className="flex items-center space-x-2">
+                <input
+                  type="checkbox"
+                  id="skipDuplicates"
+                  name="skipDuplicates"
+                  checked={skipDuplicates}
+                  onChange={(e) => setSkipDuplicates(e.target.checked)}
+                />
+                <Label htmlFor="skipDuplicates">
+                  Skip files already uploaded
+                </Label>
+              </div>
+              <div className="flex max-w-full flex-col space-y-1.5 overflow-auto">
+                <Label htmlFor="creditPolicy">Module Credit Conflicts</Label>
+                <Select
+                  onValueChange={setCreditPolicy}
+                  defaultValue="keep"
+                  name="creditPolicy"
+                >
+                  <SelectTrigger>
+                    <SelectValue placeholder="Select a Resolution" />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectGroup>
+                      <SelectLabel>Module Credit Conflicts</SelectLabel>
+                      <SelectItem value="keep">Keep saved credits</SelectItem>
+                      <SelectItem value="overwrite">
+                        Overwrite saved credits
+                      </SelectItem>
+                      <SelectItem value="fail">Stop the upload</SelectItem>
+                    </SelectGroup>
+                  </SelectContent>
+                </Select>
+              </div>
+              <div className="flex max-w-full flex-col space-y-1.5 overflow-auto">
+                <Label htmlFor="conflictPolicy">Saved Student Details</Label>
+                <Select
+                  onValueChange={setConflictPolicy}
+                  defaultValue="overwrite"
+                  name="conflictPolicy"
+                >
+                  <SelectTrigger>
+                    <SelectValue placeholder="Select a Resolution" />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectGroup>
+                      <SelectLabel>Saved Student Details</SelectLabel>
+                      <SelectItem value="overwrite">
+                        Overwrite saved details
+                      </SelectItem>
+                      <SelectItem value="fill-missing">
+                        Fill missing details only
+                      </SelectItem>
+                      <SelectItem value="keep-existing">
+                        Keep saved details
+                      </SelectItem>
+                      <SelectItem value="fail">
+                        Stop the upload if details change
+                      </SelectItem>
+                    </SelectGroup>
+                  </SelectContent>
+                </Select>
+              </div>
             </div>
-            <div className="flex max-w-full flex-col space-y-1.5 overflow-auto">
-              <Label htmlFor="year">Academic Year</Label>
-              <Input id="year" name="year" onChange={handleYearChange} />
-            </div>
-            <div className="flex max-w-full flex-col space-y-1.5 overflow-auto">
-              <Label htmlFor="file">XLSX File</Label>
-              <FileInput
-                name="file"
-                id="file"
-                accept=".xlsx"
-                clickFn={handleFileChange}
-              />
-            </div>
-          </div>
-        </CardContent>
-        <CardFooter>
-          <Button type="submit" className="w-full">
-            Upload
-          </Button>
-        </CardFooter>
-      </form>
-    </Card>
+          </CardContent>
+          <CardFooter>
+            <Button type="submit" className="w-full">
+              Upload
+            </Button>
+          </CardFooter>
+        </form>
+      </Card>
+      {problems.length > 0 && <ProblemsTable problems={problems} />}
+      {conflicts.length > 0 && (
+        <ConflictsTable conflicts={conflicts} policy={creditPolicy} />
+      )}
+      {changes.length > 0 && <ChangesTable changes={changes} />}
+      {intakes.length > 0 && <IntakesTable intakes={intakes} />}
+    </>
   );
 }